@@ -0,0 +1,138 @@
+use cosmwasm_std::{coins, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128};
+use criterion::{criterion_group, criterion_main, Criterion};
+use cw721_base::msg::{ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, QueryMsg as Cw721QueryMsg};
+use cw721_base::Cw721Contract;
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use cw_auction::asset::{Asset, AssetInfo};
+use cw_auction::contract::{execute, instantiate, query, reply, sudo};
+use cw_auction::msg::{ExecuteMsg, GFMintMsg, InstantiateMsg};
+use cw_auction::state::{Collection, Metadata, Royalty};
+
+const OWNER: &str = "owner";
+const ARTIST: &str = "artist";
+const SELLER: &str = "seller";
+const BIDDERS: [&str; 2] = ["alice", "bob"];
+const DENOM: &str = "ujuno";
+
+// cw721-base with the auction metadata, reporting its instantiator as collection owner
+type NftContract<'a> = Cw721Contract<'a, Metadata, Empty>;
+const COLLECTION_OWNER: Item<Addr> = Item::new("collection_owner");
+
+fn nft_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |deps: DepsMut, env: Env, info: MessageInfo, msg: Cw721ExecuteMsg<Metadata>| NftContract::default().execute(deps, env, info, msg),
+        |deps: DepsMut, env: Env, info: MessageInfo, msg: Cw721InstantiateMsg| -> StdResult<Response> {
+            COLLECTION_OWNER.save(deps.storage, &info.sender)?;
+            NftContract::default().instantiate(deps, env, info, msg)
+        },
+        |deps: Deps, env: Env, msg: Cw721QueryMsg| -> StdResult<Binary> {
+            match msg {
+                Cw721QueryMsg::CollectionInfo {} => to_binary(&Collection {
+                    name: NftContract::default().contract_info.load(deps.storage)?.name,
+                    description: None,
+                    owner: COLLECTION_OWNER.load(deps.storage)?,
+                    logo_url: None,
+                    banner_url: None,
+                }),
+                msg => NftContract::default().query(deps, env, msg),
+            }
+        },
+    ))
+}
+
+fn attribute(res: &AppResponse, key: &str) -> String {
+    res.events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.clone())
+        .unwrap()
+}
+
+// an auction with a single native listing the bidders can outbid each other on
+fn setup() -> (App, Addr, String) {
+    let mut app = App::new(|router, _, storage| {
+        for bidder in BIDDERS {
+            router.bank.init_balance(storage, &Addr::unchecked(bidder), coins(u128::MAX / 4, DENOM)).unwrap();
+        }
+    });
+    let auction_code = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply).with_sudo(sudo)));
+    let nft_code = app.store_code(nft_contract());
+    let auction = app
+        .instantiate_contract(auction_code, Addr::unchecked(OWNER), &InstantiateMsg::default(), &[], "auction", None)
+        .unwrap();
+    let nft = app
+        .instantiate_contract(nft_code, Addr::unchecked(ARTIST), &Cw721InstantiateMsg {
+            name: String::from("glassflow"),
+            symbol: String::from("GF"),
+            minter: auction.to_string(),
+        }, &[], "nft", None)
+        .unwrap();
+
+    let owner = Addr::unchecked(OWNER);
+    app.execute_contract(owner.clone(), auction.clone(), &ExecuteMsg::UpdateMinter { minter: String::from(ARTIST), expires: None }, &[]).unwrap();
+    app.execute_contract(owner, auction.clone(), &ExecuteMsg::UpdateAcceptedDenom {
+        denom: String::from(DENOM),
+        display: String::from("JUNO"),
+        decimals: 6,
+        min_bid_unit: Uint128::new(1),
+        tick_size: None,
+    }, &[]).unwrap();
+
+    let res = app.execute_contract(Addr::unchecked(ARTIST), auction.clone(), &ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("first nft"),
+        image_uri: Some(String::from("https://glassflow.art/1.png")),
+        external_link: None,
+        description: Some(String::from("first nft")),
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![Royalty { address: String::from(ARTIST), royalty_rate: Decimal::percent(10) }],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap();
+    let token_id = attribute(&res, "token_id");
+
+    let seller = Addr::unchecked(SELLER);
+    app.execute_contract(seller.clone(), nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = app.execute_contract(seller, auction.clone(), &ExecuteMsg::PlaceListing {
+        id: token_id,
+        minimum_bid: Asset { info: AssetInfo::NativeToken { denom: String::from(DENOM) }, amount: Uint128::new(100) },
+        nft_addr: nft.to_string(),
+        escrowless: None,
+        template: None,
+    }, &[]).unwrap();
+
+    (app, auction, attribute(&res, "listing_id"))
+}
+
+// every bid outbids the previous one, refunding its bidder, like a contested auction
+fn bid_listing(c: &mut Criterion) {
+    let (mut app, auction, listing_id) = setup();
+    let mut amount = 100u128;
+    c.bench_function("bid_listing", |b| {
+        b.iter(|| {
+            amount += 1;
+            let bidder = BIDDERS[(amount % 2) as usize];
+            app.execute_contract(Addr::unchecked(bidder), auction.clone(), &ExecuteMsg::BidListing {
+                listing_id: listing_id.clone(),
+                bid_price: Asset { info: AssetInfo::NativeToken { denom: String::from(DENOM) }, amount: Uint128::new(amount) },
+                on_behalf_of: None,
+            }, &coins(amount, DENOM)).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bid_listing);
+criterion_main!(benches);
@@ -1,8 +1,11 @@
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 use std::env::current_dir;
 use std::fs::create_dir_all;
+use cosmwasm_std::Addr;
 
-use cw_auction::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ResolveListingResponse, GFMintMsg, Cw20HookMsg};
+use cw_auction::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, SudoMsg, QueryMsg, ResolveListingResponse, GFMintMsg, Cw20HookMsg, FloorPriceResponse, TopBidsResponse, WatchlistResponse, EscrowResponse, AcceptedDenomsResponse,
+    SettlementPreviewResponse, SellerStatsResponse, ProvenanceResponse, PointsResponse, AllPointsResponse, ListingsByCollectionResponse, ListingsByTagResponse, FeaturedListingsResponse, CreatorOverviewResponse, MinterInfoResponse, MinterApplicationsResponse, TokenFullInfoResponse, DeadLettersResponse, ConfigHistoryResponse, AccruedRoyaltiesResponse, ExportResponse, SettlementPacket, LedgerResponse, SalesResponse, ConvertAmountResponse, VerifyEscrowResponse, MintersResponse, FeaturesResponse, TokenIdFormatResponse};
+use cw_auction::state::{Config, SettlementReceipt, Offer, FeeOverride, CollectionLevy, Cw721Variant, SecurityDeposit, ListingTemplate, NftInfo, Metadata, Raffle, QuarantinedNft, CustodiedNft, ProvenanceCommitment, IncrementStep, AbsenteeBid};
 use cw_auction::asset::{Asset};
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -13,8 +16,63 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(ResolveListingResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
     export_schema(&schema_for!(GFMintMsg), &out_dir);
     export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema(&schema_for!(Asset), &out_dir);
+    export_schema(&schema_for!(SettlementPacket), &out_dir);
+
+    // query responses
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(ResolveListingResponse), &out_dir);
+    export_schema(&schema_for!(MintersResponse), &out_dir);
+    export_schema_with_title(&schema_for!(NftInfo<Metadata>), &out_dir, "NftInfoResponse");
+    export_schema_with_title(&schema_for!(Vec<NftInfo<Metadata>>), &out_dir, "TokensByOwnerResponse");
+    export_schema_with_title(&schema_for!(Vec<String>), &out_dir, "AllTokensResponse");
+    export_schema_with_title(&schema_for!(Vec<String>), &out_dir, "AllAuctionIdsResponse");
+    export_schema(&schema_for!(FloorPriceResponse), &out_dir);
+    export_schema(&schema_for!(TopBidsResponse), &out_dir);
+    export_schema(&schema_for!(WatchlistResponse), &out_dir);
+    export_schema(&schema_for!(EscrowResponse), &out_dir);
+    export_schema(&schema_for!(AccruedRoyaltiesResponse), &out_dir);
+    export_schema(&schema_for!(DeadLettersResponse), &out_dir);
+    export_schema(&schema_for!(SalesResponse), &out_dir);
+    export_schema(&schema_for!(ConvertAmountResponse), &out_dir);
+    export_schema(&schema_for!(VerifyEscrowResponse), &out_dir);
+    export_schema(&schema_for!(FeaturesResponse), &out_dir);
+    export_schema(&schema_for!(TokenIdFormatResponse), &out_dir);
+    export_schema(&schema_for!(ConfigHistoryResponse), &out_dir);
+    export_schema(&schema_for!(ExportResponse), &out_dir);
+    export_schema(&schema_for!(LedgerResponse), &out_dir);
+    export_schema(&schema_for!(SettlementReceipt), &out_dir);
+    export_schema(&schema_for!(AcceptedDenomsResponse), &out_dir);
+    export_schema(&schema_for!(SettlementPreviewResponse), &out_dir);
+    export_schema(&schema_for!(SellerStatsResponse), &out_dir);
+    export_schema_with_title(&schema_for!(Option<FeeOverride>), &out_dir, "CollectionFeeOverrideResponse");
+    export_schema_with_title(&schema_for!(Option<CollectionLevy>), &out_dir, "CollectionLevyResponse");
+    export_schema_with_title(&schema_for!(Cw721Variant), &out_dir, "CollectionVariantResponse");
+    export_schema_with_title(&schema_for!(bool), &out_dir, "CharityResponse");
+    export_schema_with_title(&schema_for!(Option<SecurityDeposit>), &out_dir, "SecurityDepositResponse");
+    export_schema_with_title(&schema_for!(Option<QuarantinedNft>), &out_dir, "QuarantinedResponse");
+    export_schema_with_title(&schema_for!(Option<CustodiedNft>), &out_dir, "CustodyResponse");
+    export_schema_with_title(&schema_for!(Option<ProvenanceCommitment>), &out_dir, "CollectionProvenanceResponse");
+    export_schema_with_title(&schema_for!(Option<Vec<IncrementStep>>), &out_dir, "IncrementScheduleResponse");
+    export_schema_with_title(&schema_for!(Vec<AbsenteeBid>), &out_dir, "AbsenteeBidsResponse");
+    export_schema_with_title(&schema_for!(Option<Addr>), &out_dir, "ListingOperatorResponse");
+    export_schema(&schema_for!(ProvenanceResponse), &out_dir);
+    export_schema(&schema_for!(PointsResponse), &out_dir);
+    export_schema(&schema_for!(AllPointsResponse), &out_dir);
+    export_schema(&schema_for!(ListingsByCollectionResponse), &out_dir);
+    export_schema(&schema_for!(ListingsByTagResponse), &out_dir);
+    export_schema(&schema_for!(FeaturedListingsResponse), &out_dir);
+    export_schema(&schema_for!(CreatorOverviewResponse), &out_dir);
+    export_schema(&schema_for!(MinterInfoResponse), &out_dir);
+    export_schema(&schema_for!(MinterApplicationsResponse), &out_dir);
+    export_schema(&schema_for!(TokenFullInfoResponse), &out_dir);
+    export_schema_with_title(&schema_for!(Option<ListingTemplate>), &out_dir, "ListingTemplateResponse");
+    export_schema_with_title(&schema_for!(Option<Offer>), &out_dir, "OfferResponse");
+    export_schema_with_title(&schema_for!(Option<Asset>), &out_dir, "AutoAcceptPriceResponse");
+    export_schema_with_title(&schema_for!(Option<Raffle>), &out_dir, "RaffleResponse");
+    export_schema_with_title(&schema_for!(u64), &out_dir, "ListingNonceResponse");
 }
@@ -1,10 +1,13 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, MessageInfo, StdError, StdResult,
+use cosmwasm_std::{to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, MessageInfo, StdError, StdResult,
   Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg};
 
+use crate::coin_helpers::may_pay;
+use crate::error::ContractError;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AssetInfo {
@@ -36,6 +39,22 @@ impl AssetInfo {
     }
   }
   
+  // native denoms follow the bank module's format, cw20 tokens need a valid contract address
+  pub fn validate(&self, api: &dyn Api) -> StdResult<()> {
+    match self {
+      AssetInfo::NativeToken { denom } => {
+        let valid = (3..=128).contains(&denom.len())
+          && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+          && denom.chars().all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+        if !valid {
+          return Err(StdError::generic_err(format!("invalid denom {}", denom)));
+        }
+        Ok(())
+      }
+      AssetInfo::Token { contract_addr } => api.addr_validate(contract_addr).map(|_| ()),
+    }
+  }
+
   pub fn equal(&self, asset: &AssetInfo) -> bool {
     match self {
       AssetInfo::Token { contract_addr, .. } => {
@@ -69,30 +88,36 @@ impl fmt::Display for Asset {
 }
 
 impl Asset{
-  pub fn assert_sent_native_token_balance(&self, message_info: &MessageInfo) -> StdResult<()> {
-    if let AssetInfo::NativeToken { denom } = &self.info {
-      match message_info.funds.iter().find(|x| x.denom == *denom) {
-        Some(coin) => {
-          if self.amount == coin.amount {
-            Ok(())
-          } else {
-            Err(StdError::generic_err("Native token balance mismatch between the argument and the transferred"))
-          }
-        }
-        None => {
-          if self.amount.is_zero() {
-              Ok(())
-          } else {
-            Err(StdError::generic_err("Native token balance mismatch between the argument and the transferred"))
-          }
-        }
-      }
-    } else {
-      Ok(())
+  // the funds of a message have to pay a native asset exactly, cw20 assets arrive through the
+  // receive hook and no native funds go along with them
+  pub fn assert_sent(&self, info: &MessageInfo) -> Result<(), ContractError> {
+    let paid = match &self.info {
+      AssetInfo::NativeToken { denom } => may_pay(info, denom)?,
+      AssetInfo::Token { .. } if info.funds.is_empty() => return Ok(()),
+      AssetInfo::Token { .. } => return Err(ContractError::UnexpectedFunds {}),
+    };
+    if paid != self.amount {
+      return Err(ContractError::FundsMismatch { expected: self.to_string() });
+    }
+    Ok(())
+  }
+
+  pub fn checked_add(&self, other: &Asset) -> StdResult<Asset> {
+    if self.info != other.info {
+      return Err(StdError::generic_err(format!("can't add {} to {}", other, self)));
+    }
+    Ok(Asset { info: self.info.clone(), amount: self.amount.checked_add(other.amount)? })
+  }
+
+  pub fn checked_sub(&self, other: &Asset) -> StdResult<Asset> {
+    if self.info != other.info {
+      return Err(StdError::generic_err(format!("can't subtract {} from {}", other, self)));
     }
+    Ok(Asset { info: self.info.clone(), amount: self.amount.checked_sub(other.amount)? })
   }
 
-  pub fn into_msg(self, recipient: Addr) -> StdResult<CosmosMsg> {
+  // a bank send for native assets, a cw20 transfer for tokens
+  pub fn into_transfer_msg(self, recipient: Addr) -> StdResult<CosmosMsg> {
     let amount = self.amount;
 
     match &self.info {
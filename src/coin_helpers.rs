@@ -0,0 +1,63 @@
+use cosmwasm_std::{Coin, MessageInfo, Uint128};
+
+use crate::asset::{Asset, AssetInfo};
+use crate::error::ContractError;
+
+// the only coin sent with a message
+pub fn one_coin(info: &MessageInfo) -> Result<Coin, ContractError> {
+    match info.funds.as_slice() {
+        [] => Err(ContractError::NoFunds {}),
+        [coin] if coin.amount.is_zero() => Err(ContractError::ZeroFunds { denom: coin.denom.clone() }),
+        [coin] => Ok(coin.clone()),
+        _ => Err(ContractError::MultipleDenoms {}),
+    }
+}
+
+// the amount of a denom sent as the only coin of a message
+pub fn must_pay(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    let coin = one_coin(info)?;
+    if coin.denom != denom {
+        return Err(ContractError::WrongDenom { denom: denom.to_string() });
+    }
+    Ok(coin.amount)
+}
+
+// like `must_pay`, a message without funds pays zero
+pub fn may_pay(info: &MessageInfo, denom: &str) -> Result<Uint128, ContractError> {
+    if info.funds.is_empty() {
+        return Ok(Uint128::zero());
+    }
+    must_pay(info, denom)
+}
+
+// the funds of a message have to pay all native assets, summed by denom, exactly and nothing else
+pub fn assert_paid_all(info: &MessageInfo, assets: &[Asset]) -> Result<(), ContractError> {
+    let mut expected: Vec<Coin> = vec![];
+    for asset in assets {
+        if let AssetInfo::NativeToken { denom } = &asset.info {
+            match expected.iter_mut().find(|coin| coin.denom == *denom) {
+                Some(coin) => coin.amount += asset.amount,
+                None => expected.push(Coin { denom: denom.clone(), amount: asset.amount }),
+            }
+        }
+    }
+    expected.retain(|coin| !coin.amount.is_zero());
+
+    match expected.as_slice() {
+        [] if info.funds.is_empty() => Ok(()),
+        [] => Err(ContractError::UnexpectedFunds {}),
+        [coin] => Asset {
+            info: AssetInfo::NativeToken { denom: coin.denom.clone() },
+            amount: coin.amount,
+        }.assert_sent(info),
+        _ => {
+            let matches = info.funds.len() == expected.len()
+                && expected.iter().all(|coin| info.funds.contains(coin));
+            if !matches {
+                let expected: Vec<String> = expected.iter().map(|coin| coin.to_string()).collect();
+                return Err(ContractError::FundsMismatch { expected: expected.join(",") });
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,71 +1,340 @@
 use cosmwasm_std::{
     entry_point, to_binary, from_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, WasmMsg, Decimal, QueryRequest, WasmQuery, Addr, Order
+    Response, StdError, StdResult, WasmMsg, Decimal, QueryRequest, WasmQuery, Addr, Order, Event, Uint128,
+    Reply, Storage, SubMsg, Timestamp, Coin, Empty, to_vec, BankMsg, coins
 };
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use ripemd160::Ripemd160;
+use bech32::{ToBase32, Variant};
 use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Map;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 
+use crate::coin_helpers::assert_paid_all;
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ResolveListingResponse, GFMintMsg, Cw20HookMsg};
-use crate::state::{store_config, read_config, store_minters, remove_minter, read_minters, read_minter_info, list_resolver, list_resolver_read, Config, Listing, MinterInfo, Metadata, read_auction_ids, NftInfo, Collection};
+use crate::ids::{next_id, seed_legacy_counters, LISTING_IDS, OFFER_IDS, TOKEN_IDS, COLLECTION_SERIALS, DEFAULT_TOKEN_ID_PREFIX,
+    MAX_TOKEN_ID_FORMAT_LEN, TokenIdPart, next_serial, parse_token_id_format, render_token_id};
+use crate::math::payout_share;
+use crate::pagination::{page_limit, Page, MAX_LIMIT};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, SudoMsg, QueryMsg, ResolveListingResponse, ListingStatus, GFMintMsg, Cw20HookMsg, FloorPrice, FloorPriceResponse, TopBidsResponse, WatchlistResponse, EscrowResponse, AcceptedDenomsResponse,
+    PayoutPreview, SettlementPreviewResponse, SellerStatsResponse, ComplianceQueryMsg, IsAttestedResponse,
+    ProvenanceResponse, PointsResponse, AllPointsResponse, PlaceListingParams, ListingIntent, BidIntent, RandomnessProviderMsg,
+    CollectionListing, ListingsByCollectionResponse, ListingsByTagResponse, MintersResponse, FeaturedListing, FeaturedListingsResponse, CreatorOverviewResponse, MinterInfoResponse, MinterApplicationsResponse, TokenFullInfoResponse, DeadLettersResponse, ConfigHistoryResponse, AccruedRoyaltiesResponse, LedgerResponse, ExportSection, ExportEntry, ExportResponse, Sale, SalesResponse,
+    OracleQueryMsg, ExchangeRateResponse, ConvertAmountResponse, VerifyEscrowResponse, FeaturesResponse, TokenIdFormatResponse, YieldVaultMsg, YieldVaultQueryMsg, YieldBalanceResponse};
+use crate::state::{read_config, store_config_change, read_config_history, store_minters, remove_minter, read_minters, read_expired_minters, read_minter_info, MinterAction, MinterAudit, MINTER_AUDIT, append_minter_audit, MinterApplication, MINTER_APPLICATIONS, read_minter_applications, LAUNCHPADS, INTENT_NONCES, DEAD_LETTERS, store_dead_letter, read_dead_letters, read_raw_entries, LIST_RESOLVER_KEY, list_resolver, list_resolver_read, load_listing, with_bid_state, save_bid_state, LISTING_BIDS, Config, Listing, MinterInfo, Metadata, read_auction_ids, NftInfo, Collection, store_floor_price, remove_floor_price, read_floor_prices, store_top_bid, remove_top_bid, read_top_bids, WatchTarget, WATCHERS, read_watcher_count, store_watch, remove_watch, read_watchlist, read_watchers, BID_APPROVALS, LISTING_OPERATORS,
+    Payout, PayoutStatus, SettlementReceipt, RECEIPTS, ReplyContext, REPLIES, next_reply_id, ESCROW, EscrowStatus, UNDELIVERABLE, store_escrow, remove_escrow, read_escrow_by_recipient,
+    DenomInfo, ACCEPTED_DENOMS, read_accepted_denoms,
+    FeeTier, read_seller_volume, add_seller_sale, read_seller_stats, FeeOverride, COLLECTION_FEE_OVERRIDES,
+    DepositConfig, SecurityDeposit, SECURITY_DEPOSITS, store_security_deposit, remove_security_deposit,
+    LedgerBucket, LEDGER, read_ledger, credit_ledger, debit_ledger, take_held_bids,
+    ProvenanceEvent, append_provenance, read_provenance, LocalizedString,
+    read_minted_by, has_been_sold, CREATOR_APPROVALS, add_token_sale, remove_listing, ROYALTY_LEDGER, accrue_royalty, read_accrued_royalties, CollectionLevy, COLLECTION_LEVIES, Cw721Variant, COLLECTION_VARIANTS, CollectionPause, COLLECTION_PAUSES, PriceFloor, COLLECTION_PRICE_FLOORS, COLLECTION_TOKEN_ID_FORMATS, IncrementStep, INCREMENT_SCHEDULES, AbsenteeBid, ABSENTEE_BIDS, read_absentee_bids, take_absentee_bids, has_absentee_bids, ProvenanceCommitment, COLLECTION_PROVENANCE_COMMITMENTS, read_mint_height, MembershipConfig, RateLimits, AntiSniping, RateCounter, LISTING_RATE, ACTIVE_LISTINGS, BID_RATE, bump_rate_counter,
+    POINTS, POINTS_RATES, add_points, read_points, read_all_points, ListingTemplate, LISTING_TEMPLATES, CHARITIES,
+    Offer, OFFERS, AUCTIONS, LISTING_TRANSFERS, AutoRelist, AUTO_RELISTS, RevealedReserve, REVEALED_RESERVES, YieldVault, YIELD_DEPOSITS, change_listing_seller, store_offer, remove_offer, read_token_offers, TOKEN_LISTINGS, AutoAccept, TOKEN_AUTO_ACCEPT, COLLECTION_AUTO_ACCEPT, read_auto_accept,
+    LISTING_ALIASES, LISTING_NONCES, canonical_listing_id, hashed_listing_id, CandleBid, hold_bid, drop_lowest_held_bid, held_bid_count, has_held_bids, migrate_held_bids,
+    Raffle, RAFFLES, TicketPurchase, RAFFLE_TICKETS, next_raffle_id, read_ticket_owner, store_listing, read_collection_listings, read_tag_listings, LISTINGS_BY_TAG, FEATURED_LISTINGS, read_featured_listings, read_seller_listings, read_creator_collections, CREATOR_COLLECTIONS, QuarantinedNft, QUARANTINE, CustodiedNft, CUSTODY, UnclaimedItem, UNCLAIMED_ITEMS, may_load_listing,
+    SALES_BY_HEIGHT, store_receipt, read_sales_between, seed_floor_assets, move_listing_end, sweep_listing_counts, seed_listing_counts, PENDING_ESCROW, seed_pending_escrow};
 use cw721::{
     Cw721ExecuteMsg::{TransferNft},
+    Cw721ReceiveMsg,
+    ContractInfoResponse,
     AllNftInfoResponse,
     TokensResponse,
-    NumTokensResponse
+    NumTokensResponse,
+    OwnerOfResponse,
+    Expiration,
+    OperatorsResponse
 };
 use crate::asset::{ Asset, AssetInfo };
+use crate::uri::validate_uri;
 
 use cw721_base::msg::{ ExecuteMsg as Cw721BaseExecuteMsg, MintMsg, QueryMsg as Cw721QueryMsg };
-pub const DEFAULT_EXPIRE_BLOCKS: u64 = 50_000;  // in seconds
+pub const DEFAULT_EXPIRE_BLOCKS: u64 = 50_000;  // in blocks
+pub const DEFAULT_APPROVAL_WINDOW_BLOCKS: u64 = 20_000;
+
+// gas a yield vault being left gets to return the deposits
+pub const YIELD_EXIT_GAS_LIMIT: u64 = 500_000;
+
+// bounds of the owner-set listing windows
+pub const MAX_AUCTION_DURATION_BLOCKS: u64 = 1_000_000;
+pub const MAX_APPROVAL_WINDOW_BLOCKS: u64 = 200_000;
+pub const MAX_SETTLEMENT_GRACE_BLOCKS: u64 = 200_000;
+pub const MAX_RATE_LIMIT_WINDOW_BLOCKS: u64 = 100_000;
+pub const MAX_ANTI_SNIPING_BLOCKS: u64 = 10_000;
+
+// config changes kept in the config history
+pub const MAX_CONFIG_HISTORY: u64 = 100;
+
+// highest collection levy, in percent of the price
+pub const MAX_LEVY_PERCENT: u64 = 25;
+pub const MAX_BUYER_PREMIUM_PERCENT: u64 = 25;
+pub const MAX_INCREMENT_STEPS: u32 = 20;
+pub const MAX_ABSENTEE_BIDS: u32 = 50;
+pub const MAX_HELD_BIDS: u64 = 50;
+
+// listing tag bounds
+pub const MAX_LISTING_TAGS: usize = 5;
+pub const MAX_TAG_LENGTH: usize = 32;
+
+// watchers of a listing or collection, all of them are named in its watch events
+pub const MAX_WATCHERS: u32 = 50;
+
+// failed deliveries after which an escrowed transfer can only be claimed
+pub const MAX_TRANSFER_ATTEMPTS: u32 = 3;
+
+// blocks a raffle waits for its winner after it ended or the draw was requested, anyone may void it then
+pub const RAFFLE_DRAW_TIMEOUT_BLOCKS: u64 = 10_000;
+
+// most rounds an auction below its reserve price is relisted for
+pub const MAX_AUTO_RELISTS: u32 = 10;
+
+// mint metadata bounds
+const MIN_NAME_LENGTH: usize = 1;
+const MAX_NAME_LENGTH: usize = 128;
+const MAX_DESCRIPTION_LENGTH: usize = 2048;
+
+// most items handled by a single bulk message
+pub const MAX_BATCH_SIZE: usize = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
-) -> Result<Response, StdError> {
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let owner = match &msg.owner {
+        Some(owner) => deps.api.addr_validate(owner)?,
+        None => info.sender.clone(),
+    };
+    assert_fee_config(deps.as_ref(), &msg.fee_tiers, &msg.fee_collector, msg.primary_fee_rate)?;
+
     let config_state = Config { 
         listing_count: 0,
-        owner: info.sender.to_string(),
+        owner: owner.to_string(),
         max_aution_duration_blocks: DEFAULT_EXPIRE_BLOCKS,
+        fee_tiers: msg.fee_tiers,
+        fee_collector: msg.fee_collector,
+        primary_fee_rate: msg.primary_fee_rate,
+        fee_manager: None,
+        security_deposit: None,
+        compliance_contract: None,
+        membership: None,
+        hashed_listing_ids: false,
+        minter_curator: None,
+        randomness_provider: None,
+        approval_window_blocks: DEFAULT_APPROVAL_WINDOW_BLOCKS,
+        settlement_grace_blocks: 0,
+        unsold_grace_blocks: 0,
+        settlement_reward_rate: None,
+        rate_limits: None,
+        defer_royalties: false,
+        listing_curator: None,
+        results_channel: None,
+        allow_bid_restatement: false,
+        retain_fees: false,
+        max_active_listings: None,
+        anti_sniping: None,
+        max_royalty_rate: None,
+        price_oracle: None,
+        sunset_height: None,
+        reveal_reserves: false,
+        yield_vault: None,
+        buyer_premium: None,
     };
     // Initiate listing_id with 0
-    store_config(deps.storage, &config_state)?;
+    store_config_change(deps.storage, &env.block, &info.sender, &config_state)?;
+
+    for denom_info in msg.accepted_denoms {
+        store_accepted_denom(deps.branch(), denom_info)?;
+    }
+    let mut res = Response::new().add_attribute("owner", owner.to_string());
+    for initial in msg.minters {
+        let minter = deps.api.addr_validate(&initial.minter)?;
+        res = res.add_event(grant_minter(deps.branch(), &env, &owner, &minter, initial.expires)?);
+    }
+
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    seed_legacy_counters(deps.storage, config.listing_count)?;
+    migrate_held_bids(deps.storage)?;
+    seed_listing_counts(deps.storage, env.block.height)?;
+    seed_pending_escrow(deps.storage)?;
+    seed_floor_assets(deps.storage)?;
+
+    // receipts from before the height index are indexed by the height they were settled at
+    let receipts: Vec<SettlementReceipt> = RECEIPTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, receipt)| receipt))
+        .collect::<StdResult<_>>()?;
+    for receipt in receipts {
+        SALES_BY_HEIGHT.save(deps.storage, (receipt.block_height, &receipt.listing_id), &Empty {})?;
+    }
 
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let res = dispatch(deps.branch(), env.clone(), info, msg)?;
+    let res = fund_from_yield(deps.branch(), &env, res)?;
+
+    #[cfg(feature = "invariants")]
+    crate::invariants::assert_invariants(deps.as_ref(), &env)?;
+
+    Ok(res)
+}
+
+fn dispatch(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::V2(msg) => dispatch(deps, env, info, msg.into()),
         // Route messages to appropriate handlers
         ExecuteMsg::PlaceListing {
             id,
             minimum_bid,
-            nft_addr
-        } => execute_place_listing(deps, env, info.clone(), id, minimum_bid, info.sender, nft_addr),
-        ExecuteMsg::BidListing { listing_id, bid_price} => execute_bid_listing(deps, env, info.clone(), listing_id, bid_price, info.sender.clone()),
+            nft_addr,
+            escrowless,
+            template
+        } => {
+            let seller = info.sender.clone();
+            execute_place_listing(deps, env, info, PlaceListingParams { id, minimum_bid, nft_addr, escrowless, template }, seller, None)
+        }
+        ExecuteMsg::PlaceListings(listings) => execute_place_listings(deps, env, info, listings),
+        ExecuteMsg::PlaceListingFor { seller, params } => execute_place_listing_for(deps, env, info, seller, params),
+        ExecuteMsg::PlaceListingWithSig { intent, signature } => execute_place_listing_with_sig(deps, env, info, intent, signature),
+        ExecuteMsg::UpdateLaunchpad{ launchpad, registered } => update_launchpad(deps, env, info, launchpad, registered),
+        ExecuteMsg::BidListing { listing_id, bid_price, on_behalf_of } => execute_bid_listing(deps, env, info.clone(), listing_id, bid_price, info.sender, on_behalf_of),
+        ExecuteMsg::BidOnBehalf { intent, signature } => {
+            let bid_price = intent.bid_price.clone();
+            execute_bid_on_behalf(deps, env, info, intent, signature, bid_price)
+        }
+        ExecuteMsg::BuyNow { listing_id, price } => execute_buy_now(deps, env, info.clone(), listing_id, price, info.sender),
         ExecuteMsg::WithdrawListing { listing_id } => {
             execute_withdraw_listing(deps, env, info, listing_id)
         },
+        ExecuteMsg::ClaimItem { listing_id } => execute_claim_item(deps, env, info, listing_id),
+        ExecuteMsg::ClaimProceeds { listing_id } => execute_claim_proceeds(deps, env, info, listing_id),
+        ExecuteMsg::CancelListings { listing_ids } => execute_cancel_listings(deps, env, info, listing_ids),
+        ExecuteMsg::ReturnUnsold { listing_ids } => execute_return_unsold(deps, env, info, listing_ids),
+        ExecuteMsg::BeginSunset{} => execute_begin_sunset(deps, env, info),
+        ExecuteMsg::SunsetCrank{ limit } => execute_sunset_crank(deps, env, info, limit),
         ExecuteMsg::Mint(mint_msg) => execute_mint(deps, env, info, mint_msg),
-        ExecuteMsg::UpdateMinter{ minter } => update_minters(deps, env, info, &minter),
+        ExecuteMsg::UpdateMinter{ minter, expires } => update_minters(deps, env, info, &minter, expires),
+        ExecuteMsg::PruneExpiredMinters{ limit } => prune_expired_minters(deps, env, info, limit),
         ExecuteMsg::RemoveMinter{ minter } => unregister_minter(deps, env, info, &minter),
-        ExecuteMsg::ReceiveToken(msg) => receive_token(deps, env, info, msg),
+        ExecuteMsg::ApplyForMinter{ metadata_uri } => apply_for_minter(deps, env, info, metadata_uri),
+        ExecuteMsg::ApproveMinter{ applicant, expires, quota } => approve_minter(deps, env, info, applicant, expires, quota),
+        ExecuteMsg::RejectMinter{ applicant } => reject_minter(deps, env, info, applicant),
+        ExecuteMsg::UpdateMinterCurator{ curator } => update_minter_curator(deps, env, info, curator),
+        ExecuteMsg::UpdateListingCurator{ curator } => update_listing_curator(deps, env, info, curator),
+        ExecuteMsg::UpdateCharity{ charity, approved } => update_charity(deps, env, info, charity, approved),
+        ExecuteMsg::UpdateResultsChannel{ channel_id } => update_results_channel(deps, env, info, channel_id),
+        ExecuteMsg::FeatureListing{ listing_id, until } => execute_feature_listing(deps, env, info, listing_id, Some(until)),
+        ExecuteMsg::UnfeatureListing{ listing_id } => execute_feature_listing(deps, env, info, listing_id, None),
+        ExecuteMsg::Receive(msg) => receive_token(deps, env, info, msg),
+        ExecuteMsg::ReceiveNft(msg) => execute_quarantine_nft(deps, env, info, msg),
+        ExecuteMsg::ReclaimQuarantined{ nft_addr, token_id } => execute_reclaim_quarantined(deps, env, info, nft_addr, token_id),
+        ExecuteMsg::ReleaseCustody{ nft_addr, token_id } => execute_release_custody(deps, env, info, nft_addr, token_id),
+        ExecuteMsg::Watch{ target } => execute_watch(deps, env, info, target),
+        ExecuteMsg::Unwatch{ target } => execute_unwatch(deps, env, info, target),
+        ExecuteMsg::ApproveBidder{ operator, listing_id, cap } => execute_approve_bidder(deps, env, info, operator, listing_id, cap),
+        ExecuteMsg::RevokeBidder{ operator, listing_id } => execute_revoke_bidder(deps, env, info, operator, listing_id),
+        ExecuteMsg::ClaimRoyalties{} => execute_claim_royalties(deps, env, info),
+        ExecuteMsg::WithdrawFees{ asset } => execute_withdraw_fees(deps, env, info, asset),
+        ExecuteMsg::ClaimEscrow{ escrow_id, recipient } => execute_claim_escrow(deps, env, info, escrow_id, recipient),
+        ExecuteMsg::RetryRefund{ escrow_id } => execute_retry_refund(deps, env, info, escrow_id),
+        ExecuteMsg::RetryDeadLetter{ id } => execute_retry_dead_letter(deps, env, info, id),
+        ExecuteMsg::UpdateAcceptedDenom{ denom, display, decimals, min_bid_unit, tick_size }
+            => update_accepted_denom(deps, env, info, DenomInfo { denom, display, decimals, min_bid_unit, tick_size }),
+        ExecuteMsg::RemoveAcceptedDenom{ denom } => remove_accepted_denom(deps, env, info, denom),
+        ExecuteMsg::UpdateFeeConfig{ fee_tiers, fee_collector, primary_fee_rate }
+            => update_fee_config(deps, env, info, fee_tiers, fee_collector, primary_fee_rate),
+        ExecuteMsg::UpdateFeeManager{ fee_manager } => update_fee_manager(deps, env, info, fee_manager),
+        ExecuteMsg::SetCollectionFeeOverride{ collection, fee_rate, start_time, end_time }
+            => set_collection_fee_override(deps, env, info, collection, FeeOverride { fee_rate, start_time, end_time }),
+        ExecuteMsg::SetCollectionLevy{ collection, rate, recipient } => set_collection_levy(deps, env, info, collection, rate, recipient),
+        ExecuteMsg::SetCollectionPriceFloor{ collection, price_floor } => set_collection_price_floor(deps, env, info, collection, price_floor),
+        ExecuteMsg::SetCollectionTokenIdFormat{ collection, format } => set_collection_token_id_format(deps, env, info, collection, format),
+        ExecuteMsg::CommitCollectionProvenance{ collection, provenance_hash } => commit_collection_provenance(deps, env, info, collection, provenance_hash),
+        ExecuteMsg::SetCollectionVariant{ collection, variant } => set_collection_variant(deps, env, info, collection, variant),
+        ExecuteMsg::SetCollectionPaused{ collection, paused } => set_collection_paused(deps, env, info, collection, paused),
+        ExecuteMsg::RemoveCollectionFeeOverride{ collection } => remove_collection_fee_override(deps, env, info, collection),
+        ExecuteMsg::UpdateDepositConfig{ security_deposit } => update_deposit_config(deps, env, info, security_deposit),
+        ExecuteMsg::ReleaseDeposit{ listing_id } => execute_release_deposit(deps, env, info, listing_id),
+        ExecuteMsg::ResolveDispute{ listing_id, slash } => execute_resolve_dispute(deps, env, info, listing_id, slash),
+        ExecuteMsg::UpdateComplianceContract{ compliance_contract } => update_compliance_contract(deps, env, info, compliance_contract),
+        ExecuteMsg::UpdatePriceOracle{ price_oracle } => update_price_oracle(deps, env, info, price_oracle),
+        ExecuteMsg::UpdateMembership{ membership } => update_membership(deps, env, info, membership),
+        ExecuteMsg::CreateRaffle{ nft_addr, token_id, ticket_price, duration_blocks, refund_losers }
+            => execute_create_raffle(deps, env, info, nft_addr, token_id, ticket_price, duration_blocks, refund_losers),
+        ExecuteMsg::BuyTickets{ raffle_id, count } => {
+            let payment = read_raffle_payment(deps.storage, raffle_id, count)?;
+            execute_buy_tickets(deps, env, info.clone(), raffle_id, payment, info.sender)
+        },
+        ExecuteMsg::DrawRaffle{ raffle_id } => execute_draw_raffle(deps, env, info, raffle_id),
+        ExecuteMsg::VoidRaffle{ raffle_id } => execute_void_raffle(deps, env, raffle_id),
+        ExecuteMsg::UpdateRandomnessProvider{ randomness_provider } => update_randomness_provider(deps, env, info, randomness_provider),
+        ExecuteMsg::OpenAuction{ listing_id } => execute_open_auction(deps, env, info, listing_id),
+        ExecuteMsg::RequestCandleEnd{ listing_id } => execute_request_candle_end(deps, env, info, listing_id),
+        ExecuteMsg::ReceiveRandomness{ job_id, randomness } => execute_receive_randomness(deps, env, info, job_id, randomness),
+        ExecuteMsg::UpdateRoyaltyPayout{ deferred } => update_royalty_payout(deps, env, info, deferred),
+        ExecuteMsg::UpdateBidRestatement{ allowed } => update_bid_restatement(deps, env, info, allowed),
+        ExecuteMsg::UpdateYieldVault{ yield_vault } => update_yield_vault(deps, env, info, yield_vault),
+        ExecuteMsg::SweepYield{} => execute_sweep_yield(deps, env),
+        ExecuteMsg::HarvestYield{} => execute_harvest_yield(deps, env),
+        ExecuteMsg::UpdateReserveReveal{ revealed } => update_reserve_reveal(deps, env, info, revealed),
+        ExecuteMsg::UpdateBuyerPremium{ rate } => update_buyer_premium(deps, env, info, rate),
+        ExecuteMsg::UpdateIncrementSchedule{ name, steps } => update_increment_schedule(deps, env, info, name, steps),
+        ExecuteMsg::UpdateFeePayout{ retained } => update_fee_payout(deps, env, info, retained),
+        ExecuteMsg::UpdateMaxActiveListings{ max_active_listings } => update_max_active_listings(deps, env, info, max_active_listings),
+        ExecuteMsg::UpdateMaxRoyaltyRate{ max_royalty_rate } => update_max_royalty_rate(deps, env, info, max_royalty_rate),
+        ExecuteMsg::UpdateRateLimits{ rate_limits } => update_rate_limits(deps, env, info, rate_limits),
+        ExecuteMsg::UpdateAntiSniping{ anti_sniping } => update_anti_sniping(deps, env, info, anti_sniping),
+        ExecuteMsg::UpdateSettlementRules{ grace_blocks, reward_rate } => update_settlement_rules(deps, env, info, grace_blocks, reward_rate),
+        ExecuteMsg::UpdateUnsoldGrace{ grace_blocks } => update_unsold_grace(deps, env, info, grace_blocks),
+        ExecuteMsg::UpdateListingWindows{ max_aution_duration_blocks, approval_window_blocks } => update_listing_windows(deps, env, info, max_aution_duration_blocks, approval_window_blocks),
+        ExecuteMsg::UpdateListingIdScheme{ hashed } => update_listing_id_scheme(deps, env, info, hashed),
+        ExecuteMsg::ApproveDiscountListing{ nft_addr, token_id, min_price } => execute_approve_discount_listing(deps, env, info, nft_addr, token_id, Some(min_price)),
+        ExecuteMsg::RevokeDiscountListing{ nft_addr, token_id } => execute_approve_discount_listing(deps, env, info, nft_addr, token_id, None),
+        ExecuteMsg::TransferListing{ listing_id, new_seller } => execute_transfer_listing(deps, env, info, listing_id, new_seller),
+        ExecuteMsg::AcceptListingTransfer{ listing_id } => execute_accept_listing_transfer(deps, env, info, listing_id),
+        ExecuteMsg::SetListingTags{ listing_id, tags } => execute_set_listing_tags(deps, env, info, listing_id, tags),
+        ExecuteMsg::SetListingOperator{ listing_id, operator } => execute_set_listing_operator(deps, env, info, listing_id, operator),
+        ExecuteMsg::InvalidateListing{ listing_id } => execute_invalidate_listing(deps, env, info, listing_id),
+        ExecuteMsg::UpdatePointsRate{ asset, rate } => update_points_rate(deps, env, info, asset, rate),
+        ExecuteMsg::SnapshotPoints{} => snapshot_points(deps, env, info),
+        ExecuteMsg::SaveListingTemplate{ name, template } => save_listing_template(deps, env, info, name, template),
+        ExecuteMsg::RemoveListingTemplate{ name } => remove_listing_template(deps, env, info, name),
+        ExecuteMsg::MakeOffer{ nft_addr, token_id, price } => execute_make_offer(deps, env, info.clone(), nft_addr, token_id, price, info.sender),
+        ExecuteMsg::CancelOffer{ offer_id } => execute_cancel_offer(deps, env, info, offer_id),
+        ExecuteMsg::AcceptOffer{ offer_id } => execute_accept_offer(deps, env, info, offer_id),
+        ExecuteMsg::SetAutoAccept{ nft_addr, token_id, price } => set_auto_accept(deps, env, info, nft_addr, token_id, price),
     }
 }
 
 fn update_minters(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    minter: &String
+    minter: &str,
+    expires: Option<Expiration>
 ) -> Result<Response, ContractError> {
     let config = read_config(deps.storage)?;
     let owner = deps.api.addr_validate(&config.owner)?;
@@ -74,15 +343,36 @@ fn update_minters(
         return Err(ContractError::Unauthorized{});
     }
 
-    let minter_info = MinterInfo {};
+    let minter = deps.api.addr_validate(minter)?;
+    let event = grant_minter(deps, &env, &info.sender, &minter, expires)?;
+    Ok(Response::new().add_event(event))
+}
 
-    store_minters(deps.storage, deps.api.addr_validate(minter)?, minter_info)?;
-    Ok(Response::default())
+// whitelists the minter or updates its expiration, keeping the quota set at approval
+fn grant_minter(
+    deps: DepsMut,
+    env: &Env,
+    granter: &Addr,
+    minter: &Addr,
+    expires: Option<Expiration>
+) -> Result<Event, ContractError> {
+    let previous = read_minter_info(deps.storage, minter.clone());
+    let minter_info = MinterInfo {
+        expires,
+        granted_by: Some(granter.clone()),
+        granted_height: Some(env.block.height),
+        granted_time: Some(env.block.time),
+        quota: previous.as_ref().and_then(|previous| previous.quota),
+        minted: previous.map_or(0, |previous| previous.minted),
+    };
+
+    store_minters(deps.storage, minter.clone(), minter_info)?;
+    Ok(record_minter_audit(deps.storage, env, minter, MinterAction::Update, granter, expires)?)
 }
 
 fn unregister_minter(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     minter: &String
 ) -> Result<Response, ContractError> {
@@ -93,306 +383,4792 @@ fn unregister_minter(
         return Err(ContractError::Unauthorized{});
     }
 
-    remove_minter(deps.storage, deps.api.addr_validate(minter)?)?;
-    Ok(Response::default())
+    let minter = deps.api.addr_validate(minter)?;
+    remove_minter(deps.storage, minter.clone())?;
+    let event = record_minter_audit(deps.storage, &env, &minter, MinterAction::Remove, &info.sender, None)?;
+    Ok(Response::new().add_event(event))
 }
 
-fn receive_token(
+fn apply_for_minter(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    wrapper: Cw20ReceiveMsg
-) -> Result<Response, ContractError>{
-    let msg = from_binary(&wrapper.msg)?;
-    
-    let asset = Asset {
-        info: AssetInfo::Token {contract_addr: info.sender.to_string()},
-        amount: wrapper.amount
-    };
+    metadata_uri: String
+) -> Result<Response, ContractError> {
+    validate_uri(&metadata_uri)?;
 
-    let sender = deps.api.addr_validate(&wrapper.sender)?;
-    match msg {
-        Cw20HookMsg::BidListing{ listing_id,} 
-            => execute_bid_listing(deps, env, info, listing_id, asset, sender),
-        Cw20HookMsg::PlaceListing{ id , nft_addr}
-            => execute_place_listing(deps, env, info, id, asset, sender, nft_addr),
+    let whitelisted = read_minter_info(deps.storage, info.sender.clone())
+        .map_or(false, |minter_info| !minter_info.is_expired(&env.block));
+    if whitelisted {
+        return Err(ContractError::AlreadyMinter {});
     }
+
+    // a new application replaces a pending one
+    MINTER_APPLICATIONS.save(deps.storage, &info.sender, &MinterApplication {
+        applicant: info.sender.clone(),
+        metadata_uri: metadata_uri.clone(),
+        height: env.block.height,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("apply_for_minter", info.sender)
+        .add_attribute("metadata_uri", metadata_uri))
 }
 
-fn execute_mint(
+fn approve_minter(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    msg: GFMintMsg,
+    applicant: String,
+    expires: Option<Expiration>,
+    quota: Option<u64>
 ) -> Result<Response, ContractError> {
-    // check if the sender is a whitelisted minter
-    let minter_info = read_minter_info(deps.storage, info.sender.clone());
+    assert_minter_curator(deps.as_ref(), &info.sender)?;
 
-    if minter_info == None {
-        return Err(ContractError::Unauthorized{});
+    let applicant = deps.api.addr_validate(&applicant)?;
+    if !MINTER_APPLICATIONS.has(deps.storage, &applicant) {
+        return Err(ContractError::NoMinterApplication {});
     }
+    if expires.map_or(false, |expires| expires.is_expired(&env.block)) || quota == Some(0) {
+        return Err(ContractError::InvalidMinterApproval {});
+    }
+    MINTER_APPLICATIONS.remove(deps.storage, &applicant);
 
-    let nft_contract_address = deps.api.addr_validate(&msg.nft_addr)?;
+    store_minters(deps.storage, applicant.clone(), MinterInfo {
+        expires,
+        granted_by: Some(info.sender.clone()),
+        granted_height: Some(env.block.height),
+        granted_time: Some(env.block.time),
+        quota,
+        minted: 0,
+    })?;
+    let event = record_minter_audit(deps.storage, &env, &applicant, MinterAction::Approve, &info.sender, expires)?;
+    Ok(Response::new().add_event(event))
+}
 
-    let collection: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: nft_contract_address.to_string(),
-        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
-    }))?;
+fn reject_minter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    applicant: String
+) -> Result<Response, ContractError> {
+    assert_minter_curator(deps.as_ref(), &info.sender)?;
 
-    if collection.owner != info.sender {
-        return Err(ContractError::Unauthorized{});
+    let applicant = deps.api.addr_validate(&applicant)?;
+    if !MINTER_APPLICATIONS.has(deps.storage, &applicant) {
+        return Err(ContractError::NoMinterApplication {});
     }
+    MINTER_APPLICATIONS.remove(deps.storage, &applicant);
 
-    // check if royalties are set properly. sum of them must not be greater than 100%
-    let mut sum_total_rate = Decimal::zero();
+    let event = record_minter_audit(deps.storage, &env, &applicant, MinterAction::Reject, &info.sender, None)?;
+    Ok(Response::new().add_event(event))
+}
 
-    for royalty in msg.royalties.iter() {
-        sum_total_rate = sum_total_rate + (*royalty).royalty_rate;
+fn update_minter_curator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    curator: Option<String>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
     }
 
-    if sum_total_rate > Decimal::one() {
-        return Err(ContractError::InvalidRoyaltyRate {})
+    if let Some(curator) = &curator {
+        deps.api.addr_validate(curator)?;
     }
 
+    config.minter_curator = curator;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_minter_curator", config.minter_curator.unwrap_or_default()))
+}
+
+fn update_listing_curator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    curator: Option<String>
+) -> Result<Response, ContractError> {
     let mut config = read_config(deps.storage)?;
-    config.listing_count = config.listing_count + 1;
+    let owner = deps.api.addr_validate(&config.owner)?;
 
-    store_config(deps.storage, &config)?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-    let token_id: String = ["GF".to_string(), config.listing_count.to_string()].join(".");
+    if let Some(curator) = &curator {
+        deps.api.addr_validate(curator)?;
+    }
 
-    let meta_data = Metadata {
-        name: Some(msg.name),
-        description: msg.description,
-        external_link: msg.external_link,
-        nft_addr: msg.nft_addr,
-        num_real_repr: Some(msg.num_real_repr),
-        num_nfts: Some(msg.num_nfts),
-        royalties: Some(msg.royalties),
-        init_price: Some(msg.init_price),
-    };
+    config.listing_curator = curator;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
 
-    Ok(Response::new()
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: nft_contract_address.to_string(),
-            msg: to_binary(&Cw721BaseExecuteMsg::<Metadata>::Mint(MintMsg::<Metadata> {  //::<Metadata>
-                token_id: token_id.clone(),
-                owner: msg.owner.clone(),
-                token_uri: msg.image_uri,
-                extension: meta_data.clone()
-            }))?,
-            funds: vec![]
-        }))
-        .add_attribute("token_id", token_id)
-        .add_attribute("owner", msg.owner)
-        .add_attribute("name", meta_data.name.unwrap())
-    )
+    Ok(Response::new().add_attribute("update_listing_curator", config.listing_curator.unwrap_or_else(|| String::from("none"))))
 }
 
-pub fn execute_bid_listing(
+fn update_results_channel(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    listing_id: String,
-    bid_price: Asset,
-    sender: Addr,
+    channel_id: Option<String>
 ) -> Result<Response, ContractError> {
-    // check if the bid_price is correct in case of native tokens
-    bid_price.assert_sent_native_token_balance(&info)?;
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
 
-    // Fetch listing from listing_id
-    let key = listing_id.as_bytes();
-    let mut listing = list_resolver_read(deps.storage).load(key)?;
-    
-    if listing.block_limit < env.block.height {
-        return Err(ContractError::AuctionEnded {});
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
     }
 
-    // check if the token type is identical to the one in the listing
-    if bid_price.info != listing.max_bid.info {
-        return Err(ContractError::AssetInfoMismatch{});
+    if cfg!(not(feature = "ibc")) && channel_id.is_some() {
+        return Err(ContractError::IbcDisabled{});
     }
 
-    // check if current bid exceeds the previous one
- 
-    if bid_price.amount < listing.max_bid.amount {
-        return Err(ContractError::InsufficientFundsSend{});
-    } 
+    config.results_channel = channel_id;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
 
-    // refund former bid
-    let last_bid = listing.max_bid;
-    let last_bidder = listing.max_bidder;
-    let msg = last_bid.into_msg(last_bidder.clone())?;
+    Ok(Response::new().add_attribute("update_results_channel", config.results_channel.unwrap_or_else(|| String::from("none"))))
+}
 
-    // update bidder
-    listing.max_bidder = sender.clone();
-    listing.max_bid = bid_price.clone();
-    list_resolver(deps.storage).save(key, &listing)?;
+fn update_charity(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    charity: String,
+    approved: bool
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner && Some(info.sender.to_string()) != config.listing_curator {
+        return Err(ContractError::Unauthorized{});
+    }
 
-    if env.contract.address != last_bidder {
-    // return money to last bidder
-        Ok(Response::new()
-            .add_attribute("Bidding", listing_id)
-            .add_message(msg))
+    // listings placed while the charity was approved keep passing their price through
+    let charity = deps.api.addr_validate(&charity)?;
+    if approved {
+        CHARITIES.save(deps.storage, &charity, &Empty {})?;
     } else {
-        Ok(Response::new().add_attribute("Bidding", listing_id))
+        CHARITIES.remove(deps.storage, &charity);
     }
 
+    Ok(Response::new()
+        .add_attribute("update_charity", charity.to_string())
+        .add_attribute("approved", approved.to_string()))
 }
 
-pub fn execute_place_listing(
+// features a listing until the given height, or stops featuring it
+fn execute_feature_listing(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    token_id: String,
-    minimum_bid: Asset,
-    sender: Addr,
-    nft_addr: String
+    listing_id: String,
+    until: Option<u64>
 ) -> Result<Response, ContractError> {
-    // check if the nft is locked on the auction contract
-    let nft_contract_address = deps.api.addr_validate(&nft_addr)?;
-    let nft_info: NftInfo<Metadata> = query_nft_info(deps.as_ref(), token_id.clone(), nft_contract_address.clone().to_string())?;
-    if nft_info.owner != info.sender {
-        return Err(ContractError::Unauthorized {});
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner && Some(info.sender.to_string()) != config.listing_curator {
+        return Err(ContractError::Unauthorized{});
     }
 
-    if nft_info.is_listing {
-        return Err(ContractError::AlreadyOnAuction {});
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    match until {
+        Some(until) => {
+            if until < env.block.height {
+                return Err(ContractError::InvalidFeatureHeight {});
+            }
+            load_listing(deps.storage, &listing_id)?;
+            FEATURED_LISTINGS.save(deps.storage, &listing_id, &until)?;
+        }
+        None => FEATURED_LISTINGS.remove(deps.storage, &listing_id),
     }
 
-    // update listing id in store
-    let mut config_state = read_config(deps.storage)?;
+    let mut res = Response::new().add_attribute("feature_listing", listing_id);
+    if let Some(until) = until {
+        res = res.add_attribute("until", until.to_string());
+    }
+    Ok(res)
+}
 
-    // Each auction has a limit for 50000 blocks
-    let listing = Listing {
-        token_id: token_id.clone(),
-        contract_addr: nft_contract_address.clone(),
-        seller: sender,
-        max_bid: minimum_bid,
-        max_bidder: env.contract.address.clone(),
-        block_limit: env.block.height + config_state.max_aution_duration_blocks,
-    };
-    
-    let key: String = ["AUCTION".to_string(), config_state.listing_count.to_string()].join(".");
+// the owner or the minter curator
+fn assert_minter_curator(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let config = read_config(deps.storage)?;
 
-    config_state.listing_count = config_state.listing_count + 1;
-   
-    store_config(deps.storage, &config_state)?;
+    if *sender != config.owner && Some(sender.to_string()) != config.minter_curator {
+        return Err(ContractError::Unauthorized{});
+    }
+    Ok(())
+}
 
-    // save listing to store
-    list_resolver(deps.storage).save(key.as_bytes(), &listing)?;
-
-    // lock nft to contract
-    Ok(Response::new()
-        .add_attribute("place_listing", token_id.to_string())
-        .add_messages(vec![
-            // CosmosMsg::Wasm(WasmMsg::Execute {
-            //     contract_addr: nft_contract_address.to_string(),
-            //     funds: vec![],
-            //     msg: to_binary(&Approve {
-            //         spender: env.contract.address.to_string(),
-            //         token_id: id.clone(),
-            //         expires: Some(Expiration::AtHeight(env.block.height + config_state.max_aution_duration_blocks)),
-            //     })?,
-            // }),
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: nft_contract_address.to_string(),
-                funds: vec![],
-                msg: to_binary(&TransferNft {
-                    recipient: String::from(env.contract.address.as_str()),
-                    token_id,
-                })?,
-            }),
-        ]))
+fn assert_charity(deps: Deps, charity: &str) -> Result<Addr, ContractError> {
+    let charity = deps.api.addr_validate(charity)?;
+    if !CHARITIES.has(deps.storage, &charity) {
+        return Err(ContractError::CharityNotApproved { charity: charity.to_string() });
+    }
+    Ok(charity)
 }
 
-pub fn execute_withdraw_listing(
+// appends a whitelist change to the minter's audit trail, returning the event reporting it
+fn record_minter_audit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    minter: &Addr,
+    action: MinterAction,
+    by: &Addr,
+    expires: Option<Expiration>
+) -> StdResult<Event> {
+    let entry = MinterAudit {
+        action,
+        by: by.clone(),
+        height: env.block.height,
+        time: env.block.time,
+        expires,
+    };
+    append_minter_audit(storage, minter, entry.clone())?;
+
+    let action = match entry.action {
+        MinterAction::Update => "update",
+        MinterAction::Remove => "remove",
+        MinterAction::Prune => "prune",
+        MinterAction::Approve => "approve",
+        MinterAction::Reject => "reject",
+    };
+    Ok(Event::new("minter_audit")
+        .add_attribute("action", action)
+        .add_attribute("minter", minter.to_string())
+        .add_attribute("by", entry.by.to_string())
+        .add_attribute("height", entry.height.to_string())
+        .add_attribute("expires", entry.expires.map_or(String::from("never"), |expires| expires.to_string())))
+}
+
+fn prune_expired_minters(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    listing_id: String,
+    limit: Option<u32>
 ) -> Result<Response, ContractError> {
+    let limit = page_limit(limit);
 
-    let key = listing_id.as_bytes();
-    let listing = list_resolver_read(deps.storage).load(key)?;
-
-    if info.sender != listing.seller {
-        return Err(ContractError::Unauthorized {});
+    let expired = read_expired_minters(deps.storage, &env.block, limit)?;
+    let mut events = vec![];
+    for minter in expired.iter() {
+        let minter = Addr::unchecked(minter);
+        remove_minter(deps.storage, minter.clone())?;
+        events.push(record_minter_audit(deps.storage, &env, &minter, MinterAction::Prune, &info.sender, None)?);
     }
 
-    // Check if the auction ended or not
-    // if listing.block_limit >= env.block.height {
-    //     return Err(ContractError::AuctionNotEnded {});
-    // }
+    Ok(Response::new()
+        .add_events(events)
+        .add_attribute("prune_expired_minters", expired.len().to_string())
+        .add_attributes(expired.into_iter().map(|minter| ("pruned_minter", minter))))
+}
 
-    let mut msgs = vec![];
-    // remove listing from the store
-    list_resolver(deps.storage).remove(key);
+fn update_accepted_denom(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom_info: DenomInfo
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
 
-    // If noone has put a bid then then seller will be sent back with his NFT
-    // Transfer the locked NFT to highest bidder and bid amount to the seller
-    if env.contract.address != listing.max_bidder {
-        // transfer NFT to buyer
-        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: listing.contract_addr.to_string(),
-            funds: vec![],
-            msg: to_binary(&TransferNft {
-                recipient: listing.max_bidder.to_string(),
-                token_id: listing.token_id.clone(),
-            })?,
-        }));
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-        // distribute royalties
-        let mut remain_amount = listing.max_bid.amount;
+    let denom = denom_info.denom.clone();
+    store_accepted_denom(deps, denom_info)?;
+    Ok(Response::new().add_attribute("update_accepted_denom", denom))
+}
 
-        let token_info: NftInfo<Metadata> = query_nft_info(deps.as_ref(), listing.token_id, listing.contract_addr.to_string())?;
+fn store_accepted_denom(deps: DepsMut, denom_info: DenomInfo) -> Result<(), ContractError> {
+    if denom_info.tick_size == Some(Uint128::zero()) {
+        return Err(ContractError::InvalidTickSize { tick_size: Uint128::zero() });
+    }
+    AssetInfo::NativeToken { denom: denom_info.denom.clone() }.validate(deps.api)?;
 
-        for royalty in token_info.extension.royalties.unwrap().iter() {
-            msgs.push((Asset {
-                info: listing.max_bid.info.clone(),
-                amount: listing.max_bid.amount * royalty.royalty_rate
-            }).into_msg(deps.api.addr_validate(&royalty.address)?)?);
+    ACCEPTED_DENOMS.save(deps.storage, &denom_info.denom, &denom_info)?;
+    Ok(())
+}
 
-            remain_amount = remain_amount.checked_sub(listing.max_bid.amount * royalty.royalty_rate)?;
-        }
+fn remove_accepted_denom(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
 
-        // transfer remain amount to seller
-        msgs.push((Asset {
-            info: listing.max_bid.info,
-            amount: remain_amount
-        }).into_msg(listing.seller.clone())?);
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
 
-        Ok(Response::new()
-            .add_attribute("listing_sold", listing_id.to_string())
-            .add_messages(msgs))
-    } else {
-        Ok(Response::new()
-            .add_attribute("listing_unsold", listing_id.to_string())
-            .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: listing.contract_addr.to_string(),
-                funds: vec![],
-                msg: to_binary(&TransferNft {
+    ACCEPTED_DENOMS.remove(deps.storage, &denom);
+    Ok(Response::new().add_attribute("remove_accepted_denom", denom))
+}
+
+fn update_points_rate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    rate: Option<Decimal>
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    match rate {
+        Some(rate) => POINTS_RATES.save(deps.storage, &asset.to_string(), &rate)?,
+        None => POINTS_RATES.remove(deps.storage, &asset.to_string()),
+    }
+    Ok(Response::new().add_attribute("update_points_rate", asset.to_string()))
+}
+
+fn snapshot_points(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    POINTS.add_checkpoint(deps.storage, env.block.height)?;
+    Ok(Response::new().add_attribute("snapshot_points", env.block.height.to_string()))
+}
+
+fn save_listing_template(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+    template: ListingTemplate
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+
+    let invalid_duration = template.duration_blocks
+        .map_or(false, |duration| duration == 0 || duration > config.max_aution_duration_blocks);
+    let total_share = template.payout_splits
+        .iter()
+        .fold(Decimal::zero(), |total, split| total + split.share);
+    let invalid_penalty = template.cancel_penalty
+        .as_ref()
+        .map_or(false, |penalty| !penalty.info.is_native_token() || penalty.amount.is_zero());
+    let invalid_buy_now = match (template.buy_now_price, template.buy_now_lock) {
+        (Some(price), lock) => price.is_zero() || lock.map_or(false, |lock| lock.is_zero() || lock > Decimal::one()),
+        (None, lock) => lock.is_some(),
+    };
+    let invalid_duration = invalid_duration
+        || template.candle_window_blocks == Some(0)
+        || template.start_delay_blocks.map_or(false, |delay| delay == 0 || delay > config.max_aution_duration_blocks);
+    // the whole price of a charity listing goes to the charity, it can't be split
+    let invalid_charity = template.charity.is_some() && !template.payout_splits.is_empty();
+    let invalid_relists = template.auto_relists
+        .map_or(false, |relists| relists == 0 || relists > MAX_AUTO_RELISTS || template.reserve_price.is_none());
+    if invalid_duration || invalid_penalty || invalid_buy_now || invalid_charity || invalid_relists || total_share > Decimal::one() {
+        return Err(ContractError::InvalidListingTemplate {});
+    }
+    if let Some(penalty) = &template.cancel_penalty {
+        penalty.info.validate(deps.api)?;
+    }
+    for split in template.payout_splits.iter() {
+        deps.api.addr_validate(&split.address)?;
+    }
+    if let Some(charity) = &template.charity {
+        assert_charity(deps.as_ref(), charity)?;
+    }
+    if let Some(schedule) = &template.increment_schedule {
+        increment_steps(deps.storage, schedule)?;
+    }
+
+    LISTING_TEMPLATES.save(deps.storage, (&info.sender, &name), &template)?;
+    Ok(Response::new().add_attribute("save_listing_template", name))
+}
+
+fn increment_steps(storage: &dyn Storage, schedule: &str) -> Result<Vec<IncrementStep>, ContractError> {
+    INCREMENT_SCHEDULES
+        .may_load(storage, schedule)?
+        .ok_or_else(|| ContractError::IncrementScheduleNotFound { name: schedule.to_string() })
+}
+
+fn remove_listing_template(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String
+) -> Result<Response, ContractError> {
+    LISTING_TEMPLATES.remove(deps.storage, (&info.sender, &name));
+    Ok(Response::new().add_attribute("remove_listing_template", name))
+}
+
+fn update_fee_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_tiers: Vec<FeeTier>,
+    fee_collector: Option<String>,
+    primary_fee_rate: Option<Decimal>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    assert_fee_config(deps.as_ref(), &fee_tiers, &fee_collector, primary_fee_rate)?;
+
+    config.fee_tiers = fee_tiers;
+    config.fee_collector = fee_collector;
+    config.primary_fee_rate = primary_fee_rate;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_fee_config", info.sender))
+}
+
+fn assert_fee_config(
+    deps: Deps,
+    fee_tiers: &[FeeTier],
+    fee_collector: &Option<String>,
+    primary_fee_rate: Option<Decimal>
+) -> Result<(), ContractError> {
+    if fee_tiers.iter().any(|tier| tier.fee_rate > Decimal::one())
+        || fee_tiers.windows(2).any(|tiers| tiers[0].min_volume >= tiers[1].min_volume)
+        || primary_fee_rate.unwrap_or_default() > Decimal::one() {
+        return Err(ContractError::InvalidFeeTiers {});
+    }
+
+    if let Some(fee_collector) = fee_collector {
+        deps.api.addr_validate(fee_collector)?;
+    }
+    Ok(())
+}
+
+fn update_fee_manager(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_manager: Option<String>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(fee_manager) = &fee_manager {
+        deps.api.addr_validate(fee_manager)?;
+    }
+
+    config.fee_manager = fee_manager;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_fee_manager", config.fee_manager.unwrap_or_default()))
+}
+
+// the owner or the fee manager
+fn assert_fee_manager(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let config = read_config(deps.storage)?;
+
+    if *sender != config.owner && Some(sender.to_string()) != config.fee_manager {
+        return Err(ContractError::Unauthorized{});
+    }
+    Ok(())
+}
+
+fn set_collection_fee_override(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    fee_override: FeeOverride
+) -> Result<Response, ContractError> {
+    assert_fee_manager(deps.as_ref(), &info.sender)?;
+    let collection = deps.api.addr_validate(&collection)?;
+
+    if fee_override.fee_rate > Decimal::one() || fee_override.start_time >= fee_override.end_time {
+        return Err(ContractError::InvalidFeeOverride {});
+    }
+
+    COLLECTION_FEE_OVERRIDES.save(deps.storage, collection.as_str(), &fee_override)?;
+    Ok(Response::new()
+        .add_attribute("set_collection_fee_override", collection)
+        .add_attribute("fee_rate", fee_override.fee_rate.to_string()))
+}
+
+fn remove_collection_fee_override(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String
+) -> Result<Response, ContractError> {
+    assert_fee_manager(deps.as_ref(), &info.sender)?;
+
+    COLLECTION_FEE_OVERRIDES.remove(deps.storage, &collection);
+    Ok(Response::new().add_attribute("remove_collection_fee_override", collection))
+}
+
+fn set_collection_levy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    rate: Option<Decimal>,
+    recipient: Option<String>
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let levy = match (rate, recipient) {
+        (None, None) => None,
+        (Some(rate), Some(recipient)) if rate <= Decimal::percent(MAX_LEVY_PERCENT) => Some(CollectionLevy {
+            rate,
+            recipient: deps.api.addr_validate(&recipient)?,
+        }),
+        _ => return Err(ContractError::InvalidLevy { max_percent: MAX_LEVY_PERCENT }),
+    };
+
+    match &levy {
+        Some(levy) => COLLECTION_LEVIES.save(deps.storage, collection.as_str(), levy)?,
+        None => COLLECTION_LEVIES.remove(deps.storage, collection.as_str()),
+    }
+    Ok(Response::new()
+        .add_attribute("set_collection_levy", collection)
+        .add_attribute("rate", levy.map(|levy| levy.rate).unwrap_or_default().to_string()))
+}
+
+fn set_collection_price_floor(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    price_floor: Option<PriceFloor>
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    match &price_floor {
+        Some(floor) if floor.floor_rate > Decimal::one()
+            || floor.window_blocks == 0
+            || floor.window_blocks > MAX_AUCTION_DURATION_BLOCKS => {
+            return Err(ContractError::InvalidPriceFloor { max_blocks: MAX_AUCTION_DURATION_BLOCKS });
+        }
+        Some(floor) => COLLECTION_PRICE_FLOORS.save(deps.storage, collection.as_str(), floor)?,
+        None => COLLECTION_PRICE_FLOORS.remove(deps.storage, collection.as_str()),
+    }
+    Ok(Response::new()
+        .add_attribute("set_collection_price_floor", collection)
+        .add_attribute("floor_rate", price_floor.map(|floor| floor.floor_rate).unwrap_or_default().to_string()))
+}
+
+fn set_collection_token_id_format(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    format: Option<String>
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    match &format {
+        Some(format) => {
+            // the prefix ahead of the serial is the same for every id, so one sample tells if the
+            // format renders into the default ids
+            let parts = parse_token_id_format(format)?;
+            let symbol = token_id_symbol(deps.as_ref(), &collection, &parts)?;
+            if render_token_id(&parts, &symbol, 0).starts_with(DEFAULT_TOKEN_ID_PREFIX) {
+                return Err(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN });
+            }
+            COLLECTION_TOKEN_ID_FORMATS.save(deps.storage, collection.as_str(), format)?
+        }
+        None => COLLECTION_TOKEN_ID_FORMATS.remove(deps.storage, collection.as_str()),
+    }
+    Ok(Response::new()
+        .add_attribute("set_collection_token_id_format", collection)
+        .add_attribute("format", format.unwrap_or_else(|| "none".to_string())))
+}
+
+fn commit_collection_provenance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    provenance_hash: String
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+    if provenance_hash.len() != 64 || !provenance_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ContractError::InvalidProvenanceHash {});
+    }
+
+    // a hash committed next to existing tokens could be computed over the revealed artwork
+    let token_count: NumTokensResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::NumTokens {})?,
+    }))?;
+    if token_count.count > 0 || COLLECTION_PROVENANCE_COMMITMENTS.has(deps.storage, collection.as_str()) {
+        return Err(ContractError::ProvenanceCommitmentClosed {});
+    }
+
+    let provenance_hash = provenance_hash.to_ascii_lowercase();
+    COLLECTION_PROVENANCE_COMMITMENTS.save(deps.storage, collection.as_str(), &ProvenanceCommitment {
+        provenance_hash: provenance_hash.clone(),
+        committed_by: info.sender,
+        committed_height: env.block.height,
+    })?;
+    Ok(Response::new()
+        .add_attribute("commit_collection_provenance", collection)
+        .add_attribute("provenance_hash", provenance_hash))
+}
+
+// the cw721 symbol of a collection, only queried when the format takes it
+fn token_id_symbol(deps: Deps, collection: &Addr, parts: &[TokenIdPart]) -> StdResult<String> {
+    if !parts.contains(&TokenIdPart::Symbol) {
+        return Ok(String::new());
+    }
+    let info: ContractInfoResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::ContractInfo {})?,
+    }))?;
+    Ok(info.symbol)
+}
+
+fn set_collection_variant(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection: String,
+    variant: Cw721Variant
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    COLLECTION_VARIANTS.save(deps.storage, collection.as_str(), &variant)?;
+    Ok(Response::new()
+        .add_attribute("set_collection_variant", collection)
+        .add_attribute("variant", variant.as_str()))
+}
+
+fn set_collection_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    paused: bool
+) -> Result<Response, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let collection_info: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection_info.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if paused {
+        let pause = CollectionPause { paused_by: info.sender, paused_height: env.block.height };
+        COLLECTION_PAUSES.save(deps.storage, collection.as_str(), &pause)?;
+    } else {
+        COLLECTION_PAUSES.remove(deps.storage, collection.as_str());
+    }
+    Ok(Response::new()
+        .add_attribute("set_collection_paused", collection)
+        .add_attribute("paused", paused.to_string()))
+}
+
+fn assert_collection_open(deps: Deps, collection: &Addr) -> Result<(), ContractError> {
+    if COLLECTION_PAUSES.has(deps.storage, collection.as_str()) {
+        return Err(ContractError::CollectionPaused { collection: collection.to_string() });
+    }
+    Ok(())
+}
+
+// fee rate of the collection promotion running at the given time
+fn active_fee_override(deps: Deps, collection: &Addr, time: Timestamp) -> StdResult<Option<Decimal>> {
+    Ok(COLLECTION_FEE_OVERRIDES
+        .may_load(deps.storage, collection.as_str())?
+        .filter(|fee_override| fee_override.is_active(time))
+        .map(|fee_override| fee_override.fee_rate))
+}
+
+fn update_deposit_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    security_deposit: Option<DepositConfig>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(deposit) = &security_deposit {
+        if !deposit.amount.info.is_native_token() {
+            return Err(ContractError::InvalidSecurityDeposit {});
+        }
+        deps.api.addr_validate(&deposit.arbiter)?;
+    }
+
+    config.security_deposit = security_deposit;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_deposit_config", info.sender))
+}
+
+pub fn execute_release_deposit(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let deposit = SECURITY_DEPOSITS.load(deps.storage, &listing_id)?;
+
+    match deposit.release_height {
+        None => return Err(ContractError::ListingNotSold {}),
+        Some(height) if env.block.height < height => return Err(ContractError::DisputeWindowOpen {}),
+        _ => {}
+    }
+
+    remove_security_deposit(deps.storage, &listing_id, &deposit)?;
+
+    Ok(Response::new()
+        .add_attribute("release_deposit", listing_id)
+        .add_message(deposit.asset.into_transfer_msg(deposit.seller)?))
+}
+
+pub fn execute_resolve_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    slash: bool,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let deposit = SECURITY_DEPOSITS.load(deps.storage, &listing_id)?;
+
+    if info.sender != deposit.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let (buyer, release_height) = match (deposit.buyer.clone(), deposit.release_height) {
+        (Some(buyer), Some(release_height)) => (buyer, release_height),
+        _ => return Err(ContractError::ListingNotSold {}),
+    };
+
+    if env.block.height >= release_height {
+        return Err(ContractError::DisputeWindowClosed {});
+    }
+
+    remove_security_deposit(deps.storage, &listing_id, &deposit)?;
+
+    let recipient = if slash { buyer } else { deposit.seller };
+    Ok(Response::new()
+        .add_attribute("resolve_dispute", listing_id)
+        .add_attribute("slashed", slash.to_string())
+        .add_message(deposit.asset.into_transfer_msg(recipient)?))
+}
+
+fn update_randomness_provider(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    randomness_provider: Option<String>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(randomness_provider) = &randomness_provider {
+        deps.api.addr_validate(randomness_provider)?;
+    }
+
+    config.randomness_provider = randomness_provider;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_randomness_provider", config.randomness_provider.unwrap_or_default()))
+}
+
+fn update_launchpad(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    launchpad: String,
+    registered: bool
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    let launchpad = deps.api.addr_validate(&launchpad)?;
+    if registered {
+        LAUNCHPADS.save(deps.storage, &launchpad, &Empty {})?;
+    } else {
+        LAUNCHPADS.remove(deps.storage, &launchpad);
+    }
+
+    Ok(Response::new()
+        .add_attribute("update_launchpad", launchpad)
+        .add_attribute("registered", registered.to_string()))
+}
+
+fn update_royalty_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    deferred: bool
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    // royalties accrued before stay claimable
+    config.defer_royalties = deferred;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_royalty_payout", deferred.to_string()))
+}
+
+fn update_bid_restatement(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    allowed: bool
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    config.allow_bid_restatement = allowed;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_bid_restatement", allowed.to_string()))
+}
+
+fn update_yield_vault(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    yield_vault: Option<YieldVault>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(vault) = &yield_vault {
+        deps.api.addr_validate(&vault.contract)?;
+        AssetInfo::NativeToken { denom: vault.denom.clone() }.validate(deps.api)?;
+    }
+
+    // leaving a vault takes the deposits out of it, unsetting it is the kill switch. It goes through
+    // with a broken vault too: the vault isn't queried and deposits it fails to return are written off
+    let mut res = Response::new();
+    if let Some(current) = &config.yield_vault {
+        let left = yield_vault.as_ref().map_or(true, |vault| vault.contract != current.contract || vault.denom != current.denom);
+        let principal = YIELD_DEPOSITS.may_load(deps.storage, &current.denom)?.unwrap_or_default();
+        if left && !principal.is_zero() {
+            let reply_id = next_reply_id(deps.storage)?;
+            REPLIES.save(deps.storage, reply_id, &ReplyContext::YieldExit { denom: current.denom.clone(), principal })?;
+            res = res.add_submessage(SubMsg::reply_always(WasmMsg::Execute {
+                contract_addr: current.contract.clone(),
+                funds: vec![],
+                msg: to_binary(&YieldVaultMsg::Withdraw { amount: principal })?,
+            }, reply_id).with_gas_limit(YIELD_EXIT_GAS_LIMIT));
+        }
+    }
+
+    config.yield_vault = yield_vault;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(res.add_attribute("update_yield_vault", config.yield_vault.map_or(String::from("none"), |vault| vault.contract)))
+}
+
+pub fn execute_sweep_yield(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let vault = read_config(deps.storage)?.yield_vault.ok_or(ContractError::YieldVaultUnset {})?;
+    let on_hand = deps.querier.query_balance(&env.contract.address, &vault.denom)?.amount;
+    let amount = on_hand.saturating_sub(vault.buffer);
+    if amount.is_zero() {
+        return Err(ContractError::NoIdleFunds {});
+    }
+
+    let reply_id = next_reply_id(deps.storage)?;
+    REPLIES.save(deps.storage, reply_id, &ReplyContext::YieldDeposit { denom: vault.denom.clone(), amount })?;
+    Ok(Response::new()
+        .add_attribute("sweep_yield", amount.to_string())
+        .add_submessage(SubMsg::reply_on_success(WasmMsg::Execute {
+            contract_addr: vault.contract,
+            funds: coins(amount.u128(), vault.denom),
+            msg: to_binary(&YieldVaultMsg::Deposit {})?,
+        }, reply_id)))
+}
+
+pub fn execute_harvest_yield(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let vault = read_config(deps.storage)?.yield_vault.ok_or(ContractError::YieldVaultUnset {})?;
+    let principal = YIELD_DEPOSITS.may_load(deps.storage, &vault.denom)?.unwrap_or_default();
+    let accrued = query_yield_balance(deps.as_ref(), &env, &vault)?.saturating_sub(principal);
+    if accrued.is_zero() {
+        return Err(ContractError::NoYield {});
+    }
+
+    Ok(Response::new()
+        .add_attribute("harvest_yield", accrued.to_string())
+        .add_submessage(yield_withdrawal(deps.storage, &vault, accrued, Uint128::zero())?))
+}
+
+fn query_yield_balance(deps: Deps, env: &Env, vault: &YieldVault) -> StdResult<Uint128> {
+    let res: YieldBalanceResponse = deps.querier.query_wasm_smart(&vault.contract, &YieldVaultQueryMsg::Balance {
+        address: env.contract.address.to_string(),
+    })?;
+    Ok(res.amount)
+}
+
+// a withdrawal from the yield vault, accounted for in its reply once the funds arrived
+fn yield_withdrawal(storage: &mut dyn Storage, vault: &YieldVault, amount: Uint128, principal: Uint128) -> StdResult<SubMsg> {
+    let reply_id = next_reply_id(storage)?;
+    REPLIES.save(storage, reply_id, &ReplyContext::YieldWithdrawal { denom: vault.denom.clone(), amount, principal })?;
+    Ok(SubMsg::reply_on_success(WasmMsg::Execute {
+        contract_addr: vault.contract.clone(),
+        funds: vec![],
+        msg: to_binary(&YieldVaultMsg::Withdraw { amount })?,
+    }, reply_id))
+}
+
+// native funds of the vault denom a response sends out
+fn native_outflow(res: &Response, denom: &str) -> Uint128 {
+    res.messages
+        .iter()
+        .flat_map(|submsg| match &submsg.msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount.as_slice(),
+            CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) | CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => funds.as_slice(),
+            _ => &[],
+        })
+        .filter(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .sum()
+}
+
+// refunds and settlements the funds on hand don't cover are paid from the yield vault, withdrawn
+// ahead of the other messages together with what refills the buffer
+fn fund_from_yield(deps: DepsMut, env: &Env, mut res: Response) -> Result<Response, ContractError> {
+    let vault = match read_config(deps.storage)?.yield_vault {
+        Some(vault) => vault,
+        None => return Ok(res),
+    };
+    let principal = YIELD_DEPOSITS.may_load(deps.storage, &vault.denom)?.unwrap_or_default();
+    let outflow = native_outflow(&res, &vault.denom);
+    let on_hand = deps.querier.query_balance(&env.contract.address, &vault.denom)?.amount;
+    if principal.is_zero() || outflow <= on_hand {
+        return Ok(res);
+    }
+
+    let amount = (outflow - on_hand).checked_add(vault.buffer)?.min(principal);
+    res.messages.insert(0, yield_withdrawal(deps.storage, &vault, amount, amount)?);
+    Ok(res)
+}
+
+fn update_reserve_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    revealed: bool
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    config.reveal_reserves = revealed;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_reserve_reveal", revealed.to_string()))
+}
+
+fn update_buyer_premium(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate: Option<Decimal>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if rate.map_or(false, |rate| rate > Decimal::percent(MAX_BUYER_PREMIUM_PERCENT)) {
+        return Err(ContractError::InvalidBuyerPremium { max_percent: MAX_BUYER_PREMIUM_PERCENT });
+    }
+
+    config.buyer_premium = rate;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("update_buyer_premium", rate.map_or_else(|| "none".to_string(), |rate| rate.to_string())))
+}
+
+fn update_increment_schedule(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+    steps: Option<Vec<IncrementStep>>
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    match &steps {
+        Some(steps) => {
+            let starts_at_zero = steps.first().map_or(false, |step| step.from.is_zero());
+            let ascending = steps.windows(2).all(|pair| pair[0].from < pair[1].from);
+            if !starts_at_zero || !ascending || steps.len() > MAX_INCREMENT_STEPS as usize
+                || steps.iter().any(|step| step.increment.is_zero()) {
+                return Err(ContractError::InvalidIncrementSchedule { max_steps: MAX_INCREMENT_STEPS });
+            }
+            INCREMENT_SCHEDULES.save(deps.storage, &name, steps)?;
+        }
+        None => INCREMENT_SCHEDULES.remove(deps.storage, &name),
+    }
+
+    Ok(Response::new()
+        .add_attribute("update_increment_schedule", name)
+        .add_attribute("steps", steps.map_or(0, |steps| steps.len()).to_string()))
+}
+
+fn update_fee_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    retained: bool
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    // fees retained before stay withdrawable
+    config.retain_fees = retained;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_fee_payout", retained.to_string()))
+}
+
+fn update_max_active_listings(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_active_listings: Option<u32>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    // sellers above a lowered cap keep their listings but can't place new ones
+    config.max_active_listings = max_active_listings;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_max_active_listings", max_active_listings.map_or(String::from("none"), |max| max.to_string())))
+}
+
+fn update_max_royalty_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_royalty_rate: Option<Decimal>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+    if max_royalty_rate.map_or(false, |max| max > Decimal::one()) {
+        return Err(ContractError::InvalidRoyaltyRate {});
+    }
+
+    // tokens minted before keep their royalties, the cap applies to new mints
+    config.max_royalty_rate = max_royalty_rate;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_max_royalty_rate", max_royalty_rate.map_or(String::from("none"), |max| max.to_string())))
+}
+
+fn update_rate_limits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate_limits: Option<RateLimits>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(rate_limits) = &rate_limits {
+        if rate_limits.listing_window_blocks == 0 || rate_limits.listing_window_blocks > MAX_RATE_LIMIT_WINDOW_BLOCKS {
+            return Err(ContractError::InvalidRateLimits { max: MAX_RATE_LIMIT_WINDOW_BLOCKS });
+        }
+    }
+
+    config.rate_limits = rate_limits;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_rate_limits", config.rate_limits.is_some().to_string()))
+}
+
+fn update_anti_sniping(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    anti_sniping: Option<AntiSniping>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(anti_sniping) = &anti_sniping {
+        let invalid = |blocks: u64| blocks == 0 || blocks > MAX_ANTI_SNIPING_BLOCKS;
+        if invalid(anti_sniping.window_blocks) || invalid(anti_sniping.extension_blocks) || anti_sniping.max_extension_blocks == Some(0) {
+            return Err(ContractError::InvalidAntiSniping { max: MAX_ANTI_SNIPING_BLOCKS });
+        }
+    }
+
+    config.anti_sniping = anti_sniping;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_anti_sniping", config.anti_sniping.is_some().to_string()))
+}
+
+// moves the end of a listing bid on within the anti-sniping window, as far as its extension caps
+// allow, returning the blocks added
+fn extend_listing(listing: &mut Listing, anti_sniping: &AntiSniping, height: u64) -> u64 {
+    if height + anti_sniping.window_blocks < listing.block_limit {
+        return 0;
+    }
+    if anti_sniping.max_extensions.map_or(false, |max| listing.extensions_used >= max) {
+        return 0;
+    }
+
+    let mut added = (height + anti_sniping.extension_blocks).saturating_sub(listing.block_limit);
+    if let Some(max) = anti_sniping.max_extension_blocks {
+        added = added.min(max.saturating_sub(listing.extended_blocks));
+    }
+    if added > 0 {
+        listing.block_limit += added;
+        listing.extensions_used += 1;
+        listing.extended_blocks += added;
+    }
+    added
+}
+
+// counts the action against the limit of `max` per `window_blocks`, none when unlimited
+fn enforce_rate_limit(
+    storage: &mut dyn Storage,
+    counters: &Map<&Addr, RateCounter>,
+    addr: &Addr,
+    height: u64,
+    window_blocks: u64,
+    max: Option<u32>,
+) -> Result<(), ContractError> {
+    let max = match max {
+        Some(max) => max,
+        None => return Ok(()),
+    };
+
+    let counter = bump_rate_counter(storage, counters, addr, height, window_blocks)?;
+    if counter.count > max {
+        return Err(ContractError::RateLimited { height: counter.window_start + window_blocks });
+    }
+    Ok(())
+}
+
+fn update_settlement_rules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    grace_blocks: u64,
+    reward_rate: Option<Decimal>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if grace_blocks > MAX_SETTLEMENT_GRACE_BLOCKS || reward_rate.map_or(false, |reward_rate| reward_rate > Decimal::one()) {
+        return Err(ContractError::InvalidSettlementRules { max: MAX_SETTLEMENT_GRACE_BLOCKS });
+    }
+
+    config.settlement_grace_blocks = grace_blocks;
+    config.settlement_reward_rate = reward_rate;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("update_settlement_rules", grace_blocks.to_string())
+        .add_attribute("reward_rate", reward_rate.unwrap_or_default().to_string()))
+}
+
+fn update_unsold_grace(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    grace_blocks: u64
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if grace_blocks > MAX_SETTLEMENT_GRACE_BLOCKS {
+        return Err(ContractError::InvalidSettlementRules { max: MAX_SETTLEMENT_GRACE_BLOCKS });
+    }
+
+    config.unsold_grace_blocks = grace_blocks;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_unsold_grace", grace_blocks.to_string()))
+}
+
+fn update_listing_windows(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_aution_duration_blocks: Option<u64>,
+    approval_window_blocks: Option<u64>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(duration) = max_aution_duration_blocks {
+        if duration == 0 || duration > MAX_AUCTION_DURATION_BLOCKS {
+            return Err(ContractError::InvalidListingWindow { max: MAX_AUCTION_DURATION_BLOCKS });
+        }
+        config.max_aution_duration_blocks = duration;
+    }
+    if let Some(window) = approval_window_blocks {
+        if window > MAX_APPROVAL_WINDOW_BLOCKS {
+            return Err(ContractError::InvalidListingWindow { max: MAX_APPROVAL_WINDOW_BLOCKS });
+        }
+        config.approval_window_blocks = window;
+    }
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    // listings placed before keep their block limit
+    Ok(Response::new()
+        .add_attribute("update_listing_windows", config.max_aution_duration_blocks.to_string())
+        .add_attribute("approval_window_blocks", config.approval_window_blocks.to_string()))
+}
+
+fn update_listing_id_scheme(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    hashed: bool
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    config.hashed_listing_ids = hashed;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_listing_id_scheme", if hashed { "hashed" } else { "sequential" }))
+}
+
+fn update_compliance_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    compliance_contract: Option<String>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(compliance_contract) = &compliance_contract {
+        deps.api.addr_validate(compliance_contract)?;
+    }
+
+    config.compliance_contract = compliance_contract;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_compliance_contract", config.compliance_contract.unwrap_or_default()))
+}
+
+fn update_price_oracle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_oracle: Option<String>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(price_oracle) = &price_oracle {
+        deps.api.addr_validate(price_oracle)?;
+    }
+
+    config.price_oracle = price_oracle;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_price_oracle", config.price_oracle.unwrap_or_else(|| String::from("none"))))
+}
+
+fn update_membership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    membership: Option<MembershipConfig>
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    if let Some(membership) = &membership {
+        deps.api.addr_validate(&membership.collection)?;
+        if membership.discount > Decimal::one() {
+            return Err(ContractError::InvalidMembershipDiscount {});
+        }
+    }
+
+    config.membership = membership;
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("update_membership", info.sender))
+}
+
+// whether the address holds at least one token of the membership collection
+fn holds_membership(deps: Deps, membership: &MembershipConfig, address: &Addr) -> StdResult<bool> {
+    let tokens: TokensResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: membership.collection.clone(),
+        msg: to_binary(&Cw721QueryMsg::Tokens {
+            owner: address.to_string(),
+            start_after: None,
+            limit: Some(1),
+        })?,
+    }))?;
+
+    Ok(!tokens.tokens.is_empty())
+}
+
+// rejects addresses not attested by the compliance contract, when one is configured
+fn assert_attested(deps: Deps, address: &Addr) -> Result<(), ContractError> {
+    let config = read_config(deps.storage)?;
+
+    if let Some(compliance_contract) = config.compliance_contract {
+        let res: IsAttestedResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: compliance_contract,
+            msg: to_binary(&ComplianceQueryMsg::IsAttested { address: address.to_string() })?,
+        }))?;
+
+        if !res.attested {
+            return Err(ContractError::NotAttested { address: address.to_string() });
+        }
+    }
+    Ok(())
+}
+
+fn execute_approve_discount_listing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: String,
+    min_price: Option<Asset>
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+
+    if read_minted_by(deps.storage, nft_addr.as_str(), &token_id)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized{});
+    }
+
+    match min_price {
+        Some(min_price) => {
+            CREATOR_APPROVALS.save(deps.storage, (nft_addr.as_str(), &token_id), &min_price)?;
+            Ok(Response::new()
+                .add_attribute("approve_discount_listing", token_id)
+                .add_attribute("min_price", min_price.to_string()))
+        }
+        None => {
+            CREATOR_APPROVALS.remove(deps.storage, (nft_addr.as_str(), &token_id));
+            Ok(Response::new().add_attribute("revoke_discount_listing", token_id))
+        }
+    }
+}
+
+// until its primary sale, a token minted here can only be listed below its initial price, or in
+// another asset than the one it is denominated in, with the approval of its minter
+fn assert_primary_price(storage: &mut dyn Storage, nft_addr: &Addr, token_id: &str, extension: &Metadata, minimum_bid: &Asset) -> Result<(), ContractError> {
+    let init_price = match extension.init_price {
+        Some(init_price) => init_price,
+        None => return Ok(()),
+    };
+    // amounts of different assets don't compare, neither do those of tokens without a recorded asset
+    let same_asset = extension.init_price_asset.as_ref() == Some(&minimum_bid.info);
+    if same_asset && minimum_bid.amount >= init_price {
+        return Ok(());
+    }
+
+    if read_minted_by(storage, nft_addr.as_str(), token_id)?.is_none()
+        || has_been_sold(storage, nft_addr.as_str(), token_id)? {
+        return Ok(());
+    }
+
+    match CREATOR_APPROVALS.may_load(storage, (nft_addr.as_str(), token_id))? {
+        Some(min_price) if min_price.info == minimum_bid.info && minimum_bid.amount >= min_price.amount => {
+            CREATOR_APPROVALS.remove(storage, (nft_addr.as_str(), token_id));
+            Ok(())
+        }
+        _ if same_asset => Err(ContractError::BelowInitPrice { init_price }),
+        _ => Err(ContractError::NotInitPriceAsset {}),
+    }
+}
+
+// resales inside the price floor window of the collection can't offer buy-now below the floor
+fn assert_buy_now_floor(deps: Deps, env: &Env, nft_addr: &Addr, token_id: &str, init_price: Option<Uint128>, buy_now_price: Option<Uint128>) -> Result<(), ContractError> {
+    let (init_price, buy_now_price) = match (init_price, buy_now_price) {
+        (Some(init_price), Some(buy_now_price)) => (init_price, buy_now_price),
+        _ => return Ok(()),
+    };
+    let price_floor = match COLLECTION_PRICE_FLOORS.may_load(deps.storage, nft_addr.as_str())? {
+        Some(price_floor) => price_floor,
+        None => return Ok(()),
+    };
+    match read_mint_height(deps.storage, nft_addr.as_str(), token_id)? {
+        Some(mint_height) if env.block.height < mint_height + price_floor.window_blocks => {}
+        _ => return Ok(()),
+    }
+    // the primary sale is covered by the initial price itself
+    if !has_been_sold(deps.storage, nft_addr.as_str(), token_id)? {
+        return Ok(());
+    }
+
+    let floor = payout_share(init_price, price_floor.floor_rate);
+    if buy_now_price < floor {
+        return Err(ContractError::BuyNowBelowFloor { floor });
+    }
+    Ok(())
+}
+
+// sums the amounts of equal assets
+fn merge_assets(assets: &[Asset]) -> StdResult<Vec<Asset>> {
+    let mut merged: Vec<Asset> = vec![];
+    for asset in assets {
+        match merged.iter_mut().find(|merged| merged.info == asset.info) {
+            Some(merged) => *merged = merged.checked_add(asset)?,
+            None => merged.push(asset.clone()),
+        }
+    }
+    Ok(merged)
+}
+
+// native listings and bids must use a vetted denom and respect its minimum bid unit
+fn assert_accepted_asset(deps: Deps, asset: &Asset) -> Result<(), ContractError> {
+    if let AssetInfo::NativeToken { denom } = &asset.info {
+        let denom_info = ACCEPTED_DENOMS
+            .may_load(deps.storage, denom)?
+            .ok_or(ContractError::UnacceptedDenom { denom: denom.clone() })?;
+
+        if asset.amount < denom_info.min_bid_unit {
+            return Err(ContractError::BelowMinBidUnit { min_bid_unit: denom_info.min_bid_unit });
+        }
+
+        if let Some(tick_size) = denom_info.tick_size {
+            if !(asset.amount % tick_size).is_zero() {
+                return Err(ContractError::InvalidTickSize { tick_size });
+            }
+        }
+    }
+    Ok(())
+}
+
+// decimals of an accepted native denom for displaying amounts
+fn asset_decimals(deps: Deps, asset: &Asset) -> StdResult<Option<u8>> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } => Ok(ACCEPTED_DENOMS.may_load(deps.storage, denom)?.map(|denom_info| denom_info.decimals)),
+        AssetInfo::Token { .. } => Ok(None),
+    }
+}
+
+fn execute_watch(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    target: WatchTarget
+) -> Result<Response, ContractError> {
+    match &target {
+        WatchTarget::Listing { listing_id } => {
+            load_listing(deps.storage, listing_id)?;
+        }
+        WatchTarget::Collection { nft_addr } => {
+            deps.api.addr_validate(nft_addr)?;
+        }
+    }
+
+    // every watcher is named in the events of the target, each bid pays for them
+    let watching = WATCHERS.has(deps.storage, (&target.key(), &info.sender));
+    if !watching && read_watcher_count(deps.storage, &target)? >= MAX_WATCHERS {
+        return Err(ContractError::TooManyWatchers { max: MAX_WATCHERS });
+    }
+
+    store_watch(deps.storage, &info.sender, &target)?;
+    Ok(Response::new()
+        .add_attribute("watch", target.key())
+        .add_attribute("watcher", info.sender))
+}
+
+fn execute_unwatch(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    target: WatchTarget
+) -> Result<Response, ContractError> {
+    remove_watch(deps.storage, &info.sender, &target)?;
+    Ok(Response::new()
+        .add_attribute("unwatch", target.key())
+        .add_attribute("watcher", info.sender))
+}
+
+fn execute_approve_bidder(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+    listing_id: String,
+    cap: Uint128
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    load_listing(deps.storage, &listing_id)?;
+
+    BID_APPROVALS.save(deps.storage, (&info.sender, &operator, &listing_id), &cap)?;
+    Ok(Response::new()
+        .add_attribute("approve_bidder", operator)
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("cap", cap))
+}
+
+fn execute_revoke_bidder(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+    listing_id: String
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+
+    BID_APPROVALS.remove(deps.storage, (&info.sender, &operator, &listing_id));
+    Ok(Response::new()
+        .add_attribute("revoke_bidder", operator)
+        .add_attribute("listing_id", listing_id))
+}
+
+// builds a `watch` event naming everyone watching the listing or its collection,
+// so notification services only have to filter on this event type
+fn watch_events(deps: Deps, action: &str, listing_id: &str, listing: &Listing) -> StdResult<Vec<Event>> {
+    let listing_target = WatchTarget::Listing { listing_id: listing_id.to_string() };
+    let collection_target = WatchTarget::Collection { nft_addr: listing.contract_addr.to_string() };
+
+    let mut watchers = read_watchers(deps.storage, &listing_target)?;
+    for watcher in read_watchers(deps.storage, &collection_target)? {
+        if !watchers.contains(&watcher) {
+            watchers.push(watcher);
+        }
+    }
+
+    if watchers.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let watchers: Vec<String> = watchers.iter().map(|watcher| watcher.to_string()).collect();
+    Ok(vec![Event::new("watch")
+        .add_attribute("action", action)
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("collection", listing.contract_addr.to_string())
+        .add_attribute("watchers", watchers.join(","))])
+}
+
+// builds the `outbid` event of a refunded bid, for notification services to alert the
+// outbid address without reading the listing
+fn outbid_event(listing_id: &str, bidder: &Addr, refund: &Asset, listing: &Listing) -> Event {
+    Event::new("outbid")
+        .add_attribute("listing_id", listing_id)
+        .add_attribute("bidder", bidder.to_string())
+        .add_attribute("refund", refund.to_string())
+        .add_attribute("new_bid", listing.max_bid.to_string())
+        .add_attribute("new_bidder", listing.max_bidder.to_string())
+}
+
+fn receive_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg
+) -> Result<Response, ContractError>{
+    let msg = from_binary(&wrapper.msg)?;
+    
+    let asset = Asset {
+        info: AssetInfo::Token {contract_addr: info.sender.to_string()},
+        amount: wrapper.amount
+    };
+
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    match msg {
+        Cw20HookMsg::BidListing{ listing_id, on_behalf_of } => {
+            let bid_price = cw20_hammer_price(deps.as_ref(), &listing_id, asset)?;
+            execute_bid_listing(deps, env, info, listing_id, bid_price, sender, on_behalf_of)
+        }
+        Cw20HookMsg::BidOnBehalf{ intent, signature } => {
+            let bid_price = cw20_hammer_price(deps.as_ref(), &intent.listing_id, asset)?;
+            execute_bid_on_behalf(deps, env, info, intent, signature, bid_price)
+        }
+        Cw20HookMsg::PlaceListing{ id , nft_addr, escrowless, template } => {
+            let params = PlaceListingParams { id, minimum_bid: asset, nft_addr, escrowless, template };
+            execute_place_listing(deps, env, info, params, sender, None)
+        }
+        Cw20HookMsg::MakeOffer{ nft_addr, token_id }
+            => execute_make_offer(deps, env, info, nft_addr, token_id, asset, sender),
+        Cw20HookMsg::BuyNow{ listing_id } => {
+            let price = cw20_hammer_price(deps.as_ref(), &listing_id, asset)?;
+            execute_buy_now(deps, env, info, listing_id, price, sender)
+        }
+        Cw20HookMsg::BuyTickets{ raffle_id }
+            => execute_buy_tickets(deps, env, info, raffle_id, asset, sender),
+    }
+}
+
+// cw20 payments send the hammer price and the buyer premium on it in one amount, the hammer price
+// is the one whose premium adds up to the sent amount
+fn cw20_hammer_price(deps: Deps, listing_id: &str, paid: Asset) -> Result<Asset, ContractError> {
+    let listing = load_listing(deps.storage, &canonical_listing_id(deps.storage, listing_id.to_string())?)?;
+    let rate = match listing.buyer_premium {
+        Some(rate) => rate,
+        None => return Ok(paid),
+    };
+
+    // the sent amount grows with the price, so only the truncated inverse or the next amount can match
+    let floor = paid.amount.multiply_ratio(Decimal::one().atomics(), (Decimal::one() + rate).atomics());
+    [floor, floor + Uint128::new(1)]
+        .iter()
+        .map(|amount| Asset { info: paid.info.clone(), amount: *amount })
+        .find(|price| listing.with_premium(price).amount == paid.amount)
+        .ok_or(ContractError::BuyerPremiumMismatch { rate })
+}
+
+fn validate_name(name: &str) -> Result<(), ContractError> {
+    if name.trim().chars().count() < MIN_NAME_LENGTH || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ContractError::InvalidNameLength { min: MIN_NAME_LENGTH, max: MAX_NAME_LENGTH });
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), ContractError> {
+    if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return Err(ContractError::DescriptionTooLong { max: MAX_DESCRIPTION_LENGTH });
+    }
+    Ok(())
+}
+
+// language tags like `en` or `pt-BR`: a 2-3 letter language optionally followed by subtags
+fn validate_language_code(lang: &str) -> Result<(), ContractError> {
+    let mut subtags = lang.split('-');
+    let language = subtags.next().unwrap_or_default();
+
+    let valid = (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_lowercase())
+        && subtags.all(|subtag| (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    if !valid {
+        return Err(ContractError::InvalidLanguageCode { lang: lang.to_string() });
+    }
+    Ok(())
+}
+
+fn validate_translations(
+    translations: &[LocalizedString],
+    validate_text: fn(&str) -> Result<(), ContractError>
+) -> Result<(), ContractError> {
+    for (index, translation) in translations.iter().enumerate() {
+        validate_language_code(&translation.lang)?;
+        validate_text(&translation.text)?;
+
+        if translations[..index].iter().any(|other| other.lang == translation.lang) {
+            return Err(ContractError::DuplicateLanguage { lang: translation.lang.clone() });
+        }
+    }
+    Ok(())
+}
+
+fn validate_mint_msg(msg: &GFMintMsg) -> Result<(), ContractError> {
+    validate_name(&msg.name)?;
+    validate_translations(&msg.localized_names, validate_name)?;
+
+    if let Some(description) = &msg.description {
+        validate_description(description)?;
+    }
+    validate_translations(&msg.localized_descriptions, validate_description)?;
+
+    if let Some(image_uri) = &msg.image_uri {
+        validate_uri(image_uri)?;
+    }
+    if let Some(external_link) = &msg.external_link {
+        validate_uri(external_link)?;
+    }
+
+    if msg.num_nfts.is_zero() {
+        return Err(ContractError::InvalidNumNfts {});
+    }
+    Ok(())
+}
+
+fn execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: GFMintMsg,
+) -> Result<Response, ContractError> {
+    // check if the sender is a whitelisted minter
+    let mut minter_info = match read_minter_info(deps.storage, info.sender.clone()) {
+        Some(minter_info) if !minter_info.is_expired(&env.block) => minter_info,
+        _ => return Err(ContractError::Unauthorized{}),
+    };
+    if minter_info.quota_exhausted() {
+        return Err(ContractError::MintQuotaExhausted {});
+    }
+    minter_info.minted += 1;
+    store_minters(deps.storage, info.sender.clone(), minter_info)?;
+
+    validate_mint_msg(&msg)?;
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let nft_contract_address = deps.api.addr_validate(&msg.nft_addr)?;
+
+    let collection: Collection = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_contract_address.to_string(),
+        msg: to_binary(&Cw721QueryMsg::CollectionInfo {})?,
+    }))?;
+
+    if collection.owner != info.sender {
+        return Err(ContractError::Unauthorized{});
+    }
+    assert_collection_open(deps.as_ref(), &nft_contract_address)?;
+
+    let variant = COLLECTION_VARIANTS.may_load(deps.storage, nft_contract_address.as_str())?.unwrap_or_default();
+    let translated = !msg.localized_names.is_empty() || !msg.localized_descriptions.is_empty();
+    if variant == Cw721Variant::Standard && (!msg.royalties.is_empty() || translated) {
+        return Err(ContractError::UnsupportedMetadata {});
+    }
+
+    // check if royalties are set properly. sum of them must not be greater than 100%
+    let mut sum_total_rate = Decimal::zero();
+
+    for royalty in msg.royalties.iter() {
+        sum_total_rate = sum_total_rate + (*royalty).royalty_rate;
+    }
+
+    if sum_total_rate > Decimal::one() {
+        return Err(ContractError::InvalidRoyaltyRate {})
+    }
+
+    // the platform may cap royalties lower to protect buyers
+    if let Some(max) = read_config(deps.storage)?.max_royalty_rate {
+        if sum_total_rate > max {
+            return Err(ContractError::RoyaltyRateAboveMax { max });
+        }
+    }
+
+    let token_id: String = match COLLECTION_TOKEN_ID_FORMATS.may_load(deps.storage, nft_contract_address.as_str())? {
+        Some(format) => {
+            let parts = parse_token_id_format(&format)?;
+            let symbol = token_id_symbol(deps.as_ref(), &nft_contract_address, &parts)?;
+            render_token_id(&parts, &symbol, next_serial(deps.storage, nft_contract_address.as_str())?)
+        }
+        None => ["GF".to_string(), next_id(deps.storage, &TOKEN_IDS)?.to_string()].join("."),
+    };
+
+    let meta_data = Metadata {
+        name: Some(msg.name),
+        description: msg.description,
+        external_link: msg.external_link,
+        nft_addr: msg.nft_addr,
+        num_real_repr: Some(msg.num_real_repr),
+        num_nfts: Some(msg.num_nfts),
+        royalties: Some(msg.royalties),
+        init_price: Some(msg.init_price),
+        init_price_asset: msg.init_price_asset,
+        transferable: Some(msg.transferable && variant != Cw721Variant::Soulbound),
+        localized_names: Some(msg.localized_names).filter(|names| !names.is_empty()),
+        localized_descriptions: Some(msg.localized_descriptions).filter(|descriptions| !descriptions.is_empty()),
+    };
+
+    CREATOR_COLLECTIONS.save(deps.storage, (&info.sender, &nft_contract_address), &Empty {})?;
+    append_provenance(deps.storage, nft_contract_address.as_str(), &token_id, &ProvenanceEvent::Minted {
+        minter: info.sender.clone(),
+        owner: msg.owner.clone(),
+        block_height: env.block.height,
+    })?;
+
+    // custodied tokens are held by the contract for their owner
+    let holder = if msg.custody {
+        CUSTODY.save(deps.storage, (nft_contract_address.as_str(), &token_id), &CustodiedNft {
+            owner: owner.clone(),
+            block_height: env.block.height,
+        })?;
+        env.contract.address.to_string()
+    } else {
+        owner.to_string()
+    };
+
+    // standard cw721 contracts reject the metadata extension
+    let mint_msg = match variant {
+        Cw721Variant::Standard => to_binary(&Cw721BaseExecuteMsg::<Empty>::Mint(MintMsg::<Empty> {
+            token_id: token_id.clone(),
+            owner: holder,
+            token_uri: msg.image_uri,
+            extension: Empty {}
+        }))?,
+        Cw721Variant::OnChainMetadata | Cw721Variant::Soulbound => to_binary(&Cw721BaseExecuteMsg::<Metadata>::Mint(MintMsg::<Metadata> {
+            token_id: token_id.clone(),
+            owner: holder,
+            token_uri: msg.image_uri,
+            extension: meta_data.clone()
+        }))?,
+    };
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: nft_contract_address.to_string(),
+            msg: mint_msg,
+            funds: vec![]
+        }))
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", msg.owner)
+        .add_attribute("name", meta_data.name.unwrap())
+        .add_attribute("variant", variant.as_str())
+        .add_attribute("custody", msg.custody.to_string())
+    )
+}
+
+// lodges a bid before the start of an auction, a bidder lodging again gets its former bid back
+fn lodge_absentee_bid(
+    deps: DepsMut,
+    env: &Env,
+    listing_id: &str,
+    listing: &Listing,
+    bidder: Addr,
+    bid_price: Asset,
+) -> Result<Response, ContractError> {
+    let previous = ABSENTEE_BIDS.may_load(deps.storage, (listing_id, &bidder))?;
+    if previous.is_none() && read_absentee_bids(deps.storage, listing_id)?.len() >= MAX_ABSENTEE_BIDS as usize {
+        return Err(ContractError::TooManyAbsenteeBids { max: MAX_ABSENTEE_BIDS });
+    }
+
+    credit_ledger(deps.storage, LedgerBucket::Bids, &listing.with_premium(&bid_price))?;
+    ABSENTEE_BIDS.save(deps.storage, (listing_id, &bidder), &AbsenteeBid {
+        bidder: bidder.clone(),
+        bid: bid_price,
+        height: env.block.height,
+    })?;
+
+    let mut res = Response::new()
+        .add_attribute("absentee_bid", listing_id)
+        .add_attribute("bidder", bidder.to_string())
+        .add_attribute("opens_at", listing.start_height.to_string());
+    if let Some(previous) = previous {
+        let refund = listing.with_premium(&previous.bid);
+        debit_ledger(deps.storage, LedgerBucket::Bids, &refund)?;
+        res = res.add_submessages(refund_submsg(deps.storage, listing_id, &bidder, &refund)?);
+    }
+    Ok(res)
+}
+
+// opens an auction past its start with its absentee bids: the highest becomes the opening bid at
+// one increment above the runner-up, or at the minimum without one, and gets the rest back. An
+// auction without absentee bids is left as it is
+fn open_auction(storage: &mut dyn Storage, env: &Env, listing_id: &str, listing: &mut Listing) -> StdResult<Response> {
+    let mut bids = take_absentee_bids(storage, listing_id, listing)?;
+    if bids.is_empty() {
+        return Ok(Response::new());
+    }
+    // highest first, the stable sort keeps the earlier lodged of equal bids ahead
+    bids.sort_by_key(|bid| Reverse(bid.bid.amount));
+    let top = bids.remove(0);
+    let opening_amount = match bids.first() {
+        Some(runner_up) => Listing {
+            max_bid: runner_up.bid.clone(),
+            max_bidder: runner_up.bidder.clone(),
+            ..listing.clone()
+        }
+        .min_next_bid(&env.contract.address)
+        .min(top.bid.amount),
+        None => listing.max_bid.amount,
+    };
+    let opening = Asset { info: top.bid.info.clone(), amount: opening_amount };
+
+    let mut submsgs = vec![];
+    for bid in bids.iter() {
+        submsgs.extend(refund_submsg(storage, listing_id, &bid.bidder, &listing.with_premium(&bid.bid))?);
+    }
+    let excess = listing.with_premium(&top.bid).amount - listing.with_premium(&opening).amount;
+    if !excess.is_zero() {
+        let excess = Asset { info: opening.info.clone(), amount: excess };
+        submsgs.extend(refund_submsg(storage, listing_id, &top.bidder, &excess)?);
+    }
+
+    remove_floor_price(storage, listing_id, listing)?;
+    remove_top_bid(storage, listing_id, listing);
+    credit_ledger(storage, LedgerBucket::Bids, &listing.with_premium(&opening))?;
+    listing.max_bid = opening;
+    listing.max_bidder = top.bidder;
+    listing.max_bid_height = env.block.height;
+    save_bid_state(storage, listing_id, listing)?;
+    store_floor_price(storage, listing_id, listing)?;
+    store_top_bid(storage, listing_id, listing)?;
+
+    Ok(Response::new()
+        .add_attribute("auction_opened", listing_id)
+        .add_attribute("opening_bid", listing.max_bid.to_string())
+        .add_submessages(submsgs))
+}
+
+pub fn execute_open_auction(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let mut listing = load_listing(deps.storage, &listing_id)?;
+    if env.block.height < listing.start_height {
+        return Err(ContractError::AuctionNotOpen { height: listing.start_height });
+    }
+    if !has_absentee_bids(deps.storage, &listing_id) {
+        return Err(ContractError::NoAbsenteeBids {});
+    }
+    Ok(open_auction(deps.storage, &env, &listing_id, &mut listing)?)
+}
+
+pub fn execute_bid_listing(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    bid_price: Asset,
+    sender: Addr,
+    on_behalf_of: Option<String>,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    assert_accepted_asset(deps.as_ref(), &bid_price)?;
+
+    // operators bid for an approved bidder, who then receives the refund or the nft. The operator
+    // of a listing bids for any client without an approval
+    let listing_operator = LISTING_OPERATORS.may_load(deps.storage, &listing_id)?;
+    let bidder = match on_behalf_of {
+        Some(bidder) if listing_operator.as_ref() == Some(&sender) => deps.api.addr_validate(&bidder)?,
+        Some(bidder) => {
+            let bidder = deps.api.addr_validate(&bidder)?;
+            let cap = BID_APPROVALS
+                .may_load(deps.storage, (&bidder, &sender, &listing_id))?
+                .ok_or(ContractError::Unauthorized {})?;
+            if bid_price.amount > cap {
+                return Err(ContractError::BidCapExceeded {});
+            }
+            bidder
+        }
+        None => sender.clone(),
+    };
+
+    assert_attested(deps.as_ref(), &sender)?;
+    if bidder != sender {
+        assert_attested(deps.as_ref(), &bidder)?;
+    }
+
+    let config = read_config(deps.storage)?;
+    if let Some(rate_limits) = config.rate_limits {
+        enforce_rate_limit(deps.storage, &BID_RATE, &sender, env.block.height, 1, rate_limits.max_bids_per_block)?;
+    }
+
+    // Fetch listing from listing_id
+    let mut listing = load_listing(deps.storage, &listing_id)?;
+    // native bids send the buyer premium along with the bid_price
+    let held_bid = listing.with_premium(&bid_price);
+    held_bid.assert_sent(&info)?;
+
+    if listing.block_limit < env.block.height {
+        return Err(ContractError::AuctionEnded {});
+    }
+
+    if !escrow_intact(deps.as_ref(), &env, &listing)? {
+        return Err(ContractError::EscrowBroken {});
+    }
+
+    // check if the token type is identical to the one in the listing
+    if bid_price.info != listing.max_bid.info {
+        return Err(ContractError::AssetInfoMismatch{});
+    }
+
+    // check if current bid exceeds the previous one
+ 
+    if bid_price.amount < listing.max_bid.amount {
+        return Err(ContractError::InsufficientFundsSend{});
+    } 
+
+    // bids before the start are lodged, the auction opens with the highest of them
+    if env.block.height < listing.start_height {
+        let on_behalf = bidder != sender;
+        let res = lodge_absentee_bid(deps, &env, &listing_id, &listing, bidder, bid_price)?;
+        return Ok(if on_behalf { res.add_attribute("operator", sender.to_string()) } else { res });
+    }
+    let opening = open_auction(deps.storage, &env, &listing_id, &mut listing)?;
+
+    // restating the highest bid at the same amount only churns the refund
+    if !config.allow_bid_restatement && bidder == listing.max_bidder && bid_price.amount <= listing.max_bid.amount {
+        return Err(ContractError::BidRestatement {});
+    }
+
+    // outbidding has to raise the current bid by the increment rate or schedule of the listing
+    let min_bid = listing.min_next_bid(&env.contract.address);
+    if bid_price.amount < min_bid {
+        return Err(ContractError::BidIncrementTooLow { min_bid });
+    }
+
+    // refund former bid
+    credit_ledger(deps.storage, LedgerBucket::Bids, &held_bid)?;
+    remove_floor_price(deps.storage, &listing_id, &listing)?;
+    remove_top_bid(deps.storage, &listing_id, &listing);
+    let last_bid = listing.max_bid.clone();
+    let last_bidder = listing.max_bidder.clone();
+
+    // the first bid on an escrowless listing pulls the nft from the seller, voiding the listing and
+    // refunding the bid if the seller no longer owns it or revoked the approval
+    let mut escrow_pull = vec![];
+    if listing.pending_escrow {
+        let reply_id = next_reply_id(deps.storage)?;
+        REPLIES.save(deps.storage, reply_id, &ReplyContext::EscrowPull { listing_id: listing_id.clone() })?;
+        escrow_pull.push(SubMsg::reply_always(WasmMsg::Execute {
+            contract_addr: listing.contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: env.contract.address.to_string(),
+                token_id: listing.token_id.clone(),
+            })?,
+        }, reply_id));
+        listing.pending_escrow = false;
+    }
+
+    // bids outbid in the closing window of a candle auction may still win and are held, one
+    // outbid at its own height never wins and goes back right away
+    let block_limit = listing.block_limit;
+    let window_open = listing.candle_window.map_or(false, |window| env.block.height + window >= block_limit);
+    let hold_last_bid = window_open && env.contract.address != last_bidder && listing.max_bid_height < env.block.height;
+    let mut dropped_refunds = vec![];
+    let mut dropped_events = vec![];
+    if hold_last_bid {
+        // settlement refunds every held bid, at the cap the lowest makes room so bidding stays open
+        if held_bid_count(deps.storage, &listing_id)? >= MAX_HELD_BIDS {
+            if let Some(lowest) = drop_lowest_held_bid(deps.storage, &listing_id, &listing)? {
+                let refund = listing.with_premium(&lowest.bid);
+                dropped_refunds.extend(refund_submsg(deps.storage, &listing_id, &lowest.bidder, &refund)?);
+                dropped_events.push(outbid_event(&listing_id, &lowest.bidder, &refund, &listing));
+            }
+        }
+        hold_bid(deps.storage, &listing_id, &CandleBid {
+            bidder: last_bidder.clone(),
+            bid: last_bid.clone(),
+            height: listing.max_bid_height,
+        })?;
+    }
+
+    // update bidder
+    listing.max_bidder = bidder;
+    listing.max_bid = bid_price.clone();
+    listing.max_bid_height = env.block.height;
+    let extension = match &config.anti_sniping {
+        // the random end of a candle auction already deters sniping
+        Some(anti_sniping) if listing.candle_window.is_none() => extend_listing(&mut listing, anti_sniping, env.block.height),
+        _ => 0,
+    };
+    if extension > 0 {
+        move_listing_end(deps.storage, &listing_id, block_limit, listing.block_limit)?;
+    }
+    // only the bid state is rewritten, unless the first bid pulled the nft of an escrowless listing
+    if !escrow_pull.is_empty() {
+        list_resolver(deps.storage).save(listing_id.as_bytes(), &listing)?;
+    }
+    save_bid_state(deps.storage, &listing_id, &listing)?;
+    store_floor_price(deps.storage, &listing_id, &listing)?;
+    store_top_bid(deps.storage, &listing_id, &listing)?;
+
+    let events = watch_events(deps.as_ref(), "bid", &listing_id, &listing)?;
+    let mut res = Response::new()
+        .add_attributes(opening.attributes)
+        .add_submessages(opening.messages)
+        .add_submessages(dropped_refunds)
+        .add_events(dropped_events);
+    // a bid placed for a client names the operator, the client is the bidder of record
+    if listing.max_bidder != sender {
+        res = res
+            .add_attribute("operator", sender.to_string())
+            .add_attribute("beneficiary", listing.max_bidder.to_string());
+    }
+    if extension > 0 {
+        res = res
+            .add_attribute("extended_to", listing.block_limit.to_string())
+            .add_attribute("extensions_used", listing.extensions_used.to_string());
+    }
+
+    if env.contract.address != last_bidder && !hold_last_bid {
+    // return money to last bidder
+        let refund = listing.with_premium(&last_bid);
+        debit_ledger(deps.storage, LedgerBucket::Bids, &refund)?;
+        let msg = refund_submsg(deps.storage, &listing_id, &last_bidder, &refund)?;
+        let outbid = outbid_event(&listing_id, &last_bidder, &refund, &listing);
+
+        Ok(res
+            .add_attribute("Bidding", listing_id)
+            .add_submessages(msg)
+            .add_event(outbid)
+            .add_events(events))
+    } else {
+        Ok(res
+            .add_attribute("Bidding", listing_id)
+            .add_submessages(escrow_pull)
+            .add_events(events))
+    }
+
+}
+
+pub fn execute_place_listing(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: PlaceListingParams,
+    sender: Addr,
+    listed_by: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let PlaceListingParams { id: token_id, minimum_bid, nft_addr, escrowless, template } = params;
+    let escrowless = escrowless.unwrap_or(false);
+    let config_state = read_config(deps.storage)?;
+
+    // check if the nft is locked on the auction contract
+    let nft_contract_address = deps.api.addr_validate(&nft_addr)?;
+    assert_not_sunset(&config_state)?;
+    assert_collection_open(deps.as_ref(), &nft_contract_address)?;
+    let nft_info: NftInfo<Metadata> = query_nft_info(deps.as_ref(), token_id.clone(), nft_contract_address.clone().to_string())?;
+    // a token minted into custody is already held by the contract
+    let custodied = match CUSTODY.may_load(deps.storage, (nft_contract_address.as_str(), &token_id))? {
+        Some(custody) if custody.owner == sender && !escrowless => true,
+        Some(_) => return Err(ContractError::NotTokenOwner {}),
+        None => {
+            assert_token_owner(deps.as_ref(), &nft_contract_address, &token_id, &sender, escrowless)?;
+            false
+        }
+    };
+
+    if nft_info.is_listing {
+        return Err(ContractError::AlreadyOnAuction {});
+    }
+
+    if nft_info.extension.transferable == Some(false) {
+        return Err(ContractError::NonTransferable {});
+    }
+
+    if let Some(rate_limits) = &config_state.rate_limits {
+        enforce_rate_limit(deps.storage, &LISTING_RATE, &sender, env.block.height, rate_limits.listing_window_blocks, rate_limits.max_listings)?;
+    }
+    if let Some(max) = config_state.max_active_listings {
+        if ACTIVE_LISTINGS.may_load(deps.storage, &sender)?.unwrap_or_default() >= max {
+            return Err(ContractError::TooManyActiveListings { max });
+        }
+    }
+
+    assert_accepted_asset(deps.as_ref(), &minimum_bid)?;
+    assert_attested(deps.as_ref(), &sender)?;
+    assert_primary_price(deps.storage, &nft_contract_address, &token_id, &nft_info.extension, &minimum_bid)?;
+
+    // physically backed nfts may require a deposit protecting the buyer
+    let is_physical = nft_info.extension.num_real_repr.unwrap_or_default() > Uint128::zero();
+    let security_deposit = match &config_state.security_deposit {
+        Some(deposit) if is_physical => {
+            Some(SecurityDeposit {
+                seller: sender.clone(),
+                asset: deposit.amount.clone(),
+                arbiter: deps.api.addr_validate(&deposit.arbiter)?,
+                dispute_window_blocks: deposit.dispute_window_blocks,
+                buyer: None,
+                release_height: None,
+            })
+        }
+        _ => None,
+    };
+
+    let template = match template {
+        Some(name) => LISTING_TEMPLATES.load(deps.storage, (&sender, &name))?,
+        None => ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    };
+    assert_buy_now_floor(deps.as_ref(), &env, &nft_contract_address, &token_id, nft_info.extension.init_price, template.buy_now_price)?;
+    // the charity may have been revoked and the schedule removed since the template was saved
+    let charity = match &template.charity {
+        Some(charity) => Some(assert_charity(deps.as_ref(), charity)?),
+        None => None,
+    };
+    let increment_steps = match &template.increment_schedule {
+        Some(schedule) => increment_steps(deps.storage, schedule)?,
+        None => vec![],
+    };
+
+    let deposits: Vec<Asset> = security_deposit.iter()
+        .map(|deposit| deposit.asset.clone())
+        .chain(template.cancel_penalty.clone())
+        .collect();
+    assert_paid_all(&info, &deposits)?;
+    // the template duration was bounded when saved, the maximum may have been lowered since
+    let duration_blocks = template.duration_blocks
+        .unwrap_or(config_state.max_aution_duration_blocks)
+        .min(config_state.max_aution_duration_blocks);
+    if template.candle_window_blocks.is_some() && config_state.randomness_provider.is_none() {
+        return Err(ContractError::RandomnessProviderUnset {});
+    }
+    // absentee bids are only taken on nfts the contract already holds
+    if escrowless && template.start_delay_blocks.is_some() {
+        return Err(ContractError::InvalidListingTemplate {});
+    }
+    let start_height = env.block.height + template.start_delay_blocks.unwrap_or_default();
+    // the first bid on an escrowless listing may come late, and settlement later still
+    if escrowless {
+        let height = env.block.height + duration_blocks + config_state.approval_window_blocks;
+        if !contract_approved_until(deps.as_ref(), &env, &nft_contract_address, &token_id, height)? {
+            return Err(ContractError::ApprovalExpiresTooSoon { height });
+        }
+    }
+
+    // Each auction has a limit of max_aution_duration_blocks
+    let listing = Listing {
+        token_id: token_id.clone(),
+        contract_addr: nft_contract_address.clone(),
+        seller: sender,
+        decimals: asset_decimals(deps.as_ref(), &minimum_bid)?,
+        max_bid: minimum_bid,
+        max_bidder: env.contract.address.clone(),
+        block_limit: start_height + duration_blocks,
+        fee_override: active_fee_override(deps.as_ref(), &nft_contract_address, env.block.time)?,
+        levy: COLLECTION_LEVIES.may_load(deps.storage, nft_contract_address.as_str())?,
+        tags: vec![],
+        pending_escrow: escrowless,
+        min_increment: template.min_increment,
+        reserve_price: template.reserve_price,
+        payout_splits: template.payout_splits,
+        cancel_penalty: template.cancel_penalty,
+        buy_now_price: template.buy_now_price,
+        buy_now_lock: template.buy_now_lock,
+        candle_window: template.candle_window_blocks.map(|window| window.min(duration_blocks)),
+        candle_end: None,
+        max_bid_height: env.block.height,
+        extensions_used: 0,
+        extended_blocks: 0,
+        collection: nft_info.extension.nft_addr.clone(),
+        listed_by: listed_by.clone(),
+        charity,
+        buyer_premium: config_state.buyer_premium,
+        increment_steps,
+        start_height,
+    };
+    
+    let alias: String = ["AUCTION".to_string(), next_id(deps.storage, &LISTING_IDS)?.to_string()].join(".");
+
+    // hashed ids can be predicted from the token and its placement nonce, the
+    // numeric id stays available as an alias
+    let nonce = LISTING_NONCES.may_load(deps.storage, (&nft_contract_address, &token_id))?.unwrap_or_default();
+    let key = if config_state.hashed_listing_ids {
+        let key = hashed_listing_id(&nft_contract_address, &token_id, nonce);
+        LISTING_ALIASES.save(deps.storage, &alias, &key)?;
+        key
+    } else {
+        alias.clone()
+    };
+    LISTING_NONCES.save(deps.storage, (&nft_contract_address, &token_id), &(nonce + 1))?;
+
+    // save listing to store
+    store_listing(deps.storage, &key, &listing)?;
+    if let Some(remaining) = template.auto_relists {
+        AUTO_RELISTS.save(deps.storage, &key, &AutoRelist { minimum_bid: listing.max_bid.clone(), duration_blocks, remaining })?;
+    }
+    if custodied {
+        CUSTODY.remove(deps.storage, (nft_contract_address.as_str(), &token_id));
+    }
+    if let Some(deposit) = security_deposit {
+        store_security_deposit(deps.storage, &key, &deposit)?;
+    }
+
+    let events = watch_events(deps.as_ref(), "place_listing", &key, &listing)?;
+
+    let mut msgs = vec![];
+    // lock nft to contract, escrowless listings pull it with the first bid
+    if !escrowless && !custodied {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: nft_contract_address.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: String::from(env.contract.address.as_str()),
+                token_id: token_id.clone(),
+            })?,
+        }));
+    }
+
+    Ok(Response::new()
+        .add_attribute("place_listing", token_id)
+        .add_attribute("listing_id", key)
+        .add_attribute("listing_alias", alias)
+        .add_attribute("listing_nonce", nonce.to_string())
+        .add_attribute("escrowless", escrowless.to_string())
+        .add_attributes(listed_by.map(|launchpad| ("listed_by", launchpad)))
+        .add_events(events)
+        .add_messages(msgs))
+}
+
+// a registered launchpad lists for a seller it verified, the seller keeps the attribution and the proceeds
+pub fn execute_place_listing_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    seller: String,
+    params: PlaceListingParams,
+) -> Result<Response, ContractError> {
+    if !LAUNCHPADS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized{});
+    }
+    let seller = deps.api.addr_validate(&seller)?;
+    // the seller opts in by making the launchpad an operator of the token, an approval of this
+    // contract alone doesn't let a launchpad list it
+    let nft_addr = deps.api.addr_validate(&params.nft_addr)?;
+    assert_token_owner(deps.as_ref(), &nft_addr, &params.id, &info.sender, false)?;
+
+    let launchpad = info.sender.clone();
+    execute_place_listing(deps, env, info, params, seller, Some(launchpad))
+}
+
+// sha256 of the json array `[chain id, auction contract, intent]`, binding intents to one deployment
+pub fn intent_digest<T: Serialize>(chain_id: &str, contract: &Addr, intent: &T) -> StdResult<Vec<u8>> {
+    Ok(Sha256::digest(&to_vec(&(chain_id, contract, intent))?).to_vec())
+}
+
+// bech32 account address of a compressed secp256k1 key, ripemd160(sha256(pubkey)) as on cosmos sdk chains
+pub fn pubkey_address(prefix: &str, pubkey: &[u8]) -> Result<String, ContractError> {
+    if !matches!((pubkey.len(), pubkey.first()), (33, Some(2..=3))) {
+        return Err(ContractError::InvalidListingKey {});
+    }
+    let hash = Ripemd160::digest(&Sha256::digest(pubkey));
+    bech32::encode(prefix, hash.to_base32(), Variant::Bech32).map_err(|_| ContractError::InvalidListingKey {})
+}
+
+// fields of a relayed intent its signature is checked against
+trait SignedIntent: Serialize {
+    fn signer(&self) -> &str;
+    fn pubkey(&self) -> &Binary;
+    fn nonce(&self) -> u64;
+    fn expires_at(&self) -> u64;
+}
+
+impl SignedIntent for ListingIntent {
+    fn signer(&self) -> &str { &self.seller }
+    fn pubkey(&self) -> &Binary { &self.pubkey }
+    fn nonce(&self) -> u64 { self.nonce }
+    fn expires_at(&self) -> u64 { self.expires_at }
+}
+
+impl SignedIntent for BidIntent {
+    fn signer(&self) -> &str { &self.bidder }
+    fn pubkey(&self) -> &Binary { &self.pubkey }
+    fn nonce(&self) -> u64 { self.nonce }
+    fn expires_at(&self) -> u64 { self.expires_at }
+}
+
+// checks the intent was signed with the key of the signer's account, consumes its nonce and returns the signer
+fn verify_intent<T: SignedIntent>(deps: &mut DepsMut, env: &Env, intent: &T, signature: &Binary) -> Result<Addr, ContractError> {
+    let signer = deps.api.addr_validate(intent.signer())?;
+    let (prefix, _, _) = bech32::decode(signer.as_str()).map_err(|_| ContractError::SignerMismatch {})?;
+    if pubkey_address(&prefix, intent.pubkey())? != signer.as_str() {
+        return Err(ContractError::SignerMismatch {});
+    }
+
+    if env.block.height > intent.expires_at() {
+        return Err(ContractError::IntentExpired {});
+    }
+    let expected = INTENT_NONCES.may_load(deps.storage, &signer)?.unwrap_or(0);
+    if intent.nonce() != expected {
+        return Err(ContractError::InvalidIntentNonce { expected });
+    }
+
+    let digest = intent_digest(&env.block.chain_id, &env.contract.address, intent)?;
+    if !deps.api.secp256k1_verify(&digest, signature, intent.pubkey()).unwrap_or(false) {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    INTENT_NONCES.save(deps.storage, &signer, &(expected + 1))?;
+    Ok(signer)
+}
+
+pub fn execute_place_listing_with_sig(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    intent: ListingIntent,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let seller = verify_intent(&mut deps, &env, &intent, &signature)?;
+
+    let res = execute_place_listing(deps, env, info.clone(), intent.params, seller, None)?;
+    Ok(res.add_attribute("relayer", info.sender))
+}
+
+// the relayer pays `funds`, the signed bidder becomes the bidder of record
+pub fn execute_bid_on_behalf(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    intent: BidIntent,
+    signature: Binary,
+    funds: Asset,
+) -> Result<Response, ContractError> {
+    if funds != intent.bid_price {
+        return Err(ContractError::TokenMismatch {});
+    }
+
+    let bidder = verify_intent(&mut deps, &env, &intent, &signature)?;
+
+    let res = execute_bid_listing(deps, env, info.clone(), intent.listing_id, intent.bid_price, bidder, None)?;
+    Ok(res.add_attribute("relayer", info.sender))
+}
+
+pub fn execute_place_listings(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listings: Vec<PlaceListingParams>,
+) -> Result<Response, ContractError> {
+    if listings.is_empty() || listings.len() > MAX_BATCH_SIZE {
+        return Err(ContractError::InvalidBatchSize { max: MAX_BATCH_SIZE });
+    }
+
+    let config = read_config(deps.storage)?;
+    let mut deposits = vec![];
+    let mut res = Response::new();
+
+    for params in listings {
+        // every item gets its share of the sent security deposits and cancel penalties
+        let mut item_deposits = vec![];
+        if let Some(deposit) = &config.security_deposit {
+            let nft_info = query_nft_info(deps.as_ref(), params.id.clone(), params.nft_addr.clone())?;
+            if nft_info.extension.num_real_repr.unwrap_or_default() > Uint128::zero() {
+                item_deposits.push(deposit.amount.clone());
+            }
+        }
+        if let Some(name) = &params.template {
+            let template = LISTING_TEMPLATES.load(deps.storage, (&info.sender, name))?;
+            item_deposits.extend(template.cancel_penalty);
+        }
+        let funds = merge_assets(&item_deposits)?
+            .into_iter()
+            .filter_map(|asset| match asset.info {
+                AssetInfo::NativeToken { denom } => Some(Coin { denom, amount: asset.amount }),
+                AssetInfo::Token { .. } => None,
+            })
+            .collect();
+        deposits.extend(item_deposits);
+        let item_info = MessageInfo { sender: info.sender.clone(), funds };
+
+        let item = execute_place_listing(deps.branch(), env.clone(), item_info, params, info.sender.clone(), None)?;
+        res = res
+            .add_attributes(item.attributes)
+            .add_submessages(item.messages)
+            .add_events(item.events);
+    }
+
+    assert_paid_all(&info, &deposits)?;
+
+    Ok(res)
+}
+
+pub fn execute_buy_now(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    price: Asset,
+    buyer: Addr,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    assert_attested(deps.as_ref(), &buyer)?;
+
+    let mut listing = load_listing(deps.storage, &listing_id)?;
+    listing.with_premium(&price).assert_sent(&info)?;
+
+    if listing.block_limit < env.block.height {
+        return Err(ContractError::AuctionEnded {});
+    }
+
+    if !escrow_intact(deps.as_ref(), &env, &listing)? {
+        return Err(ContractError::EscrowBroken {});
+    }
+
+    // absentee bids open the auction first, an opening bid may lock buy-now
+    if env.block.height < listing.start_height {
+        return Err(ContractError::AuctionNotOpen { height: listing.start_height });
+    }
+    let opening = open_auction(deps.storage, &env, &listing_id, &mut listing)?;
+
+    let buy_now_price = listing
+        .buy_now_price(&env.contract.address)
+        .ok_or(ContractError::BuyNowUnavailable {})?;
+    if price.info != listing.max_bid.info {
+        return Err(ContractError::AssetInfoMismatch {});
+    }
+    if price.amount != buy_now_price {
+        return Err(ContractError::BuyNowPriceMismatch { price: buy_now_price });
+    }
+
+    remove_listing(deps.storage, &env.contract.address, &listing_id, &listing)?;
+
+    let mut msgs = vec![];
+    if let Some(penalty) = &listing.cancel_penalty {
+        msgs.push(penalty.clone().into_transfer_msg(listing.seller.clone())?);
+    }
+
+    // the outbought highest bidder is refunded
+    let mut submsgs = opening.messages;
+    submsgs.extend(refund_held_bids(deps.storage, &listing_id, &listing)?);
+    if listing.max_bidder != env.contract.address {
+        submsgs.extend(refund_submsg(deps.storage, &listing_id, &listing.max_bidder, &listing.with_premium(&listing.max_bid))?);
+    }
+
+    // an escrowless listing is transferred from the seller through the approval
+    listing.max_bid = price;
+    listing.max_bidder = buyer;
+    let events = watch_events(deps.as_ref(), "buy_now", &listing_id, &listing)?;
+
+    Ok(settle_sale(deps, &env, &listing_id, listing, None, SettlementLegs::Both)?
+        .add_attribute("buy_now", listing_id)
+        .add_messages(msgs)
+        .add_submessages(submsgs)
+        .add_events(events))
+}
+
+pub fn execute_withdraw_listing(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    // the seller settles any time, the winner once the auction ended and anyone else once the
+    // grace period after the end passed, collecting the settlement reward
+    let config = read_config(deps.storage)?;
+    let is_winner = info.sender == listing.max_bidder;
+    let settler = if info.sender == listing.seller || is_winner {
+        None
+    } else {
+        Some(info.sender.clone())
+    };
+    if is_winner && listing.block_limit >= env.block.height {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+    let open_height = listing.block_limit + config.settlement_grace_blocks;
+    if settler.is_some() && open_height >= env.block.height {
+        return Err(ContractError::SettlementNotOpen { height: open_height + 1 });
+    }
+    // the seller withdrawing takes the nft back instead of relisting it
+    if info.sender == listing.seller {
+        AUTO_RELISTS.remove(deps.storage, &listing_id);
+    }
+
+    close_listing(deps, env, listing_id, listing, settler, SettlementLegs::Both)
+}
+
+pub fn execute_claim_item(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+
+    // the seller already pulled the proceeds, only the nft is left
+    if let Some(item) = UNCLAIMED_ITEMS.may_load(deps.storage, &listing_id)? {
+        if info.sender != item.winner {
+            return Err(ContractError::Unauthorized {});
+        }
+        UNCLAIMED_ITEMS.remove(deps.storage, &listing_id);
+
+        let transfer_nft = delivery_submsg(deps.storage, &listing_id, CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: item.contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: item.winner.to_string(),
+                token_id: item.token_id,
+            })?,
+        }))?;
+        return Ok(Response::new()
+            .add_attribute("claim_item", listing_id)
+            .add_submessage(transfer_nft));
+    }
+
+    let listing = match may_load_listing(deps.storage, &listing_id)? {
+        Some(listing) => listing,
+        None if RECEIPTS.has(deps.storage, &listing_id) => return Err(ContractError::NothingToClaim { listing_id }),
+        None => load_listing(deps.storage, &listing_id)?,
+    };
+    if info.sender != listing.max_bidder {
+        return Err(ContractError::Unauthorized {});
+    }
+    if listing.block_limit >= env.block.height {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+
+    Ok(close_listing(deps, env, listing_id.clone(), listing, None, SettlementLegs::Item)?
+        .add_attribute("claim_item", listing_id))
+}
+
+pub fn execute_claim_proceeds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+
+    if let Some(listing) = may_load_listing(deps.storage, &listing_id)? {
+        if info.sender != listing.seller {
+            return Err(ContractError::Unauthorized {});
+        }
+        if listing.block_limit >= env.block.height {
+            return Err(ContractError::AuctionNotEnded {});
+        }
+        let reserve_met = listing.reserve_price.map_or(true, |reserve_price| listing.max_bid.amount >= reserve_price);
+        if listing.max_bidder == env.contract.address || !reserve_met {
+            return Err(ContractError::ListingNotSold {});
+        }
+
+        return Ok(close_listing(deps, env, listing_id.clone(), listing, None, SettlementLegs::Proceeds)?
+            .add_attribute("claim_proceeds", listing_id));
+    }
+
+    // the winner already pulled the nft, release the proceeds kept for the seller
+    let mut receipt = RECEIPTS.load(deps.storage, &listing_id)?;
+    if info.sender != receipt.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut msgs = vec![];
+    for payout in receipt.payouts.iter_mut() {
+        let escrow_id = match (&payout.status, payout.escrow_id) {
+            (PayoutStatus::Escrowed, Some(escrow_id)) if payout.recipient == receipt.seller => escrow_id,
+            _ => continue,
+        };
+        // proceeds already claimed through the escrow are skipped
+        if let Some(entry) = ESCROW.may_load(deps.storage, escrow_id)? {
+            remove_escrow(deps.storage, &entry)?;
+            msgs.push(entry.asset.into_transfer_msg(entry.recipient)?);
+        }
+        payout.status = PayoutStatus::Sent;
+        payout.escrow_id = None;
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToClaim { listing_id });
+    }
+    RECEIPTS.save(deps.storage, &listing_id, &receipt)?;
+
+    Ok(Response::new()
+        .add_attribute("claim_proceeds", listing_id)
+        .add_messages(msgs))
+}
+
+// the legs of a settlement pushed by it, a leg left out is pulled later with `ClaimItem` or `ClaimProceeds`
+#[derive(Clone, Copy, PartialEq)]
+enum SettlementLegs {
+    Both,
+    // the nft goes to the winner, the seller's proceeds are kept in escrow
+    Item,
+    // the seller's proceeds are paid out, the nft is kept for the winner
+    Proceeds,
+}
+
+// settles a listing with a winning bid and returns an unsold one to its seller
+fn close_listing(
+    deps: DepsMut,
+    env: Env,
+    listing_id: String,
+    mut listing: Listing,
+    settler: Option<Addr>,
+    legs: SettlementLegs,
+) -> Result<Response, ContractError> {
+    if listing.candle_window.is_some() && listing.candle_end.is_none() {
+        return Err(ContractError::CandleEndPending {});
+    }
+
+    // Check if the auction ended or not
+    // if listing.block_limit >= env.block.height {
+    //     return Err(ContractError::AuctionNotEnded {});
+    // }
+
+    let mut msgs = vec![];
+    let relist = AUTO_RELISTS.may_load(deps.storage, &listing_id)?;
+    // absentee bids open an auction that started without live bids and go back before the start
+    let absentee_refunds = if env.block.height >= listing.start_height {
+        open_auction(deps.storage, &env, &listing_id, &mut listing)?.messages
+    } else {
+        refund_held_bids(deps.storage, &listing_id, &listing)?
+    };
+    // remove listing from the store
+    remove_listing(deps.storage, &env.contract.address, &listing_id, &listing)?;
+
+    // the cancel penalty goes back unless the seller cancels after bids
+    if let Some(penalty) = &listing.cancel_penalty {
+        msgs.push(penalty.clone().into_transfer_msg(listing.seller.clone())?);
+    }
+
+    // a candle auction is won by the last bid up to its effective end
+    let mut held_refunds = absentee_refunds;
+    if let Some(candle_end) = listing.candle_end {
+        held_refunds.extend(resolve_candle_winner(deps.storage, &env, &listing_id, &mut listing, candle_end)?);
+    }
+
+    let events = watch_events(deps.as_ref(), "withdraw_listing", &listing_id, &listing)?;
+
+    // If noone has put a bid then then seller will be sent back with his NFT
+    // Transfer the locked NFT to highest bidder and bid amount to the seller
+    let has_bid = env.contract.address != listing.max_bidder;
+    let reserve_met = listing.reserve_price.map_or(true, |reserve_price| listing.max_bid.amount >= reserve_price);
+    if has_bid && reserve_met {
+        Ok(settle_sale(deps, &env, &listing_id, listing, settler.as_ref(), legs)?
+            .add_messages(msgs)
+            .add_submessages(held_refunds)
+            .add_events(events))
+    } else {
+        // a highest bid below the reserve price goes back to its bidder
+        let mut submsgs = held_refunds;
+        if has_bid {
+            submsgs.extend(refund_submsg(deps.storage, &listing_id, &listing.max_bidder, &listing.with_premium(&listing.max_bid))?);
+        }
+
+        // an ended auction with relists left runs again, the nft and deposits stay held
+        let config = read_config(deps.storage)?;
+        let ended = listing.block_limit < env.block.height;
+        let open = config.sunset_height.is_none() && !COLLECTION_PAUSES.has(deps.storage, listing.contract_addr.as_str());
+        if let Some(relist) = relist.filter(|relist| relist.remaining > 0 && ended && open && !listing.pending_escrow) {
+            return Ok(relist_listing(deps, &env, &listing_id, listing, relist)?
+                .add_submessages(submsgs)
+                .add_events(events));
+        }
+
+        if let Some(reserve_price) = listing.reserve_price.filter(|_| config.reveal_reserves && ended) {
+            REVEALED_RESERVES.save(deps.storage, &listing_id, &RevealedReserve {
+                reserve: Asset { info: listing.max_bid.info.clone(), amount: reserve_price },
+                max_bid: if has_bid { Some(listing.max_bid.amount) } else { None },
+                closed_height: env.block.height,
+            })?;
+        }
+
+        // nothing was sold, the deposit goes back with the nft
+        if let Some(deposit) = SECURITY_DEPOSITS.may_load(deps.storage, &listing_id)? {
+            remove_security_deposit(deps.storage, &listing_id, &deposit)?;
+            msgs.push(deposit.asset.into_transfer_msg(deposit.seller)?);
+        }
+
+        // an escrowless listing without bids never took the nft
+        if !listing.pending_escrow {
+            submsgs.push(delivery_submsg(deps.storage, &listing_id, CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: listing.contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&TransferNft {
+                    recipient: listing.seller.to_string(),
+                    token_id: listing.token_id.clone(),
+                })?,
+            }))?);
+        }
+
+        Ok(Response::new()
+            .add_attribute("listing_unsold", listing_id.to_string())
+            .add_messages(msgs)
+            .add_submessages(submsgs)
+            .add_events(events))
+    }
+}
+
+// starts an unsold listing over from its minimum bid for a fresh duration
+fn relist_listing(deps: DepsMut, env: &Env, listing_id: &str, mut listing: Listing, mut relist: AutoRelist) -> StdResult<Response> {
+    let max_duration = read_config(deps.storage)?.max_aution_duration_blocks;
+    listing.max_bid = relist.minimum_bid.clone();
+    listing.max_bidder = env.contract.address.clone();
+    listing.max_bid_height = env.block.height;
+    listing.block_limit = env.block.height + relist.duration_blocks.min(max_duration);
+    listing.candle_end = None;
+    listing.extensions_used = 0;
+    listing.extended_blocks = 0;
+    store_listing(deps.storage, listing_id, &listing)?;
+    for tag in listing.tags.iter() {
+        LISTINGS_BY_TAG.save(deps.storage, (tag, listing_id), &Empty {})?;
+    }
+
+    relist.remaining -= 1;
+    if relist.remaining > 0 {
+        AUTO_RELISTS.save(deps.storage, listing_id, &relist)?;
+    }
+    Ok(Response::new()
+        .add_attribute("listing_relisted", listing_id)
+        .add_attribute("block_limit", listing.block_limit.to_string())
+        .add_attribute("relists_left", relist.remaining.to_string()))
+}
+
+pub fn execute_cancel_listings(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    if listing_ids.is_empty() || listing_ids.len() > MAX_BATCH_SIZE {
+        return Err(ContractError::InvalidBatchSize { max: MAX_BATCH_SIZE });
+    }
+
+    let mut res = Response::new();
+    for listing_id in listing_ids {
+        let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+        let listing = load_listing(deps.storage, &listing_id)?;
+        // absentee bids count as bids once the auction started, before they go back
+        let lodged = env.block.height >= listing.start_height && has_absentee_bids(deps.storage, &listing_id);
+        let item = if listing.max_bidder == env.contract.address && !lodged {
+            // withdrawing a listing without bids returns the nft and deposit to the seller
+            execute_withdraw_listing(deps.branch(), env.clone(), info.clone(), listing_id.clone())?
+        } else if let Some(penalty) = listing.cancel_penalty.clone().filter(|_| !lodged) {
+            cancel_with_penalty(deps.branch(), &env, &info, &listing_id, listing, penalty)?
+        } else {
+            return Err(ContractError::ListingHasBids { listing_id });
+        };
+        res = res
+            .add_attribute("cancel_listing", listing_id)
+            .add_submessages(item.messages)
+            .add_events(item.events);
+    }
+
+    Ok(res)
+}
+
+// cleans up listings their sellers left in the contract after they ended without a sale
+pub fn execute_return_unsold(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.owner && Some(info.sender.to_string()) != config.listing_curator {
+        return Err(ContractError::Unauthorized{});
+    }
+    if listing_ids.is_empty() || listing_ids.len() > MAX_BATCH_SIZE {
+        return Err(ContractError::InvalidBatchSize { max: MAX_BATCH_SIZE });
+    }
+
+    let mut res = Response::new().add_attribute("cleanup_by", info.sender.to_string());
+    for listing_id in listing_ids {
+        let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+        let listing = load_listing(deps.storage, &listing_id)?;
+
+        let open_height = listing.block_limit + config.unsold_grace_blocks;
+        if open_height >= env.block.height {
+            return Err(ContractError::SettlementNotOpen { height: open_height + 1 });
+        }
+        // candle auctions holding bids and auctions with absentee bids may still have a winner
+        let has_bid = listing.max_bidder != env.contract.address;
+        let reserve_met = listing.reserve_price.map_or(true, |reserve_price| listing.max_bid.amount >= reserve_price);
+        if (has_bid && reserve_met) || has_held_bids(deps.storage, &listing_id) || has_absentee_bids(deps.storage, &listing_id) {
+            return Err(ContractError::ListingHasBids { listing_id });
+        }
+
+        AUTO_RELISTS.remove(deps.storage, &listing_id);
+        let item = close_listing(deps.branch(), env.clone(), listing_id.clone(), listing, None, SettlementLegs::Both)?;
+        res = res
+            .add_attribute("return_unsold", listing_id)
+            .add_submessages(item.messages)
+            .add_events(item.events);
+    }
+
+    Ok(res)
+}
+
+pub fn execute_begin_sunset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&config.owner)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized{});
+    }
+    assert_not_sunset(&config)?;
+
+    config.sunset_height = Some(env.block.height);
+    store_config_change(deps.storage, &env.block, &info.sender, &config)?;
+
+    Ok(Response::new().add_attribute("begin_sunset", env.block.height.to_string()))
+}
+
+// listings are wound down in id order and the crank waits for the first ones to end, no listing
+// is placed after the sunset so all of them end eventually
+pub fn execute_sunset_crank(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if read_config(deps.storage)?.sunset_height.is_none() {
+        return Err(ContractError::NotSunset {});
+    }
+    let limit = page_limit(limit);
+
+    // the stored listings lack their bid state, `load_listing` merges it in
+    let listings = AUCTIONS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .map(|listing_id| {
+            let listing_id = listing_id?;
+            let listing = load_listing(deps.storage, &listing_id)?;
+            Ok((listing_id, listing))
+        })
+        .collect::<StdResult<Vec<(String, Listing)>>>()?;
+
+    let mut res = Response::new();
+    for (listing_id, listing) in listings.iter() {
+        if listing.block_limit >= env.block.height {
+            return Err(ContractError::AuctionNotEnded {});
+        }
+
+        let item = void_listing(deps.storage, &env, listing_id, listing)?;
+        res = res
+            .add_attribute("sunset_listing", listing_id)
+            .add_submessages(item.messages);
+
+        // an escrowless listing without bids never took the nft
+        if !listing.pending_escrow {
+            res = res.add_submessage(delivery_submsg(deps.storage, listing_id, CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: listing.contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&TransferNft {
                     recipient: listing.seller.to_string(),
-                    token_id: listing_id.clone(),
+                    token_id: listing.token_id.clone(),
+                })?,
+            }))?);
+        }
+    }
+
+    // offers are refunded once no listing is left to wind down
+    let offers: Vec<Offer> = OFFERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit - listings.len())
+        .map(|item| item.map(|(_, offer)| offer))
+        .collect::<StdResult<_>>()?;
+    for offer in offers.iter() {
+        remove_offer(deps.storage, offer)?;
+        res = res
+            .add_attribute("sunset_offer", offer.id.to_string())
+            .add_message(offer.price.clone().into_transfer_msg(offer.buyer.clone())?);
+    }
+
+    Ok(res.add_attribute("sunset_crank", (listings.len() + offers.len()).to_string()))
+}
+
+fn assert_not_sunset(config: &Config) -> Result<(), ContractError> {
+    if config.sunset_height.is_some() {
+        return Err(ContractError::Sunset {});
+    }
+    Ok(())
+}
+
+// cancels a listing with bids: the bid is refunded and the penalty compensates the highest bidder
+fn cancel_with_penalty(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    listing_id: &str,
+    listing: Listing,
+    penalty: Asset,
+) -> Result<Response, ContractError> {
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let events = watch_events(deps.as_ref(), "cancel_listing", listing_id, &listing)?;
+    debit_ledger(deps.storage, LedgerBucket::Deposits, &penalty)?;
+    let compensation = refund_submsg(deps.storage, listing_id, &listing.max_bidder, &penalty)?;
+    let mut res = void_listing(deps.storage, env, listing_id, &Listing { cancel_penalty: None, ..listing.clone() })?
+        .add_attribute("cancel_penalty", penalty.to_string())
+        .add_submessages(compensation);
+
+    if !listing.pending_escrow {
+        res = res.add_submessage(delivery_submsg(deps.storage, listing_id, CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: listing.contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: listing.seller.to_string(),
+                token_id: listing.token_id.clone(),
+            })?,
+        }))?);
+    }
+
+    Ok(res.add_events(events))
+}
+
+pub fn execute_make_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: String,
+    price: Asset,
+    buyer: Addr,
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    assert_not_sunset(&read_config(deps.storage)?)?;
+
+    if price.amount.is_zero() {
+        return Err(ContractError::InsufficientFundsSend {});
+    }
+    price.assert_sent(&info)?;
+    assert_accepted_asset(deps.as_ref(), &price)?;
+    assert_attested(deps.as_ref(), &buyer)?;
+
+    let owner = query_owner_of(deps.as_ref(), &nft_addr, &token_id)?;
+    let owner_addr = deps.api.addr_validate(&owner.owner)?;
+
+    let offer = Offer {
+        id: next_id(deps.storage, &OFFER_IDS)?,
+        buyer,
+        nft_addr,
+        token_id,
+        price,
+    };
+
+    // an offer meeting the standing price of the owner settles right away, provided the contract
+    // can move the token, otherwise it waits like any other offer. It is held either way, settling
+    // removes it again
+    store_offer(deps.storage, &offer)?;
+    let auto_accept = read_auto_accept(deps.storage, offer.nft_addr.as_str(), &offer.token_id, &owner_addr)?
+        .map_or(false, |auto_accept| auto_accept.info == offer.price.info && offer.price.amount >= auto_accept.amount);
+    if auto_accept && contract_approved(deps.as_ref(), &env, &offer.nft_addr, &owner)? {
+        return Ok(settle_offer(deps, &env, offer, owner_addr)?
+            .add_attribute("auto_accepted", "true"));
+    }
+
+    Ok(Response::new()
+        .add_attribute("make_offer", offer.id.to_string())
+        .add_attribute("token_id", offer.token_id))
+}
+
+pub fn execute_cancel_offer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    offer_id: u64,
+) -> Result<Response, ContractError> {
+    let offer = OFFERS.load(deps.storage, offer_id)?;
+
+    if info.sender != offer.buyer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    remove_offer(deps.storage, &offer)?;
+
+    Ok(Response::new()
+        .add_attribute("cancel_offer", offer_id.to_string())
+        .add_message(offer.price.into_transfer_msg(offer.buyer)?))
+}
+
+pub fn execute_accept_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_id: u64,
+) -> Result<Response, ContractError> {
+    let offer = OFFERS.load(deps.storage, offer_id)?;
+
+    let owner = query_owner_of(deps.as_ref(), &offer.nft_addr, &offer.token_id)?;
+    if info.sender != owner.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !contract_approved(deps.as_ref(), &env, &offer.nft_addr, &owner)? {
+        return Err(ContractError::TransferNotApproved {});
+    }
+
+    settle_offer(deps, &env, offer, info.sender)
+}
+
+// sells the token of the owner to the buyer of the offer, settling it like an auction won at the offer price
+fn settle_offer(deps: DepsMut, env: &Env, offer: Offer, seller: Addr) -> Result<Response, ContractError> {
+    let nft_info = query_nft_info(deps.as_ref(), offer.token_id.clone(), offer.nft_addr.to_string())?;
+    if nft_info.is_listing {
+        return Err(ContractError::AlreadyOnAuction {});
+    }
+    if nft_info.extension.transferable == Some(false) {
+        return Err(ContractError::NonTransferable {});
+    }
+    assert_attested(deps.as_ref(), &seller)?;
+
+    remove_offer(deps.storage, &offer)?;
+
+    let listing_id = ["OFFER".to_string(), offer.id.to_string()].join(".");
+    let listing = Listing {
+        fee_override: active_fee_override(deps.as_ref(), &offer.nft_addr, env.block.time)?,
+        levy: COLLECTION_LEVIES.may_load(deps.storage, offer.nft_addr.as_str())?,
+        tags: vec![],
+        token_id: offer.token_id,
+        contract_addr: offer.nft_addr,
+        seller,
+        max_bid: offer.price,
+        max_bidder: offer.buyer,
+        block_limit: env.block.height,
+        pending_escrow: false,
+        min_increment: None,
+        reserve_price: None,
+        payout_splits: vec![],
+        decimals: None,
+        cancel_penalty: None,
+        buy_now_price: None,
+        buy_now_lock: None,
+        candle_window: None,
+        candle_end: None,
+        max_bid_height: env.block.height,
+        extensions_used: 0,
+        extended_blocks: 0,
+        collection: String::new(),
+        listed_by: None,
+        charity: None,
+        buyer_premium: None,
+        increment_steps: vec![],
+        start_height: 0,
+    };
+
+    Ok(settle_sale(deps, env, &listing_id, listing, None, SettlementLegs::Both)?
+        .add_attribute("accept_offer", offer.id.to_string()))
+}
+
+fn set_auto_accept(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: Option<String>,
+    price: Option<Asset>,
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+
+    if let Some(price) = &price {
+        assert_accepted_asset(deps.as_ref(), price)?;
+    }
+
+    match token_id {
+        Some(token_id) => {
+            let owner = query_owner_of(deps.as_ref(), &nft_addr, &token_id)?;
+            if info.sender != owner.owner {
+                return Err(ContractError::NotTokenOwner {});
+            }
+
+            match price {
+                Some(price) => TOKEN_AUTO_ACCEPT.save(deps.storage, (nft_addr.as_str(), &token_id), &AutoAccept {
+                    owner: info.sender.clone(),
+                    price,
                 })?,
-            }), 
-            ]))
+                None => TOKEN_AUTO_ACCEPT.remove(deps.storage, (nft_addr.as_str(), &token_id)),
+            }
+        }
+        None => match price {
+            Some(price) => COLLECTION_AUTO_ACCEPT.save(deps.storage, (nft_addr.as_str(), &info.sender), &price)?,
+            None => COLLECTION_AUTO_ACCEPT.remove(deps.storage, (nft_addr.as_str(), &info.sender)),
+        },
+    }
+
+    Ok(Response::new()
+        .add_attribute("set_auto_accept", nft_addr)
+        .add_attribute("owner", info.sender))
+}
+
+// transfers the nft to the highest bidder and pays out the bid, recording the sale
+// a third party settler is paid the settlement reward out of the platform fee
+fn settle_sale(
+    deps: DepsMut,
+    env: &Env,
+    listing_id: &str,
+    listing: Listing,
+    settler: Option<&Addr>,
+    legs: SettlementLegs,
+) -> Result<Response, ContractError> {
+    let listing_id = listing_id.to_string();
+    // transfer NFT to buyer
+    let transfer_nft = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: listing.contract_addr.to_string(),
+        funds: vec![],
+        msg: to_binary(&TransferNft {
+            recipient: listing.max_bidder.to_string(),
+            token_id: listing.token_id.clone(),
+        })?,
+    });
+
+    // distribute platform fee, royalties and the remain amount to the seller
+    let preview = settlement_preview(deps.as_ref(), &listing_id, &listing)?;
+
+    let config = read_config(deps.storage)?;
+    let royalty_status = if config.defer_royalties { PayoutStatus::Accrued } else { PayoutStatus::Sent };
+    let fee_status = if config.retain_fees { PayoutStatus::Retained } else { PayoutStatus::Sent };
+
+    let mut fee = preview.fee;
+    let reward = match (settler, config.settlement_reward_rate) {
+        (Some(settler), Some(reward_rate)) => {
+            let amount = payout_share(fee.asset.amount, reward_rate);
+            fee.asset.amount = fee.asset.amount.checked_sub(amount)?;
+            Some(PayoutPreview {
+                recipient: settler.clone(),
+                asset: Asset { info: fee.asset.info.clone(), amount },
+            })
+        }
+        _ => None,
+    };
+
+    // a winner pulling the nft leaves the seller's proceeds in escrow for `ClaimProceeds`
+    let proceeds_status = match legs {
+        SettlementLegs::Item if preview.seller_proceeds.recipient == listing.seller => PayoutStatus::Escrowed,
+        _ => PayoutStatus::Sent,
+    };
+    let others = preview.levy.clone()
+        .into_iter()
+        .chain(preview.payout_splits);
+    let mut payouts: Vec<Payout> = vec![(fee, fee_status.clone())]
+        .into_iter()
+        .chain(preview.buyer_premium.clone().map(|payout| (payout, fee_status)))
+        .chain(reward.map(|payout| (payout, PayoutStatus::Sent)))
+        .chain(preview.royalties.into_iter().map(|payout| (payout, royalty_status.clone())))
+        .chain(others.map(|payout| (payout, PayoutStatus::Sent)))
+        .chain(vec![(preview.seller_proceeds, proceeds_status)])
+        .map(|(payout, status)| Payout {
+            recipient: payout.recipient,
+            asset: payout.asset,
+            status,
+            escrow_id: None,
+        })
+        .collect();
+    payouts.retain(|payout| !payout.asset.amount.is_zero());
+
+    let mut submsgs = vec![];
+    for (index, payout) in payouts.iter_mut().enumerate() {
+        match payout.status {
+            PayoutStatus::Escrowed => {
+                let escrow_id = store_escrow(deps.storage, payout.recipient.clone(), payout.asset.clone(), listing_id.clone())?;
+                payout.escrow_id = Some(escrow_id);
+                continue;
+            }
+            PayoutStatus::Accrued => {
+                accrue_royalty(deps.storage, payout.recipient.as_str(), &payout.asset)?;
+                continue;
+            }
+            PayoutStatus::Retained => {
+                credit_ledger(deps.storage, LedgerBucket::Fees, &payout.asset)?;
+                continue;
+            }
+            _ => {}
+        }
+        let context = ReplyContext::Payout { listing_id: listing_id.clone(), index: index as u32 };
+        submsgs.push(transfer_submsg(deps.storage, context, &payout.asset, &payout.recipient)?);
+    }
+
+    add_seller_sale(deps.storage, &listing.seller, &listing.max_bid)?;
+    if let Some(rate) = POINTS_RATES.may_load(deps.storage, &listing.max_bid.info.to_string())? {
+        let points = listing.max_bid.amount * rate;
+        add_points(deps.storage, &listing.seller, points, env.block.height)?;
+        add_points(deps.storage, &listing.max_bidder, points, env.block.height)?;
+    }
+    add_token_sale(deps.storage, listing.contract_addr.as_str(), &listing.token_id)?;
+    append_provenance(deps.storage, listing.contract_addr.as_str(), &listing.token_id, &ProvenanceEvent::Sold {
+        listing_id: listing_id.clone(),
+        seller: listing.seller.clone(),
+        buyer: listing.max_bidder.clone(),
+        price: listing.max_bid.clone(),
+        block_height: env.block.height,
+    })?;
+
+    // the deposit stays locked until the dispute window after the sale closes
+    if let Some(mut deposit) = SECURITY_DEPOSITS.may_load(deps.storage, &listing_id)? {
+        deposit.buyer = Some(listing.max_bidder.clone());
+        deposit.release_height = Some(env.block.height + deposit.dispute_window_blocks);
+        SECURITY_DEPOSITS.save(deps.storage, &listing_id, &deposit)?;
+    }
+
+    #[cfg(feature = "ibc")]
+    let results_packet = match config.results_channel {
+        Some(channel_id) => Some(crate::ibc::settlement_packet_msg(env, channel_id, &crate::msg::SettlementPacket {
+            listing_id: listing_id.clone(),
+            token_id: listing.token_id.clone(),
+            contract_addr: listing.contract_addr.clone(),
+            winner: listing.max_bidder.clone(),
+            price: listing.max_bid.clone(),
+            block_height: env.block.height,
+        })?),
+        None => None,
+    };
+
+    // a seller pulling the proceeds leaves the nft with the contract for `ClaimItem`, an escrowless
+    // listing still has it with the seller and delivers it right away
+    let transfer_nft = if legs == SettlementLegs::Proceeds && !listing.pending_escrow {
+        UNCLAIMED_ITEMS.save(deps.storage, &listing_id, &UnclaimedItem {
+            contract_addr: listing.contract_addr.clone(),
+            token_id: listing.token_id.clone(),
+            winner: listing.max_bidder.clone(),
+        })?;
+        None
+    } else {
+        Some(delivery_submsg(deps.storage, &listing_id, transfer_nft)?)
+    };
+
+    store_receipt(deps.storage, &SettlementReceipt {
+        listing_id: listing_id.clone(),
+        token_id: listing.token_id,
+        contract_addr: listing.contract_addr,
+        seller: listing.seller,
+        winner: listing.max_bidder,
+        price: listing.max_bid,
+        block_height: env.block.height,
+        payouts,
+    })?;
+
+    let mut res = Response::new().add_attribute("listing_sold", listing_id);
+    if let Some(levy) = preview.levy {
+        res = res
+            .add_attribute("levy", levy.asset.amount.to_string())
+            .add_attribute("levy_recipient", levy.recipient);
+    }
+    #[cfg(feature = "ibc")]
+    if let Some(results_packet) = results_packet {
+        res = res.add_message(results_packet);
+    }
+    Ok(res
+        .add_submessages(transfer_nft)
+        .add_submessages(submsgs))
+}
+
+// splits the current highest bid of a listing into platform fee, royalties and seller proceeds
+pub fn settlement_preview(deps: Deps, listing_id: &str, listing: &Listing) -> StdResult<SettlementPreviewResponse> {
+    let config = read_config(deps.storage)?;
+    let price = &listing.max_bid;
+    let fee_collector = deps.api.addr_validate(config.fee_collector.as_ref().unwrap_or(&config.owner))?;
+
+    // primary sales only pay the platform fee, royalties start with the secondary market
+    let primary_sale = !has_been_sold(deps.storage, listing.contract_addr.as_str(), &listing.token_id)?;
+
+    // the buyer premium comes on top of the price and goes to the fee collector, also for charity listings
+    let buyer_premium = listing.buyer_premium.map(|_| PayoutPreview {
+        recipient: fee_collector.clone(),
+        asset: Asset { info: price.info.clone(), amount: listing.premium_on(price) },
+    });
+
+    // charity listings pass the whole price through to the charity
+    if let Some(charity) = &listing.charity {
+        return Ok(SettlementPreviewResponse {
+            listing_id: listing_id.to_string(),
+            price: price.clone(),
+            primary_sale,
+            fee_rate: Decimal::zero(),
+            membership_discount: false,
+            fee: PayoutPreview {
+                recipient: fee_collector,
+                asset: Asset { info: price.info.clone(), amount: Uint128::zero() },
+            },
+            royalties: vec![],
+            levy: None,
+            payout_splits: vec![],
+            seller_proceeds: PayoutPreview {
+                recipient: charity.clone(),
+                asset: price.clone(),
+            },
+            buyer_premium,
+        });
+    }
+
+    let seller_volume = read_seller_volume(deps.storage, &listing.seller, &price.info)?;
+    let fee_rate = match (listing.fee_override, config.primary_fee_rate) {
+        (Some(fee_override), _) => fee_override,
+        (None, Some(primary_fee_rate)) if primary_sale => primary_fee_rate,
+        _ => config.fee_rate(seller_volume),
+    };
+
+    // membership holders on either side of the sale get part of the fee waived
+    let membership_discount = match &config.membership {
+        Some(membership) => holds_membership(deps, membership, &listing.seller)?
+            || (listing.max_bidder != listing.seller && holds_membership(deps, membership, &listing.max_bidder)?),
+        None => false,
+    };
+    let fee_rate = match &config.membership {
+        Some(membership) if membership_discount => fee_rate * (Decimal::one() - membership.discount),
+        _ => fee_rate,
+    };
+    let fee_amount = payout_share(price.amount, fee_rate);
+    let mut remain_amount = price.amount.checked_sub(fee_amount)?;
+
+    let token_info: NftInfo<Metadata> = query_nft_info(deps, listing.token_id.clone(), listing.contract_addr.to_string())?;
+
+    // the fee, royalties and levy are each capped separately, deductions past the price are cut
+    // down to what is left so the sale still settles
+    let mut royalties = vec![];
+    let royalty_schedule = if primary_sale { vec![] } else { token_info.extension.royalties.unwrap_or_default() };
+    for royalty in royalty_schedule.iter() {
+        let amount = payout_share(price.amount, royalty.royalty_rate).min(remain_amount);
+        royalties.push(PayoutPreview {
+            recipient: if config.defer_royalties {
+                Addr::unchecked(&royalty.address)
+            } else {
+                deps.api.addr_validate(&royalty.address)?
+            },
+            asset: Asset { info: price.info.clone(), amount },
+        });
+
+        remain_amount = remain_amount.checked_sub(amount)?;
+    }
+
+    let levy = match &listing.levy {
+        Some(levy) => {
+            let amount = payout_share(price.amount, levy.rate).min(remain_amount);
+            remain_amount = remain_amount.checked_sub(amount)?;
+            Some(PayoutPreview {
+                recipient: levy.recipient.clone(),
+                asset: Asset { info: price.info.clone(), amount },
+            })
+        }
+        None => None,
+    };
+
+    let proceeds = remain_amount;
+    let mut payout_splits = vec![];
+    for split in listing.payout_splits.iter() {
+        let amount = payout_share(proceeds, split.share);
+        payout_splits.push(PayoutPreview {
+            recipient: deps.api.addr_validate(&split.address)?,
+            asset: Asset { info: price.info.clone(), amount },
+        });
+
+        remain_amount = remain_amount.checked_sub(amount)?;
+    }
+
+    Ok(SettlementPreviewResponse {
+        listing_id: listing_id.to_string(),
+        price: price.clone(),
+        primary_sale,
+        fee_rate,
+        membership_discount,
+        fee: PayoutPreview {
+            recipient: fee_collector,
+            asset: Asset { info: price.info.clone(), amount: fee_amount },
+        },
+        royalties,
+        levy,
+        payout_splits,
+        seller_proceeds: PayoutPreview {
+            recipient: listing.seller.clone(),
+            asset: Asset { info: price.info.clone(), amount: remain_amount },
+        },
+        buyer_premium,
+    })
+}
+
+// an nft leaving the contract on settlement, kept as a dead letter for a retry when the transfer fails
+// instead of reverting the settlement
+fn delivery_submsg(storage: &mut dyn Storage, listing_id: &str, msg: CosmosMsg) -> StdResult<SubMsg> {
+    let reply_id = next_reply_id(storage)?;
+    REPLIES.save(storage, reply_id, &ReplyContext::Delivery { listing_id: listing_id.to_string(), msg: msg.clone() })?;
+
+    Ok(SubMsg::reply_always(msg, reply_id))
+}
+
+// refunds and settlement payouts are dispatched as submessages so that a recipient rejecting
+// the transfer (a multisig, DAO or module account) gets it kept in the pull-based escrow instead
+// of reverting the whole transaction
+fn transfer_submsg(storage: &mut dyn Storage, context: ReplyContext, asset: &Asset, recipient: &Addr) -> StdResult<SubMsg> {
+    let reply_id = next_reply_id(storage)?;
+    REPLIES.save(storage, reply_id, &context)?;
+
+    Ok(SubMsg::reply_always(asset.clone().into_transfer_msg(recipient.clone())?, reply_id))
+}
+
+// refunds are pushed with a fallback to escrow, recipients that already failed to receive the asset,
+// like module accounts or vesting accounts locked for a denom, are escrowed without another attempt
+fn refund_submsg(storage: &mut dyn Storage, listing_id: &str, recipient: &Addr, asset: &Asset) -> StdResult<Option<SubMsg>> {
+    if UNDELIVERABLE.has(storage, (recipient, &asset.info.to_string())) {
+        store_escrow(storage, recipient.clone(), asset.clone(), listing_id.to_string())?;
+        return Ok(None);
+    }
+
+    transfer_submsg(storage, ReplyContext::Refund {
+        listing_id: listing_id.to_string(),
+        recipient: recipient.clone(),
+        asset: asset.clone(),
+    }, asset, recipient).map(Some)
+}
+
+// the seller must own the token or be an approved operator, escrowless listings keep it with the owner
+fn assert_token_owner(deps: Deps, nft_addr: &Addr, token_id: &str, seller: &Addr, escrowless: bool) -> Result<(), ContractError> {
+    let owner: OwnerOfResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::OwnerOf { token_id: token_id.to_string(), include_expired: None })?,
+    }))?;
+
+    if owner.owner == *seller {
+        return Ok(());
+    }
+    if escrowless {
+        return Err(ContractError::NotTokenOwner {});
+    }
+    if owner.approvals.iter().any(|approval| approval.spender == *seller) {
+        return Ok(());
+    }
+
+    let operators: OperatorsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::AllOperators {
+            owner: owner.owner,
+            include_expired: None,
+            start_after: None,
+            limit: None,
+        })?,
+    }))?;
+    if operators.operators.iter().any(|operator| operator.spender == *seller) {
+        return Ok(());
+    }
+
+    Err(ContractError::NotTokenOwner {})
+}
+
+// whether the nft of a listing is still in escrow, or for escrowless listings, still with the seller under
+// an unexpired approval of this contract
+fn escrow_intact(deps: Deps, env: &Env, listing: &Listing) -> StdResult<bool> {
+    Ok(escrow_report(deps, env, listing)?.intact)
+}
+
+// the current owner of the nft of a listing and whether it can still be delivered on settlement
+fn escrow_report(deps: Deps, env: &Env, listing: &Listing) -> StdResult<VerifyEscrowResponse> {
+    let mut report = VerifyEscrowResponse {
+        escrowless: listing.pending_escrow,
+        owner: None,
+        contract_approved: false,
+        intact: false,
+    };
+    let owner: OwnerOfResponse = match deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: listing.contract_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::OwnerOf { token_id: listing.token_id.clone(), include_expired: None })?,
+    })) {
+        Ok(owner) => owner,
+        // burned tokens can't settle either
+        Err(_) => return Ok(report),
+    };
+    report.owner = Some(deps.api.addr_validate(&owner.owner)?);
+
+    report.intact = if !listing.pending_escrow {
+        owner.owner == env.contract.address
+    } else if owner.owner != listing.seller {
+        false
+    } else {
+        report.contract_approved = contract_approved(deps, env, &listing.contract_addr, &owner)?;
+        report.contract_approved
+    };
+    Ok(report)
+}
+
+// whether this contract holds an unexpired approval for the token or over all tokens of its owner
+fn contract_approved(deps: Deps, env: &Env, nft_addr: &Addr, owner: &OwnerOfResponse) -> StdResult<bool> {
+    if owner.approvals.iter().any(|approval| approval.spender == env.contract.address) {
+        return Ok(true);
+    }
+
+    let operators: OperatorsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::AllOperators {
+            owner: owner.owner.clone(),
+            include_expired: None,
+            start_after: None,
+            limit: None,
+        })?,
+    }))?;
+    Ok(operators.operators.iter().any(|operator| operator.spender == env.contract.address))
+}
+
+// whether this contract holds an approval for the token or over all tokens of its owner lasting at least
+// up to the height, approvals expiring at a time can't be compared to heights and don't count
+fn contract_approved_until(deps: Deps, env: &Env, nft_addr: &Addr, token_id: &str, height: u64) -> StdResult<bool> {
+    let lasts = |expires: &Expiration| match expires {
+        Expiration::Never {} => true,
+        Expiration::AtHeight(expiry) => *expiry >= height,
+        Expiration::AtTime(_) => false,
+    };
+
+    let owner = query_owner_of(deps, nft_addr, token_id)?;
+    if owner.approvals.iter().any(|approval| approval.spender == env.contract.address && lasts(&approval.expires)) {
+        return Ok(true);
+    }
+
+    let operators: OperatorsResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::AllOperators {
+            owner: owner.owner,
+            include_expired: None,
+            start_after: None,
+            limit: None,
+        })?,
+    }))?;
+    Ok(operators.operators.iter().any(|operator| operator.spender == env.contract.address && lasts(&operator.expires)))
+}
+
+fn query_owner_of(deps: Deps, nft_addr: &Addr, token_id: &str) -> StdResult<OwnerOfResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::OwnerOf { token_id: token_id.to_string(), include_expired: None })?,
+    }))
+}
+
+// refunds the held bids of a candle auction and the absentee bids of an auction that never opened
+fn refund_held_bids(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<Vec<SubMsg>> {
+    let held_bids = take_held_bids(storage, listing_id, listing)?;
+    let absentee_bids = take_absentee_bids(storage, listing_id, listing)?;
+
+    held_bids
+        .into_iter()
+        .map(|held| (held.bidder, held.bid))
+        .chain(absentee_bids.into_iter().map(|absentee| (absentee.bidder, absentee.bid)))
+        .filter_map(|(bidder, bid)| refund_submsg(storage, listing_id, &bidder, &listing.with_premium(&bid)).transpose())
+        .collect()
+}
+
+// makes the last bid placed up to the effective end the highest bid, refunding all other bids
+fn resolve_candle_winner(
+    storage: &mut dyn Storage,
+    env: &Env,
+    listing_id: &str,
+    listing: &mut Listing,
+    candle_end: u64,
+) -> StdResult<Vec<SubMsg>> {
+    let mut bids = take_held_bids(storage, listing_id, listing)?;
+    if listing.max_bidder != env.contract.address {
+        bids.push(CandleBid { bidder: listing.max_bidder.clone(), bid: listing.max_bid.clone(), height: listing.max_bid_height });
+    }
+
+    let winner = bids.iter().rposition(|bid| bid.height <= candle_end);
+    let mut refunds = vec![];
+    for (index, bid) in bids.iter().enumerate() {
+        if Some(index) != winner {
+            refunds.extend(refund_submsg(storage, listing_id, &bid.bidder, &listing.with_premium(&bid.bid))?);
+        }
+    }
+
+    // without a bid before the effective end the listing goes unsold
+    match winner {
+        Some(index) => {
+            listing.max_bidder = bids[index].bidder.clone();
+            listing.max_bid = bids[index].bid.clone();
+        }
+        None => listing.max_bidder = env.contract.address.clone(),
+    }
+    Ok(refunds)
+}
+
+pub fn execute_request_candle_end(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    if listing.candle_window.is_none() {
+        return Err(ContractError::NotCandleAuction {});
+    }
+    // the randomness is drawn only once bidding closed
+    if listing.block_limit >= env.block.height {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+    if listing.candle_end.is_some() {
+        return Err(ContractError::CandleEndResolved {});
+    }
+
+    let randomness_provider = read_config(deps.storage)?
+        .randomness_provider
+        .ok_or(ContractError::RandomnessProviderUnset {})?;
+
+    Ok(Response::new()
+        .add_attribute("request_candle_end", listing_id.clone())
+        .add_message(WasmMsg::Execute {
+            contract_addr: randomness_provider,
+            funds: info.funds,
+            msg: to_binary(&RandomnessProviderMsg::RequestRandomness { job_id: listing_id })?,
+        }))
+}
+
+pub fn execute_receive_randomness(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    job_id: String,
+    randomness: Binary,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.randomness_provider.as_deref() != Some(info.sender.as_str()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(raffle_id) = job_id.strip_prefix("RAFFLE.") {
+        let raffle_id = raffle_id.parse::<u64>().map_err(|_| StdError::generic_err("invalid raffle job id"))?;
+        return settle_raffle(deps, &env, raffle_id, randomness);
+    }
+
+    let mut listing = load_listing(deps.storage, &job_id)?;
+    let window = listing.candle_window.ok_or(ContractError::NotCandleAuction {})?;
+    if listing.candle_end.is_some() {
+        return Err(ContractError::CandleEndResolved {});
+    }
+    if randomness.len() < 8 {
+        return Err(ContractError::InvalidRandomness {});
+    }
+
+    let candle_end = listing.block_limit - window + random_u64(&randomness)? % (window + 1);
+    listing.candle_end = Some(candle_end);
+    list_resolver(deps.storage).save(job_id.as_bytes(), &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("candle_end", candle_end.to_string())
+        .add_attribute("listing_id", job_id))
+}
+
+fn random_u64(randomness: &Binary) -> Result<u64, ContractError> {
+    if randomness.len() < 8 {
+        return Err(ContractError::InvalidRandomness {});
+    }
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&randomness.as_slice()[..8]);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_raffle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: String,
+    ticket_price: Asset,
+    duration_blocks: u64,
+    refund_losers: bool,
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    assert_collection_open(deps.as_ref(), &nft_addr)?;
+    let config = read_config(deps.storage)?;
+    assert_not_sunset(&config)?;
+
+    if ticket_price.amount.is_zero() || duration_blocks == 0 || duration_blocks > config.max_aution_duration_blocks {
+        return Err(ContractError::InvalidRaffle {});
+    }
+    if config.randomness_provider.is_none() {
+        return Err(ContractError::RandomnessProviderUnset {});
+    }
+
+    let nft_info = query_nft_info(deps.as_ref(), token_id.clone(), nft_addr.to_string())?;
+    assert_token_owner(deps.as_ref(), &nft_addr, &token_id, &info.sender, false)?;
+    if nft_info.is_listing {
+        return Err(ContractError::AlreadyOnAuction {});
+    }
+    if nft_info.extension.transferable == Some(false) {
+        return Err(ContractError::NonTransferable {});
+    }
+    assert_accepted_asset(deps.as_ref(), &ticket_price)?;
+    assert_attested(deps.as_ref(), &info.sender)?;
+
+    let raffle = Raffle {
+        id: next_raffle_id(deps.storage)?,
+        seller: info.sender,
+        nft_addr,
+        token_id,
+        ticket_price,
+        end_height: env.block.height + duration_blocks,
+        refund_losers,
+        tickets_sold: 0,
+        draw_requested: None,
+    };
+    RAFFLES.save(deps.storage, raffle.id, &raffle)?;
+
+    Ok(Response::new()
+        .add_attribute("create_raffle", raffle.id.to_string())
+        .add_message(WasmMsg::Execute {
+            contract_addr: raffle.nft_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: env.contract.address.to_string(),
+                token_id: raffle.token_id,
+            })?,
+        }))
+}
+
+// price of count tickets of a raffle
+fn read_raffle_payment(storage: &dyn Storage, raffle_id: u64, count: u32) -> StdResult<Asset> {
+    let raffle = RAFFLES.load(storage, raffle_id)?;
+    Ok(Asset {
+        info: raffle.ticket_price.info,
+        amount: raffle.ticket_price.amount.checked_mul(Uint128::from(count))?,
+    })
+}
+
+pub fn execute_buy_tickets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    raffle_id: u64,
+    payment: Asset,
+    buyer: Addr,
+) -> Result<Response, ContractError> {
+    payment.assert_sent(&info)?;
+    assert_attested(deps.as_ref(), &buyer)?;
+
+    let mut raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    if raffle.end_height < env.block.height {
+        return Err(ContractError::AuctionEnded {});
+    }
+
+    let ticket_price = raffle.ticket_price.amount;
+    let count = u32::try_from((payment.amount / ticket_price).u128()).unwrap_or_default();
+    if payment.info != raffle.ticket_price.info || count == 0 || !(payment.amount % ticket_price).is_zero() {
+        return Err(ContractError::InvalidTicketPayment { ticket_price });
+    }
+
+    RAFFLE_TICKETS.save(deps.storage, (raffle_id, raffle.tickets_sold), &TicketPurchase { buyer: buyer.clone(), count })?;
+    credit_ledger(deps.storage, LedgerBucket::Bids, &payment)?;
+    raffle.tickets_sold = raffle.tickets_sold.checked_add(count).ok_or(ContractError::InvalidTicketPayment { ticket_price })?;
+    RAFFLES.save(deps.storage, raffle_id, &raffle)?;
+
+    Ok(Response::new()
+        .add_attribute("buy_tickets", raffle_id.to_string())
+        .add_attribute("buyer", buyer)
+        .add_attribute("count", count.to_string()))
+}
+
+pub fn execute_draw_raffle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    raffle_id: u64,
+) -> Result<Response, ContractError> {
+    let mut raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    if raffle.end_height >= env.block.height {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+    if raffle.draw_requested.is_some() {
+        return Err(ContractError::RaffleDrawPending {});
+    }
+
+    // nobody took part, the nft goes back to the seller
+    if raffle.tickets_sold == 0 {
+        RAFFLES.remove(deps.storage, raffle_id);
+        let return_nft = delivery_submsg(deps.storage, &format!("RAFFLE.{}", raffle_id), CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: raffle.nft_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: raffle.seller.to_string(),
+                token_id: raffle.token_id,
+            })?,
+        }))?;
+        return Ok(Response::new()
+            .add_attribute("raffle_unsold", raffle_id.to_string())
+            .add_submessage(return_nft));
+    }
+
+    let randomness_provider = read_config(deps.storage)?
+        .randomness_provider
+        .ok_or(ContractError::RandomnessProviderUnset {})?;
+    raffle.draw_requested = Some(env.block.height);
+    RAFFLES.save(deps.storage, raffle_id, &raffle)?;
+
+    Ok(Response::new()
+        .add_attribute("draw_raffle", raffle_id.to_string())
+        .add_message(WasmMsg::Execute {
+            contract_addr: randomness_provider,
+            funds: info.funds,
+            msg: to_binary(&RandomnessProviderMsg::RequestRandomness { job_id: format!("RAFFLE.{}", raffle_id) })?,
+        }))
+}
+
+pub fn execute_void_raffle(
+    deps: DepsMut,
+    env: Env,
+    raffle_id: u64,
+) -> Result<Response, ContractError> {
+    let raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    let deadline = raffle.draw_requested.unwrap_or(raffle.end_height) + RAFFLE_DRAW_TIMEOUT_BLOCKS;
+    if env.block.height <= deadline {
+        return Err(ContractError::RaffleDrawNotExpired { height: deadline });
+    }
+
+    let listing_id = format!("RAFFLE.{}", raffle_id);
+    let purchases: Vec<(u32, TicketPurchase)> = RAFFLE_TICKETS
+        .prefix(raffle_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    RAFFLES.remove(deps.storage, raffle_id);
+    debit_ledger(deps.storage, LedgerBucket::Bids, &Asset {
+        info: raffle.ticket_price.info.clone(),
+        amount: raffle.ticket_price.amount * Uint128::from(raffle.tickets_sold),
+    })?;
+
+    let mut tickets: BTreeMap<Addr, u32> = BTreeMap::new();
+    for (first_ticket, purchase) in purchases {
+        RAFFLE_TICKETS.remove(deps.storage, (raffle_id, first_ticket));
+        *tickets.entry(purchase.buyer).or_default() += purchase.count;
+    }
+    let mut submsgs = vec![];
+    for (buyer, count) in tickets {
+        let refund = Asset {
+            info: raffle.ticket_price.info.clone(),
+            amount: raffle.ticket_price.amount * Uint128::from(count),
+        };
+        submsgs.extend(refund_submsg(deps.storage, &listing_id, &buyer, &refund)?);
+    }
+    submsgs.push(delivery_submsg(deps.storage, &listing_id, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: raffle.nft_addr.to_string(),
+        funds: vec![],
+        msg: to_binary(&TransferNft {
+            recipient: raffle.seller.to_string(),
+            token_id: raffle.token_id,
+        })?,
+    }))?);
+
+    Ok(Response::new()
+        .add_attribute("void_raffle", raffle_id.to_string())
+        .add_submessages(submsgs))
+}
+
+// gives the nft to the drawn ticket, settling the winner's tickets, or all tickets unless the
+// losers are refunded, like an auction sale
+fn settle_raffle(deps: DepsMut, env: &Env, raffle_id: u64, randomness: Binary) -> Result<Response, ContractError> {
+    let raffle = RAFFLES.load(deps.storage, raffle_id)?;
+    let listing_id = format!("RAFFLE.{}", raffle_id);
+    let ticket = (random_u64(&randomness)? % u64::from(raffle.tickets_sold)) as u32;
+    let winner = read_ticket_owner(deps.storage, raffle_id, ticket)?;
+
+    let purchases: Vec<(u32, TicketPurchase)> = RAFFLE_TICKETS
+        .prefix(raffle_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    RAFFLES.remove(deps.storage, raffle_id);
+    debit_ledger(deps.storage, LedgerBucket::Bids, &Asset {
+        info: raffle.ticket_price.info.clone(),
+        amount: raffle.ticket_price.amount * Uint128::from(raffle.tickets_sold),
+    })?;
+
+    let mut tickets: BTreeMap<Addr, u32> = BTreeMap::new();
+    for (first_ticket, purchase) in purchases {
+        RAFFLE_TICKETS.remove(deps.storage, (raffle_id, first_ticket));
+        *tickets.entry(purchase.buyer).or_default() += purchase.count;
+    }
+
+    let mut submsgs = vec![];
+    let mut sold_tickets = raffle.tickets_sold;
+    if raffle.refund_losers {
+        sold_tickets = tickets[&winner];
+        for (buyer, count) in tickets.into_iter().filter(|(buyer, _)| *buyer != winner) {
+            let refund = Asset {
+                info: raffle.ticket_price.info.clone(),
+                amount: raffle.ticket_price.amount * Uint128::from(count),
+            };
+            submsgs.extend(refund_submsg(deps.storage, &listing_id, &buyer, &refund)?);
+        }
+    }
+
+    let listing = Listing {
+        fee_override: active_fee_override(deps.as_ref(), &raffle.nft_addr, env.block.time)?,
+        levy: COLLECTION_LEVIES.may_load(deps.storage, raffle.nft_addr.as_str())?,
+        tags: vec![],
+        token_id: raffle.token_id,
+        contract_addr: raffle.nft_addr,
+        seller: raffle.seller,
+        max_bid: Asset {
+            info: raffle.ticket_price.info,
+            amount: raffle.ticket_price.amount * Uint128::from(sold_tickets),
+        },
+        max_bidder: winner,
+        block_limit: raffle.end_height,
+        pending_escrow: false,
+        min_increment: None,
+        reserve_price: None,
+        payout_splits: vec![],
+        decimals: None,
+        cancel_penalty: None,
+        buy_now_price: None,
+        buy_now_lock: None,
+        candle_window: None,
+        candle_end: None,
+        max_bid_height: env.block.height,
+        extensions_used: 0,
+        extended_blocks: 0,
+        collection: String::new(),
+        listed_by: None,
+        charity: None,
+        buyer_premium: None,
+        increment_steps: vec![],
+        start_height: 0,
+    };
+
+    Ok(settle_sale(deps, env, &listing_id, listing, None, SettlementLegs::Both)?
+        .add_attribute("winning_ticket", ticket.to_string())
+        .add_submessages(submsgs))
+}
+
+// removes a listing that can't settle, returning the deposit to the seller and the highest bid to its bidder
+fn void_listing(storage: &mut dyn Storage, env: &Env, listing_id: &str, listing: &Listing) -> StdResult<Response> {
+    remove_listing(storage, &env.contract.address, listing_id, listing)?;
+
+    let mut msgs = vec![];
+    if let Some(deposit) = SECURITY_DEPOSITS.may_load(storage, listing_id)? {
+        remove_security_deposit(storage, listing_id, &deposit)?;
+        msgs.push(deposit.asset.into_transfer_msg(deposit.seller)?);
+    }
+    if let Some(penalty) = &listing.cancel_penalty {
+        msgs.push(penalty.clone().into_transfer_msg(listing.seller.clone())?);
+    }
+
+    let mut submsgs = refund_held_bids(storage, listing_id, listing)?;
+    if listing.max_bidder != env.contract.address {
+        submsgs.extend(refund_submsg(storage, listing_id, &listing.max_bidder, &listing.with_premium(&listing.max_bid))?);
+    }
+
+    Ok(Response::new()
+        .add_attribute("listing_voided", listing_id)
+        .add_messages(msgs)
+        .add_submessages(submsgs))
+}
+
+pub fn execute_set_listing_operator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    operator: Option<String>,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let operator = operator.map(|operator| deps.api.addr_validate(&operator)).transpose()?;
+    match &operator {
+        Some(operator) => LISTING_OPERATORS.save(deps.storage, &listing_id, operator)?,
+        None => LISTING_OPERATORS.remove(deps.storage, &listing_id),
+    }
+
+    Ok(Response::new()
+        .add_attribute("set_listing_operator", listing_id)
+        .add_attribute("operator", operator.map_or_else(|| String::from("none"), String::from)))
+}
+
+pub fn execute_set_listing_tags(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    tags: Vec<String>,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let mut listing = load_listing(deps.storage, &listing_id)?;
+
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut tags = tags;
+    tags.sort();
+    tags.dedup();
+    let valid_tag = |tag: &String| !tag.is_empty()
+        && tag.len() <= MAX_TAG_LENGTH
+        && tag.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if tags.len() > MAX_LISTING_TAGS || !tags.iter().all(valid_tag) {
+        return Err(ContractError::InvalidTags { max: MAX_LISTING_TAGS, max_length: MAX_TAG_LENGTH });
+    }
+
+    for tag in listing.tags.iter() {
+        LISTINGS_BY_TAG.remove(deps.storage, (tag, &listing_id));
+    }
+    for tag in tags.iter() {
+        LISTINGS_BY_TAG.save(deps.storage, (tag, &listing_id), &Empty {})?;
+    }
+    listing.tags = tags;
+    list_resolver(deps.storage).save(listing_id.as_bytes(), &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("set_listing_tags", listing_id)
+        .add_attribute("tags", listing.tags.join(",")))
+}
+
+pub fn execute_transfer_listing(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+    new_seller: Option<String>,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let new_seller = match new_seller {
+        Some(new_seller) => deps.api.addr_validate(&new_seller)?,
+        None => {
+            LISTING_TRANSFERS.remove(deps.storage, &listing_id);
+            return Ok(Response::new()
+                .add_attribute("transfer_listing", listing_id)
+                .add_attribute("new_seller", "none"));
+        }
+    };
+    assert_transferable_listing(deps.as_ref(), &env, &listing_id, &listing)?;
+
+    LISTING_TRANSFERS.save(deps.storage, &listing_id, &new_seller)?;
+    Ok(Response::new()
+        .add_attribute("transfer_listing", listing_id)
+        .add_attribute("new_seller", new_seller))
+}
+
+pub fn execute_accept_listing_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    if LISTING_TRANSFERS.may_load(deps.storage, &listing_id)? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    // bids may have come in since the transfer was offered
+    assert_transferable_listing(deps.as_ref(), &env, &listing_id, &listing)?;
+    if let Some(max) = read_config(deps.storage)?.max_active_listings {
+        if ACTIVE_LISTINGS.may_load(deps.storage, &info.sender)?.unwrap_or_default() >= max {
+            return Err(ContractError::TooManyActiveListings { max });
+        }
+    }
+
+    let previous_seller = listing.seller.clone();
+    change_listing_seller(deps.storage, &listing_id, listing, info.sender.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("accept_listing_transfer", listing_id)
+        .add_attribute("previous_seller", previous_seller)
+        .add_attribute("seller", info.sender))
+}
+
+// only running listings holding their nft and no bid change hands
+fn assert_transferable_listing(deps: Deps, env: &Env, listing_id: &str, listing: &Listing) -> Result<(), ContractError> {
+    if listing.block_limit < env.block.height {
+        return Err(ContractError::AuctionEnded {});
+    }
+    if listing.pending_escrow {
+        return Err(ContractError::EscrowlessTransfer {});
+    }
+    if listing.max_bidder != env.contract.address || has_held_bids(deps.storage, listing_id) || has_absentee_bids(deps.storage, listing_id) {
+        return Err(ContractError::ListingHasBids { listing_id: listing_id.to_string() });
+    }
+    Ok(())
+}
+
+pub fn execute_invalidate_listing(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    listing_id: String,
+) -> Result<Response, ContractError> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    if escrow_intact(deps.as_ref(), &env, &listing)? {
+        return Err(ContractError::EscrowIntact {});
+    }
+
+    let events = watch_events(deps.as_ref(), "invalidate_listing", &listing_id, &listing)?;
+
+    Ok(void_listing(deps.storage, &env, &listing_id, &listing)?
+        .add_events(events))
+}
+
+pub fn execute_claim_royalties(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let royalties = read_accrued_royalties(deps.storage, info.sender.as_str())?;
+    if royalties.is_empty() {
+        return Err(ContractError::NoAccruedRoyalties {});
+    }
+
+    let mut msgs = vec![];
+    for royalty in royalties {
+        ROYALTY_LEDGER.remove(deps.storage, (info.sender.as_str(), &royalty.info.to_string()));
+        debit_ledger(deps.storage, LedgerBucket::Royalties, &royalty)?;
+        msgs.push(royalty.into_transfer_msg(info.sender.clone())?);
+    }
+
+    Ok(Response::new()
+        .add_attribute("claim_royalties", info.sender)
+        .add_messages(msgs))
+}
+
+// only the fees bucket can be withdrawn, bids and other escrowed funds stay untouched
+pub fn execute_withdraw_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    assert_fee_manager(deps.as_ref(), &info.sender)?;
+    asset.info.validate(deps.api)?;
+
+    let available = read_ledger(deps.storage, &asset.info, LedgerBucket::Fees)?;
+    if asset.amount.is_zero() || asset.amount > available {
+        return Err(ContractError::InsufficientFees { available });
+    }
+    debit_ledger(deps.storage, LedgerBucket::Fees, &asset)?;
+
+    let config = read_config(deps.storage)?;
+    let fee_collector = deps.api.addr_validate(config.fee_collector.as_ref().unwrap_or(&config.owner))?;
+
+    Ok(Response::new()
+        .add_attribute("withdraw_fees", asset.amount.to_string())
+        .add_attribute("fee_collector", fee_collector.to_string())
+        .add_message(asset.into_transfer_msg(fee_collector)?))
+}
+
+pub fn execute_claim_escrow(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    escrow_id: u64,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let entry = ESCROW.load(deps.storage, escrow_id)?;
+
+    if info.sender != entry.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => entry.recipient.clone(),
+    };
+
+    remove_escrow(deps.storage, &entry)?;
+    // a claim only lands if the transfer does, a recipient claiming to itself takes deliveries again
+    if recipient == entry.recipient {
+        UNDELIVERABLE.remove(deps.storage, (&entry.recipient, &entry.asset.info.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_attribute("claim_escrow", escrow_id.to_string())
+        .add_attribute("listing_id", entry.listing_id)
+        .add_attribute("recipient", recipient.to_string())
+        .add_message(entry.asset.into_transfer_msg(recipient)?))
+}
+
+pub fn execute_quarantine_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the sender of the callback is the nft contract
+    let sender = deps.api.addr_validate(&msg.sender)?;
+    QUARANTINE.save(deps.storage, (info.sender.as_str(), &msg.token_id), &QuarantinedNft {
+        sender: sender.clone(),
+        block_height: env.block.height,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("quarantine_nft", msg.token_id)
+        .add_attribute("nft_addr", info.sender)
+        .add_attribute("sender", sender))
+}
+
+pub fn execute_reclaim_quarantined(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    let entry = QUARANTINE
+        .may_load(deps.storage, (nft_addr.as_str(), &token_id))?
+        .ok_or(ContractError::NotQuarantined {})?;
+
+    if info.sender != entry.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    QUARANTINE.remove(deps.storage, (nft_addr.as_str(), &token_id));
+
+    Ok(Response::new()
+        .add_attribute("reclaim_quarantined", token_id.clone())
+        .add_attribute("nft_addr", nft_addr.to_string())
+        .add_message(WasmMsg::Execute {
+            contract_addr: nft_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: entry.sender.to_string(),
+                token_id,
+            })?,
+        }))
+}
+
+pub fn execute_release_custody(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_addr: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    let entry = CUSTODY
+        .may_load(deps.storage, (nft_addr.as_str(), &token_id))?
+        .ok_or(ContractError::NotInCustody {})?;
+
+    if info.sender != entry.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CUSTODY.remove(deps.storage, (nft_addr.as_str(), &token_id));
+
+    Ok(Response::new()
+        .add_attribute("release_custody", token_id.clone())
+        .add_attribute("nft_addr", nft_addr.to_string())
+        .add_message(WasmMsg::Execute {
+            contract_addr: nft_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&TransferNft {
+                recipient: entry.owner.to_string(),
+                token_id,
+            })?,
+        }))
+}
+
+pub fn execute_retry_refund(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    escrow_id: u64,
+) -> Result<Response, ContractError> {
+    let mut entry = ESCROW.load(deps.storage, escrow_id)?;
+
+    if entry.status == EscrowStatus::ClaimOnly {
+        return Err(ContractError::ClaimOnly {});
+    }
+
+    entry.attempts += 1;
+    ESCROW.save(deps.storage, escrow_id, &entry)?;
+
+    let msg = transfer_submsg(deps.storage, ReplyContext::Retry { escrow_id }, &entry.asset, &entry.recipient)?;
+
+    Ok(Response::new()
+        .add_attribute("retry_refund", escrow_id.to_string())
+        .add_attribute("attempt", entry.attempts.to_string())
+        .add_submessage(msg))
+}
+
+pub fn execute_retry_dead_letter(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut dead_letter = DEAD_LETTERS.load(deps.storage, id)?;
+
+    dead_letter.attempts += 1;
+    DEAD_LETTERS.save(deps.storage, id, &dead_letter)?;
+
+    let reply_id = next_reply_id(deps.storage)?;
+    REPLIES.save(deps.storage, reply_id, &ReplyContext::RetryDeadLetter { dead_letter_id: id })?;
+
+    Ok(Response::new()
+        .add_attribute("retry_dead_letter", id.to_string())
+        .add_attribute("attempt", dead_letter.attempts.to_string())
+        .add_submessage(SubMsg::reply_always(dead_letter.msg, reply_id)))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let res = handle_reply(deps.branch(), env.clone(), msg)?;
+    let res = fund_from_yield(deps.branch(), &env, res)?;
+
+    #[cfg(feature = "invariants")]
+    crate::invariants::assert_invariants(deps.as_ref(), &env)?;
+
+    Ok(res)
+}
+
+fn handle_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let context = REPLIES
+        .may_load(deps.storage, msg.id)?
+        .ok_or(ContractError::UnknownReplyId { id: msg.id })?;
+    REPLIES.remove(deps.storage, msg.id);
+
+    match (context, msg.result.into_result()) {
+        (ReplyContext::Retry { escrow_id }, Ok(_)) => {
+            let entry = ESCROW.load(deps.storage, escrow_id)?;
+            remove_escrow(deps.storage, &entry)?;
+            UNDELIVERABLE.remove(deps.storage, (&entry.recipient, &entry.asset.info.to_string()));
+
+            Ok(Response::new().add_attribute("escrow_delivered", escrow_id.to_string()))
+        }
+        (ReplyContext::RetryDeadLetter { dead_letter_id }, Ok(_)) => {
+            DEAD_LETTERS.remove(deps.storage, dead_letter_id);
+
+            Ok(Response::new().add_attribute("dead_letter_delivered", dead_letter_id.to_string()))
+        }
+        (ReplyContext::YieldDeposit { denom, amount }, Ok(_)) => {
+            let principal = YIELD_DEPOSITS.may_load(deps.storage, &denom)?.unwrap_or_default().checked_add(amount)?;
+            YIELD_DEPOSITS.save(deps.storage, &denom, &principal)?;
+
+            Ok(Response::new().add_attribute("yield_deposited", amount.to_string()))
+        }
+        (ReplyContext::YieldWithdrawal { denom, amount, principal }, Ok(_)) => {
+            match YIELD_DEPOSITS.may_load(deps.storage, &denom)?.unwrap_or_default().checked_sub(principal)? {
+                deposits if deposits.is_zero() => YIELD_DEPOSITS.remove(deps.storage, &denom),
+                deposits => YIELD_DEPOSITS.save(deps.storage, &denom, &deposits)?,
+            }
+            // the yield offsets platform fees, a loss of the vault shows as a ledger above the balance
+            let accrued = amount.saturating_sub(principal);
+            if !accrued.is_zero() {
+                credit_ledger(deps.storage, LedgerBucket::Fees, &Asset { info: AssetInfo::NativeToken { denom }, amount: accrued })?;
+            }
+
+            Ok(Response::new()
+                .add_attribute("yield_withdrawn", amount.to_string())
+                .add_attribute("yield_accrued", accrued.to_string()))
+        }
+        (ReplyContext::YieldExit { denom, principal }, Ok(_)) => {
+            YIELD_DEPOSITS.remove(deps.storage, &denom);
+
+            Ok(Response::new().add_attribute("yield_withdrawn", principal.to_string()))
+        }
+        (ReplyContext::YieldExit { denom, principal }, Err(err)) => {
+            // a loss of the vault shows as a ledger above the balance
+            YIELD_DEPOSITS.remove(deps.storage, &denom);
+
+            Ok(Response::new()
+                .add_attribute("yield_written_off", principal.to_string())
+                .add_attribute("error", err))
+        }
+        (_, Ok(_)) => Ok(Response::default()),
+        (ReplyContext::YieldDeposit { .. }, Err(err)) | (ReplyContext::YieldWithdrawal { .. }, Err(err)) => {
+            Err(StdError::generic_err(err).into())
+        }
+        (ReplyContext::Delivery { listing_id, msg }, Err(err)) => {
+            let dead_letter_id = store_dead_letter(deps.storage, listing_id.clone(), msg, err.clone(), env.block.height)?;
+
+            Ok(Response::new()
+                .add_attribute("dead_letter", dead_letter_id.to_string())
+                .add_attribute("listing_id", listing_id)
+                .add_attribute("error", err))
+        }
+        (ReplyContext::RetryDeadLetter { dead_letter_id }, Err(err)) => {
+            let mut dead_letter = DEAD_LETTERS.load(deps.storage, dead_letter_id)?;
+            dead_letter.error = err.clone();
+            DEAD_LETTERS.save(deps.storage, dead_letter_id, &dead_letter)?;
+
+            Ok(Response::new()
+                .add_attribute("retry_failed", dead_letter_id.to_string())
+                .add_attribute("attempts", dead_letter.attempts.to_string())
+                .add_attribute("error", err))
+        }
+        (ReplyContext::Payout { listing_id, index }, Err(err)) => {
+            // keep the payout for the recipient to claim
+            let mut receipt = RECEIPTS.load(deps.storage, &listing_id)?;
+            let payout = &mut receipt.payouts[index as usize];
+            let escrow_id = store_escrow(deps.storage, payout.recipient.clone(), payout.asset.clone(), listing_id.clone())?;
+            payout.status = PayoutStatus::Escrowed;
+            payout.escrow_id = Some(escrow_id);
+            RECEIPTS.save(deps.storage, &listing_id, &receipt)?;
+
+            Ok(Response::new()
+                .add_attribute("payout_escrowed", escrow_id.to_string())
+                .add_attribute("listing_id", listing_id)
+                .add_attribute("error", err))
+        }
+        (ReplyContext::Refund { listing_id, recipient, asset }, Err(err)) => {
+            // later refunds of the asset to the recipient skip the push
+            UNDELIVERABLE.save(deps.storage, (&recipient, &asset.info.to_string()), &env.block.height)?;
+            let escrow_id = store_escrow(deps.storage, recipient, asset, listing_id.clone())?;
+
+            Ok(Response::new()
+                .add_attribute("refund_escrowed", escrow_id.to_string())
+                .add_attribute("listing_id", listing_id)
+                .add_attribute("error", err))
+        }
+        (ReplyContext::EscrowPull { listing_id }, Err(err)) => {
+            // the seller moved the nft or revoked the approval, void the listing and refund the bid
+            let listing = load_listing(deps.storage, &listing_id)?;
+
+            Ok(void_listing(deps.storage, &env, &listing_id, &listing)?
+                .add_attribute("error", err))
+        }
+        (ReplyContext::Retry { escrow_id }, Err(err)) => {
+            let mut entry = ESCROW.load(deps.storage, escrow_id)?;
+            if entry.attempts >= MAX_TRANSFER_ATTEMPTS {
+                entry.status = EscrowStatus::ClaimOnly;
+            }
+            ESCROW.save(deps.storage, escrow_id, &entry)?;
+
+            Ok(Response::new()
+                .add_attribute("retry_failed", escrow_id.to_string())
+                .add_attribute("attempts", entry.attempts.to_string())
+                .add_attribute("error", err))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Tick {} => sudo_tick(deps, env),
     }
 }
 
+// counts of active and expired but unsettled listings and of refunds waiting in escrow,
+// so operators can alert on settlement backlogs from events alone
+fn sudo_tick(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    // the counters are kept on place and close, the tick only reads the listings ended since the last one
+    let counts = sweep_listing_counts(deps.storage, env.block.height)?;
+    let pending_refunds = PENDING_ESCROW.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(Response::new().add_event(Event::new("auction_status")
+        .add_attribute("height", env.block.height.to_string())
+        .add_attribute("active_listings", counts.active.to_string())
+        .add_attribute("expired_unsettled_listings", counts.expired.to_string())
+        .add_attribute("pending_refunds", pending_refunds.to_string())))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
+        QueryMsg::V2(msg) => query(deps, env, msg.into()),
         QueryMsg::Config {} => to_binary(&read_config(deps.storage)?),
         QueryMsg::ResolveListing { id } => query_list_resolver(deps, env, id),
-        QueryMsg::QueryMinter {} => to_binary(&query_minters(deps, env)?),
+        QueryMsg::QueryMinter { start_after, limit } => to_binary(&query_minters(deps, env, start_after, limit)?),
+        QueryMsg::MinterInfo { minter } => to_binary(&query_minter_info(deps, minter)?),
+        QueryMsg::MinterApplications { start_after, limit } => to_binary(&query_minter_applications(deps, start_after, limit)?),
         QueryMsg::QueryNftInfo {token_id, nft_addr} => to_binary(&query_nft_info(deps, token_id, nft_addr)?),
         QueryMsg::AllTokens{nft_addr} => to_binary(&query_all_nft_ids(deps, env, nft_addr)?),
         QueryMsg::AllAuctionIds{} => to_binary(&query_auction_ids(deps, env)?),
         QueryMsg::TokensByOwner{owner, start_after, limit, nft_addr} => to_binary(&query_nft_by_owner(deps, owner, start_after, limit, nft_addr)?),
+        QueryMsg::FloorPrice{collection} => to_binary(&query_floor_price(deps, collection)?),
+        QueryMsg::FeaturedListings{start_after, limit} => to_binary(&query_featured_listings(deps, env.clone(), start_after, limit)?),
+        QueryMsg::ListingsByTag{tag, start_after, limit} => to_binary(&query_listings_by_tag(deps, env.clone(), tag, start_after, limit)?),
+        QueryMsg::CreatorOverview{address} => to_binary(&query_creator_overview(deps, env.clone(), address)?),
+        QueryMsg::ListingsByCollection{collection, start_after, limit} => to_binary(&query_listings_by_collection(deps, env.clone(), collection, start_after, limit)?),
+        QueryMsg::TopBids{limit} => to_binary(&query_top_bids(deps, limit)?),
+        QueryMsg::Watchlist{address, start_after, limit} => to_binary(&query_watchlist(deps, address, start_after, limit)?),
+        QueryMsg::SettlementReceipt{listing_id} => to_binary(&RECEIPTS.load(deps.storage, &canonical_listing_id(deps.storage, listing_id)?)?),
+        QueryMsg::VerifyEscrow{listing_id} => to_binary(&query_verify_escrow(deps, env.clone(), listing_id)?),
+        QueryMsg::ConvertAmount{from_denom, to_denom, amount} => to_binary(&convert_amount(deps, &from_denom, &to_denom, amount)?),
+        QueryMsg::Sales{start_height, end_height, start_after, limit} => to_binary(&query_sales(deps, start_height, end_height, start_after, limit)?),
+        QueryMsg::Export{section, start_after, limit} => to_binary(&query_export(deps, section, start_after, limit)?),
+        QueryMsg::DeadLetters{start_after, limit} => to_binary(&query_dead_letters(deps, start_after, limit)?),
+        QueryMsg::ConfigHistory{start_after, limit} => to_binary(&query_config_history(deps, start_after, limit)?),
+        QueryMsg::Ledger{asset_info} => to_binary(&query_ledger(deps, env, asset_info)?),
+        QueryMsg::AccruedRoyalties{address} => to_binary(&AccruedRoyaltiesResponse {
+            royalties: read_accrued_royalties(deps.storage, &address)?,
+        }),
+        QueryMsg::Escrow{address, start_after, limit} => to_binary(&query_escrow(deps, address, start_after, limit)?),
+        QueryMsg::AcceptedDenoms{start_after, limit} => to_binary(&query_accepted_denoms(deps, start_after, limit)?),
+        QueryMsg::SettlementPreview{listing_id} => to_binary(&query_settlement_preview(deps, listing_id)?),
+        QueryMsg::SellerStats{seller} => to_binary(&query_seller_stats(deps, seller)?),
+        QueryMsg::CollectionFeeOverride{collection} => to_binary(&COLLECTION_FEE_OVERRIDES.may_load(deps.storage, &collection)?),
+        QueryMsg::CollectionLevy{collection} => to_binary(&COLLECTION_LEVIES.may_load(deps.storage, &collection)?),
+        QueryMsg::CollectionPriceFloor{collection} => to_binary(&COLLECTION_PRICE_FLOORS.may_load(deps.storage, &collection)?),
+        QueryMsg::ListingOperator{listing_id} => {
+            let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+            to_binary(&LISTING_OPERATORS.may_load(deps.storage, &listing_id)?)
+        }
+        QueryMsg::AbsenteeBids{listing_id} => {
+            let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+            to_binary(&read_absentee_bids(deps.storage, &listing_id)?)
+        }
+        QueryMsg::IncrementSchedule{name} => to_binary(&INCREMENT_SCHEDULES.may_load(deps.storage, &name)?),
+        QueryMsg::CollectionProvenance{collection} => to_binary(&COLLECTION_PROVENANCE_COMMITMENTS.may_load(deps.storage, &collection)?),
+        QueryMsg::CollectionTokenIdFormat{collection} => to_binary(&TokenIdFormatResponse {
+            format: COLLECTION_TOKEN_ID_FORMATS.may_load(deps.storage, &collection)?,
+            last_serial: COLLECTION_SERIALS.may_load(deps.storage, &collection)?.unwrap_or_default(),
+        }),
+        QueryMsg::CollectionVariant{collection} => to_binary(&COLLECTION_VARIANTS.may_load(deps.storage, &collection)?.unwrap_or_default()),
+        QueryMsg::RevealedReserve{listing_id} => to_binary(&REVEALED_RESERVES.may_load(deps.storage, &canonical_listing_id(deps.storage, listing_id)?)?),
+        QueryMsg::AutoRelist{listing_id} => to_binary(&AUTO_RELISTS.may_load(deps.storage, &canonical_listing_id(deps.storage, listing_id)?)?),
+        QueryMsg::ListingTransfer{listing_id} => to_binary(&LISTING_TRANSFERS.may_load(deps.storage, &canonical_listing_id(deps.storage, listing_id)?)?),
+        QueryMsg::CollectionPause{collection} => to_binary(&COLLECTION_PAUSES.may_load(deps.storage, &collection)?),
+        QueryMsg::Charity{address} => to_binary(&CHARITIES.has(deps.storage, &deps.api.addr_validate(&address)?)),
+        QueryMsg::IntentNonce{signer} => to_binary(&INTENT_NONCES.may_load(deps.storage, &deps.api.addr_validate(&signer)?)?.unwrap_or(0)),
+        QueryMsg::Quarantined{nft_addr, token_id} => to_binary(&QUARANTINE.may_load(deps.storage, (&nft_addr, &token_id))?),
+        QueryMsg::Custody{nft_addr, token_id} => to_binary(&CUSTODY.may_load(deps.storage, (&nft_addr, &token_id))?),
+        QueryMsg::SecurityDeposit{listing_id} => to_binary(&SECURITY_DEPOSITS.may_load(deps.storage, &canonical_listing_id(deps.storage, listing_id)?)?),
+        QueryMsg::Raffle{raffle_id} => to_binary(&RAFFLES.may_load(deps.storage, raffle_id)?),
+        QueryMsg::ListingNonce{nft_addr, token_id} => to_binary(&query_listing_nonce(deps, nft_addr, token_id)?),
+        QueryMsg::Provenance{token_id, nft_addr} => to_binary(&query_provenance(deps, token_id, nft_addr)?),
+        QueryMsg::Points{address, height} => to_binary(&query_points(deps, address, height)?),
+        QueryMsg::AllPoints{height, start_after, limit} => to_binary(&query_all_points(deps, height, start_after, limit)?),
+        QueryMsg::ListingTemplate{seller, name} => to_binary(&query_listing_template(deps, seller, name)?),
+        QueryMsg::Offer{offer_id} => to_binary(&OFFERS.may_load(deps.storage, offer_id)?),
+        QueryMsg::TokenFullInfo{contract_addr, token_id} => to_binary(&query_token_full_info(deps, env, contract_addr, token_id)?),
+        QueryMsg::AutoAcceptPrice{nft_addr, token_id} => to_binary(&query_auto_accept_price(deps, nft_addr, token_id)?),
+        QueryMsg::Features{} => to_binary(&query_features(deps)?),
     }
 }
 
+// the cw721 view of a token merged with its listing and open offers here
+pub fn query_token_full_info(deps: Deps, env: Env, contract_addr: String, token_id: String) -> StdResult<TokenFullInfoResponse> {
+    let nft_addr = deps.api.addr_validate(&contract_addr)?;
+
+    let nft: AllNftInfoResponse<Metadata> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: nft_addr.to_string(),
+        msg: to_binary(&Cw721QueryMsg::AllNftInfo { token_id: token_id.clone(), include_expired: None })?,
+    }))?;
+
+    let listing_id = TOKEN_LISTINGS.may_load(deps.storage, (nft_addr.as_str(), &token_id))?;
+    let listing = match &listing_id {
+        Some(listing_id) => Some(resolve_listing_response(deps, &env, load_listing(deps.storage, listing_id)?)?),
+        None => None,
+    };
+
+    Ok(TokenFullInfoResponse {
+        access: nft.access,
+        info: nft.info,
+        listing_id,
+        listing,
+        offers: read_token_offers(deps.storage, nft_addr.as_str(), &token_id, MAX_LIMIT as usize)?,
+    })
+}
+
 pub fn query_nft_info(
     deps: Deps, 
     token_id: String,
@@ -405,24 +5181,12 @@ pub fn query_nft_info(
         msg: to_binary(&Cw721QueryMsg::AllNftInfo {token_id: token_id.clone(), include_expired: None})?,
     }))?;
 
-    let token_count: NumTokensResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: nft_contract_addr.to_string(),
-        msg: to_binary(&Cw721QueryMsg::NumTokens {})?,
-    }))?;
-
-    let mut is_listing = false;
-    let mut listing_price = None;
-
-    for x in list_resolver_read(deps.storage)
-        .range(None, None, Order::Ascending)
-        .take(token_count.count as usize)
-         {
-            if x.as_ref().unwrap().1.token_id == token_id {
-                listing_price = Some(x.unwrap().1.max_bid);
-                is_listing = true;
-                break;
-            }
-        }
+    // the live listing of the token in this collection, if any
+    let listing_price = match TOKEN_LISTINGS.may_load(deps.storage, (nft_contract_addr.as_str(), &token_id))? {
+        Some(listing_id) => Some(load_listing(deps.storage, &listing_id)?.max_bid),
+        None => None,
+    };
+    let is_listing = listing_price.is_some();
 
     let res_nft_info = NftInfo {
         owner: nft_info.access.owner,
@@ -474,30 +5238,431 @@ pub fn query_all_nft_ids(
     Ok(token_ids.tokens)
 }
 
-pub fn query_minters(deps: Deps, _env: Env) -> StdResult<Vec<String>> {
-    read_minters(deps.storage)  
+pub fn query_minters(deps: Deps, _env: Env, start_after: Option<Binary>, limit: Option<u32>) -> StdResult<MintersResponse> {
+    let page = read_minters(deps.storage, start_after, page_limit(limit))?;
+    Ok(MintersResponse { minters: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_export(deps: Deps, section: ExportSection, start_after: Option<Binary>, limit: Option<u32>) -> StdResult<ExportResponse> {
+    let limit = page_limit(limit);
+    let namespace: &[u8] = match section {
+        ExportSection::Listings => LIST_RESOLVER_KEY,
+        ExportSection::Offers => OFFERS.namespace(),
+        ExportSection::Sales => RECEIPTS.namespace(),
+        ExportSection::Escrow => ESCROW.namespace(),
+        ExportSection::ListingBids => LISTING_BIDS.namespace(),
+        ExportSection::Ledger => LEDGER.namespace(),
+    };
+
+    // raw keys are relative to the section, so they double as cursors
+    let entries = read_raw_entries(deps.storage, namespace, start_after.map(|key| key.0), limit)
+        .into_iter()
+        .map(|(key, value)| (Binary(key.clone()), ExportEntry { key: Binary(key), value: Binary(value) }))
+        .collect();
+
+    let page = Page::new(entries, limit);
+    Ok(ExportResponse { section, entries: page.items, next_cursor: page.next_cursor })
+}
+
+// lets bots find listings to invalidate before bids are placed on them
+pub fn query_verify_escrow(deps: Deps, env: Env, listing_id: String) -> StdResult<VerifyEscrowResponse> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+
+    escrow_report(deps, &env, &listing)
+}
+
+// an amount of a denom in another at the rate quoted by the price oracle, rounded down
+pub fn convert_amount(deps: Deps, from_denom: &str, to_denom: &str, amount: Uint128) -> StdResult<ConvertAmountResponse> {
+    if from_denom == to_denom {
+        return Ok(ConvertAmountResponse { amount, rate: Decimal::one() });
+    }
+
+    let price_oracle = read_config(deps.storage)?
+        .price_oracle
+        .ok_or_else(|| StdError::generic_err("price oracle is unset"))?;
+    let res: ExchangeRateResponse = deps.querier.query_wasm_smart(price_oracle, &OracleQueryMsg::ExchangeRate {
+        base_denom: from_denom.to_string(),
+        quote_denom: to_denom.to_string(),
+    })?;
+
+    Ok(ConvertAmountResponse { amount: amount * res.rate, rate: res.rate })
+}
+
+// winners and prices of the sales settled between two heights, e.g. for a dao rewarding recent buyers
+pub fn query_sales(
+    deps: Deps,
+    start_height: u64,
+    end_height: u64,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> StdResult<SalesResponse> {
+    let page = read_sales_between(deps.storage, start_height, end_height, start_after, page_limit(limit))?;
+
+    let sales = page.items
+        .into_iter()
+        .map(|receipt| Sale {
+            listing_id: receipt.listing_id,
+            winner: receipt.winner,
+            price: receipt.price,
+            block_height: receipt.block_height,
+        })
+        .collect();
+    Ok(SalesResponse { sales, next_cursor: page.next_cursor })
+}
+
+pub fn query_dead_letters(deps: Deps, start_after: Option<Binary>, limit: Option<u32>) -> StdResult<DeadLettersResponse> {
+    let page = read_dead_letters(deps.storage, start_after, page_limit(limit))?;
+    Ok(DeadLettersResponse { dead_letters: page.items, next_cursor: page.next_cursor })
+}
+
+// balance of the contract in a native denom or cw20 token
+pub fn contract_balance(deps: Deps, env: &Env, asset_info: &AssetInfo) -> StdResult<Uint128> {
+    match asset_info {
+        // deposits in the yield vault are still the contract's
+        AssetInfo::NativeToken { denom } => {
+            let deposits = YIELD_DEPOSITS.may_load(deps.storage, denom)?.unwrap_or_default();
+            Ok(deps.querier.query_balance(&env.contract.address, denom)?.amount.checked_add(deposits)?)
+        }
+        AssetInfo::Token { contract_addr } => {
+            let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(contract_addr, &cw20::Cw20QueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            })?;
+            Ok(balance.balance)
+        }
+    }
+}
+
+pub fn query_ledger(deps: Deps, env: Env, asset_info: AssetInfo) -> StdResult<LedgerResponse> {
+    Ok(LedgerResponse {
+        bids: read_ledger(deps.storage, &asset_info, LedgerBucket::Bids)?,
+        refunds: read_ledger(deps.storage, &asset_info, LedgerBucket::Refunds)?,
+        fees: read_ledger(deps.storage, &asset_info, LedgerBucket::Fees)?,
+        royalties: read_ledger(deps.storage, &asset_info, LedgerBucket::Royalties)?,
+        deposits: read_ledger(deps.storage, &asset_info, LedgerBucket::Deposits)?,
+        balance: contract_balance(deps, &env, &asset_info)?,
+        yield_deposits: match &asset_info {
+            AssetInfo::NativeToken { denom } => YIELD_DEPOSITS.may_load(deps.storage, denom)?.unwrap_or_default(),
+            AssetInfo::Token { .. } => Uint128::zero(),
+        },
+        asset_info,
+    })
+}
+
+pub fn query_config_history(deps: Deps, start_after: Option<Binary>, limit: Option<u32>) -> StdResult<ConfigHistoryResponse> {
+    let page = read_config_history(deps.storage, start_after, page_limit(limit))?;
+    Ok(ConfigHistoryResponse { changes: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_minter_applications(deps: Deps, start_after: Option<Binary>, limit: Option<u32>) -> StdResult<MinterApplicationsResponse> {
+    let page = read_minter_applications(deps.storage, start_after, page_limit(limit))?;
+    Ok(MinterApplicationsResponse { applications: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_minter_info(deps: Deps, minter: String) -> StdResult<MinterInfoResponse> {
+    let minter = deps.api.addr_validate(&minter)?;
+
+    Ok(MinterInfoResponse {
+        info: read_minter_info(deps.storage, minter.clone()),
+        audit: MINTER_AUDIT.may_load(deps.storage, minter.as_str())?.unwrap_or_default(),
+        minter: minter.to_string(),
+    })
 }
 
 fn query_auction_ids(deps: Deps, _env: Env) -> StdResult<Vec<String>> {
     read_auction_ids(deps.storage)  
 }
 
-fn query_list_resolver(deps: Deps, _env: Env, id: String) -> StdResult<Binary> {
+pub fn query_floor_price(deps: Deps, collection: String) -> StdResult<FloorPriceResponse> {
+    let collection = deps.api.addr_validate(&collection)?;
+
+    let floors = read_floor_prices(deps.storage, collection.as_str())?
+        .into_iter()
+        .map(|(listing_id, price)| FloorPrice { listing_id, price })
+        .collect();
+
+    Ok(FloorPriceResponse {
+        collection: collection.to_string(),
+        floors,
+    })
+}
+
+pub fn query_listings_by_collection(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<ListingsByCollectionResponse> {
+    let page = read_collection_listings(deps.storage, &collection, start_after, page_limit(limit))?;
+
+    let listings = page.items
+        .into_iter()
+        .map(|(listing_id, listing)| Ok(CollectionListing {
+            listing_id,
+            listing: resolve_listing_response(deps, &env, listing)?,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(ListingsByCollectionResponse { collection, listings, next_cursor: page.next_cursor })
+}
+
+pub fn query_featured_listings(
+    deps: Deps,
+    env: Env,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<FeaturedListingsResponse> {
+    let page = read_featured_listings(deps.storage, env.block.height, start_after, page_limit(limit))?;
+
+    let listings = page.items
+        .into_iter()
+        .map(|(listing_id, until, listing)| Ok(FeaturedListing {
+            listing_id,
+            until,
+            listing: resolve_listing_response(deps, &env, listing)?,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(FeaturedListingsResponse { listings, next_cursor: page.next_cursor })
+}
+
+pub fn query_listings_by_tag(
+    deps: Deps,
+    env: Env,
+    tag: String,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<ListingsByTagResponse> {
+    let page = read_tag_listings(deps.storage, &tag, start_after, page_limit(limit))?;
+
+    let listings = page.items
+        .into_iter()
+        .map(|(listing_id, listing)| Ok(CollectionListing {
+            listing_id,
+            listing: resolve_listing_response(deps, &env, listing)?,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(ListingsByTagResponse { tag, listings, next_cursor: page.next_cursor })
+}
+
+pub fn query_creator_overview(deps: Deps, env: Env, address: String) -> StdResult<CreatorOverviewResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = MAX_LIMIT as usize;
+
+    let active_listings = read_seller_listings(deps.storage, &address, limit)?
+        .into_iter()
+        .map(|(listing_id, listing)| Ok(CollectionListing {
+            listing_id,
+            listing: resolve_listing_response(deps, &env, listing)?,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(CreatorOverviewResponse {
+        collections: read_creator_collections(deps.storage, &address, limit)?,
+        active_listings,
+        sales: read_seller_stats(deps.storage, &address)?,
+        unclaimed_payouts: read_escrow_by_recipient(deps.storage, &address, None, limit)?.items,
+        accrued_royalties: read_accrued_royalties(deps.storage, address.as_str())?,
+        minter: read_minter_info(deps.storage, address.clone()),
+        address,
+    })
+}
+
+pub fn query_top_bids(deps: Deps, limit: Option<u32>) -> StdResult<TopBidsResponse> {
+    let limit = page_limit(limit);
+
+    Ok(TopBidsResponse {
+        bids: read_top_bids(deps.storage, limit)?,
+    })
+}
+
+pub fn query_watchlist(
+    deps: Deps,
+    address: String,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<WatchlistResponse> {
+    let watcher = deps.api.addr_validate(&address)?;
+
+    let page = read_watchlist(deps.storage, &watcher, start_after, page_limit(limit))?;
+    Ok(WatchlistResponse { targets: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_escrow(
+    deps: Deps,
+    address: String,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<EscrowResponse> {
+    let recipient = deps.api.addr_validate(&address)?;
+
+    let page = read_escrow_by_recipient(deps.storage, &recipient, start_after, page_limit(limit))?;
+    Ok(EscrowResponse { entries: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_accepted_denoms(
+    deps: Deps,
+    start_after: Option<Binary>,
+    limit: Option<u32>
+) -> StdResult<AcceptedDenomsResponse> {
+    let page = read_accepted_denoms(deps.storage, start_after, page_limit(limit))?;
+    Ok(AcceptedDenomsResponse { denoms: page.items, next_cursor: page.next_cursor })
+}
+
+pub fn query_settlement_preview(deps: Deps, listing_id: String) -> StdResult<SettlementPreviewResponse> {
+    let listing_id = canonical_listing_id(deps.storage, listing_id)?;
+    let listing = load_listing(deps.storage, &listing_id)?;
+    settlement_preview(deps, &listing_id, &listing)
+}
+
+pub fn query_seller_stats(deps: Deps, seller: String) -> StdResult<SellerStatsResponse> {
+    let seller = deps.api.addr_validate(&seller)?;
+
+    Ok(SellerStatsResponse {
+        stats: read_seller_stats(deps.storage, &seller)?,
+        active_listings: ACTIVE_LISTINGS.may_load(deps.storage, &seller)?.unwrap_or_default(),
+        seller: seller.to_string(),
+    })
+}
+
+pub fn query_provenance(deps: Deps, token_id: String, nft_addr: String) -> StdResult<ProvenanceResponse> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+
+    Ok(ProvenanceResponse {
+        events: read_provenance(deps.storage, nft_addr.as_str(), &token_id)?,
+        token_id,
+        nft_addr: nft_addr.to_string(),
+    })
+}
+
+pub fn query_points(deps: Deps, address: String, height: Option<u64>) -> StdResult<PointsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    Ok(PointsResponse {
+        points: read_points(deps.storage, &address, height)?,
+        address,
+        height,
+    })
+}
+
+pub fn query_all_points(
+    deps: Deps,
+    height: Option<u64>,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> StdResult<AllPointsResponse> {
+    let page = read_all_points(deps.storage, height, start_after, page_limit(limit))?;
+
+    let balances = page.items
+        .into_iter()
+        .map(|(address, points)| PointsResponse { address, points, height })
+        .collect();
+
+    Ok(AllPointsResponse { height, balances, next_cursor: page.next_cursor })
+}
+
+pub fn query_listing_template(deps: Deps, seller: String, name: String) -> StdResult<Option<ListingTemplate>> {
+    let seller = deps.api.addr_validate(&seller)?;
+    LISTING_TEMPLATES.may_load(deps.storage, (&seller, &name))
+}
+
+// nonce the next placement of the token gets under the hashed id scheme
+pub fn query_listing_nonce(deps: Deps, nft_addr: String, token_id: String) -> StdResult<u64> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    Ok(LISTING_NONCES.may_load(deps.storage, (&nft_addr, &token_id))?.unwrap_or_default())
+}
+
+pub fn query_auto_accept_price(deps: Deps, nft_addr: String, token_id: String) -> StdResult<Option<Asset>> {
+    let nft_addr = deps.api.addr_validate(&nft_addr)?;
+    let owner = query_owner_of(deps, &nft_addr, &token_id)?;
+
+    read_auto_accept(deps.storage, nft_addr.as_str(), &token_id, &deps.api.addr_validate(&owner.owner)?)
+}
+
+pub fn query_features(deps: Deps) -> StdResult<FeaturesResponse> {
+    let config = read_config(deps.storage)?;
+    let sunset = config.sunset_height.is_some();
+    let randomness = config.randomness_provider.is_some();
+
+    Ok(FeaturesResponse {
+        cw20_bids: true,
+        offers: !sunset,
+        candle_auctions: randomness,
+        raffles: randomness,
+        dutch_auctions: false,
+        rentals: false,
+        ibc_results: cfg!(feature = "ibc") && config.results_channel.is_some(),
+        max_royalty_rate: config.max_royalty_rate.unwrap_or_else(Decimal::one),
+        max_batch_size: MAX_BATCH_SIZE as u32,
+        max_page_limit: MAX_LIMIT,
+        max_listing_tags: MAX_LISTING_TAGS as u32,
+        max_auction_duration_blocks: config.max_aution_duration_blocks,
+    })
+}
+
+fn query_list_resolver(deps: Deps, env: Env, id: String) -> StdResult<Binary> {
     // Fetch listing from listing_id
+    let id = canonical_listing_id(deps.storage, id)?;
     let key = id.as_bytes();
 
     let resp = match list_resolver_read(deps.storage).may_load(key)? {
-        Some(listing) => Some(listing),
+        Some(listing) => Some(with_bid_state(deps.storage, &id, listing)?),
         None => None,
     };
     let unwrapped_resp = resp.unwrap();
-    let resolve_listing = ResolveListingResponse {
-        token_id: unwrapped_resp.token_id,
-        contract_addr: unwrapped_resp.contract_addr,
-        seller: unwrapped_resp.seller,
-        max_bid: unwrapped_resp.max_bid,
-        max_bidder: unwrapped_resp.max_bidder,
-        block_limit: unwrapped_resp.block_limit,
-    };
-    to_binary(&resolve_listing)
+    to_binary(&resolve_listing_response(deps, &env, unwrapped_resp)?)
+}
+
+fn resolve_listing_response(deps: Deps, env: &Env, listing: Listing) -> StdResult<ResolveListingResponse> {
+    let has_bid = listing.max_bidder != env.contract.address;
+    let status = if listing.block_limit >= env.block.height {
+        ListingStatus::Active
+    } else if listing.candle_window.is_some() && listing.candle_end.is_none() {
+        ListingStatus::CandleEndPending
+    } else {
+        ListingStatus::Ended
+    };
+    let accepted_denom = match &listing.max_bid.info {
+        AssetInfo::NativeToken { denom } => ACCEPTED_DENOMS.may_load(deps.storage, denom)?,
+        AssetInfo::Token { .. } => None,
+    };
+
+    // same rules as execute_bid_listing, rounded up to the tick size of the denom
+    let min_next_bid = if status == ListingStatus::Active {
+        let min_bid = listing.min_next_bid(&env.contract.address);
+        let min_bid = match &accepted_denom {
+            Some(denom_info) => {
+                let min_bid = min_bid.max(denom_info.min_bid_unit);
+                match denom_info.tick_size {
+                    Some(tick_size) if !(min_bid % tick_size).is_zero() => min_bid + tick_size - min_bid % tick_size,
+                    _ => min_bid,
+                }
+            }
+            None => min_bid,
+        };
+        Some(min_bid)
+    } else {
+        None
+    };
+
+    Ok(ResolveListingResponse {
+        buy_now_price: listing.buy_now_price(&env.contract.address),
+        status,
+        blocks_remaining: listing.block_limit.saturating_sub(env.block.height),
+        extensions_used: listing.extensions_used,
+        reserve_met: has_bid && listing.reserve_price.map_or(true, |reserve_price| listing.max_bid.amount >= reserve_price),
+        min_next_bid,
+        accepted_denom,
+        token_id: listing.token_id,
+        contract_addr: listing.contract_addr,
+        seller: listing.seller,
+        max_bid: listing.max_bid,
+        max_bidder: listing.max_bidder,
+        block_limit: listing.block_limit,
+        decimals: listing.decimals,
+        candle_end: listing.candle_end,
+        listed_by: listing.listed_by,
+    })
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{StdError, OverflowError};
+use cosmwasm_std::{StdError, OverflowError, Uint128, Decimal};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -15,18 +15,48 @@ pub enum ContractError {
     #[error("Insufficient funds sent")]
     InsufficientFundsSend {},
 
+    #[error("no funds sent")]
+    NoFunds {},
+
+    #[error("funds sent in more than one denom")]
+    MultipleDenoms {},
+
+    #[error("funds must be sent in {denom}")]
+    WrongDenom { denom: String },
+
+    #[error("funds of zero {denom} sent")]
+    ZeroFunds { denom: String },
+
+    #[error("sent funds don't match {expected}")]
+    FundsMismatch { expected: String },
+
+    #[error("no funds expected")]
+    UnexpectedFunds {},
+
     #[error("Auction Ended")]
     AuctionEnded {},
 
     #[error("Auction Not Ended Yet")]
     AuctionNotEnded {},
 
+    #[error("the auction opens at height {height}")]
+    AuctionNotOpen { height: u64 },
+
+    #[error("no absentee bids to open the auction with")]
+    NoAbsenteeBids {},
+
+    #[error("at most {max} absentee bids per listing")]
+    TooManyAbsenteeBids { max: u32 },
+
     #[error("unregistered minter")]
     UnregisteredMinter {},
 
     #[error("sum of royalty rates are larger than 1")]
     InvalidRoyaltyRate {},
 
+    #[error("sum of royalty rates is larger than the maximum of {max}")]
+    RoyaltyRateAboveMax { max: Decimal },
+
     #[error("Token type or balance mismatch with price")]
     TokenMismatch {},
 
@@ -34,5 +64,284 @@ pub enum ContractError {
     AssetInfoMismatch {},
 
     #[error("nft is already on auction")]
-    AlreadyOnAuction {}
+    AlreadyOnAuction {},
+
+    #[error("bid exceeds the cap approved by the bidder")]
+    BidCapExceeded {},
+
+    #[error("unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("escrow entry can only be claimed by its recipient")]
+    ClaimOnly {},
+
+    #[error("denom {denom} is not accepted")]
+    UnacceptedDenom { denom: String },
+
+    #[error("amount is below the minimum bid unit of {min_bid_unit}")]
+    BelowMinBidUnit { min_bid_unit: Uint128 },
+
+    #[error("amount is not a multiple of the tick size {tick_size}")]
+    InvalidTickSize { tick_size: Uint128 },
+
+    #[error("fee tiers must be ascending by volume with rates not larger than 1")]
+    InvalidFeeTiers {},
+
+    #[error("fee override must end after it starts and have a rate not larger than 1")]
+    InvalidFeeOverride {},
+
+    #[error("security deposit must be a native token")]
+    InvalidSecurityDeposit {},
+
+    #[error("dispute window is still open")]
+    DisputeWindowOpen {},
+
+    #[error("dispute window is closed")]
+    DisputeWindowClosed {},
+
+    #[error("listing has not been sold")]
+    ListingNotSold {},
+
+    #[error("{address} is not attested by the compliance contract")]
+    NotAttested { address: String },
+
+    #[error("nft is not transferable")]
+    NonTransferable {},
+
+    #[error("name must be between {min} and {max} characters")]
+    InvalidNameLength { min: usize, max: usize },
+
+    #[error("description must not be longer than {max} characters")]
+    DescriptionTooLong { max: usize },
+
+    #[error("invalid uri {uri}")]
+    InvalidUri { uri: String },
+
+    #[error("{uri} is not a valid ipfs CID path")]
+    InvalidIpfsUri { uri: String },
+
+    #[error("num_nfts must be at least 1")]
+    InvalidNumNfts {},
+
+    #[error("invalid language code {lang}")]
+    InvalidLanguageCode { lang: String },
+
+    #[error("duplicate translation for language {lang}")]
+    DuplicateLanguage { lang: String },
+
+    #[error("listing below the initial price of {init_price} requires the creator's approval")]
+    BelowInitPrice { init_price: Uint128 },
+
+    #[error("listing in another asset than the initial price requires the creator's approval")]
+    NotInitPriceAsset {},
+
+    #[error("the listed nft is no longer held or approved for the auction")]
+    EscrowBroken {},
+
+    #[error("the listed nft is still held or approved for the auction")]
+    EscrowIntact {},
+
+    #[error("sender is neither the owner nor an approved operator of the token")]
+    NotTokenOwner {},
+
+    #[error("membership discount must not exceed 100%")]
+    InvalidMembershipDiscount {},
+
+    #[error("invalid listing template")]
+    InvalidListingTemplate {},
+
+    #[error("increment schedules take 1 to {max_steps} steps starting from zero, ascending and with nonzero increments")]
+    InvalidIncrementSchedule { max_steps: u32 },
+
+    #[error("no increment schedule {name}")]
+    IncrementScheduleNotFound { name: String },
+
+    #[error("{charity} is not an approved charity")]
+    CharityNotApproved { charity: String },
+
+    #[error("bid must be at least {min_bid}")]
+    BidIncrementTooLow { min_bid: Uint128 },
+
+    #[error("raffles need a ticket price and a duration up to the auction maximum")]
+    InvalidRaffle {},
+
+    #[error("the winner of the raffle was already requested")]
+    RaffleDrawPending {},
+
+    #[error("the raffle can only be voided after height {height}")]
+    RaffleDrawNotExpired { height: u64 },
+
+    #[error("payment must buy a whole number of tickets at {ticket_price}")]
+    InvalidTicketPayment { ticket_price: Uint128 },
+
+    #[error("listing is not a candle auction")]
+    NotCandleAuction {},
+
+    #[error("the effective end of the candle auction has not been drawn yet")]
+    CandleEndPending {},
+
+    #[error("the effective end of the candle auction was already drawn")]
+    CandleEndResolved {},
+
+    #[error("no randomness provider is configured")]
+    RandomnessProviderUnset {},
+
+    #[error("randomness must be at least 8 bytes")]
+    InvalidRandomness {},
+
+    #[error("buy-now is not available for this listing")]
+    BuyNowUnavailable {},
+
+    #[error("buy-now price is {price}")]
+    BuyNowPriceMismatch { price: Uint128 },
+
+    #[error("buy-now price can not be below {floor} this soon after the mint")]
+    BuyNowBelowFloor { floor: Uint128 },
+
+    #[error("price floors need a rate of at most 1 and a window of 1 to {max_blocks} blocks")]
+    InvalidPriceFloor { max_blocks: u64 },
+
+    #[error("token id formats take literals of letters, digits and `-_.:/`, `{{symbol}}` and exactly one `{{serial}}` or `{{serial:0N}}`, up to {max_len} characters and not rendering `GF.` ids")]
+    InvalidTokenIdFormat { max_len: usize },
+
+    #[error("provenance hashes are hex encoded sha256 digests")]
+    InvalidProvenanceHash {},
+
+    #[error("provenance hashes are committed once, before the collection's first token")]
+    ProvenanceCommitmentClosed {},
+
+    #[error("batch must contain between 1 and {max} items")]
+    InvalidBatchSize { max: usize },
+
+    #[error("listing {listing_id} already has bids")]
+    ListingHasBids { listing_id: String },
+
+    #[error("escrowless listings stay with the owner of the token")]
+    EscrowlessTransfer {},
+
+    #[error("the contract is not approved to transfer the token")]
+    TransferNotApproved {},
+
+    #[error("sender is already a whitelisted minter")]
+    AlreadyMinter {},
+
+    #[error("no pending minter application")]
+    NoMinterApplication {},
+
+    #[error("minter approvals need an unexpired expiration and a non-zero quota")]
+    InvalidMinterApproval {},
+
+    #[error("minting quota exhausted")]
+    MintQuotaExhausted {},
+
+    #[error("settlement grace period must be at most {max} blocks and the reward rate not larger than 1")]
+    InvalidSettlementRules { max: u64 },
+
+    #[error("listing can be settled by others from height {height}")]
+    SettlementNotOpen { height: u64 },
+
+    #[error("listing window out of bounds, at most {max} blocks")]
+    InvalidListingWindow { max: u64 },
+
+    #[error("the approval of the contract must last until height {height}")]
+    ApprovalExpiresTooSoon { height: u64 },
+
+    #[error("rate limits need a listing window of 1 to {max} blocks")]
+    InvalidRateLimits { max: u64 },
+
+    #[error("anti-sniping windows and extensions must be 1 to {max} blocks")]
+    InvalidAntiSniping { max: u64 },
+
+    #[error("rate limit reached, try again from height {height}")]
+    RateLimited { height: u64 },
+
+    #[error("token is not quarantined")]
+    NotQuarantined {},
+
+    #[error("token is not in custody")]
+    NotInCustody {},
+
+    #[error("invalid secp256k1 public key")]
+    InvalidListingKey {},
+
+    #[error("public key does not belong to the intent signer")]
+    SignerMismatch {},
+
+    #[error("listing intent expired")]
+    IntentExpired {},
+
+    #[error("listing intent nonce must be {expected}")]
+    InvalidIntentNonce { expected: u64 },
+
+    #[error("invalid listing intent signature")]
+    InvalidSignature {},
+
+    #[error("collection levies need a recipient and a rate of at most {max_percent}%")]
+    InvalidLevy { max_percent: u64 },
+
+    #[error("buyer premiums can be at most {max_percent}%")]
+    InvalidBuyerPremium { max_percent: u64 },
+
+    #[error("payments have to carry the buyer premium of {rate} of the price on top of it")]
+    BuyerPremiumMismatch { rate: Decimal },
+
+    #[error("collection {collection} is paused by its owner")]
+    CollectionPaused { collection: String },
+
+    #[error("no accrued royalties")]
+    NoAccruedRoyalties {},
+
+    #[error("nothing left to claim for listing {listing_id}")]
+    NothingToClaim { listing_id: String },
+
+    #[error("the marketplace is being sunset, no new listings or offers are taken")]
+    Sunset {},
+
+    #[error("the marketplace is not being sunset")]
+    NotSunset {},
+
+    #[error("no yield vault is configured")]
+    YieldVaultUnset {},
+
+    #[error("no funds above the yield buffer to deposit")]
+    NoIdleFunds {},
+
+    #[error("no yield accrued on the deposits")]
+    NoYield {},
+
+    #[error("listings take at most {max} tags of 1 to {max_length} lowercase letters, digits or dashes")]
+    InvalidTags { max: usize, max_length: usize },
+
+    #[error("at most {max} watchers per listing or collection")]
+    TooManyWatchers { max: u32 },
+
+    #[error("listings can only be featured until a future height")]
+    InvalidFeatureHeight {},
+
+    #[error("standard cw721 collections can not store royalties or translations")]
+    UnsupportedMetadata {},
+
+    #[error("only {available} in retained fees")]
+    InsufficientFees { available: Uint128 },
+
+    #[error("the highest bidder can only raise its own bid")]
+    BidRestatement {},
+
+    #[error("at most {max} active listings per address")]
+    TooManyActiveListings { max: u32 },
+
+    #[error("this build does not support IBC")]
+    IbcDisabled {},
+
+    #[error("results channels must be unordered")]
+    InvalidChannelOrder {},
+
+    #[error("unsupported results channel version, expected {expected}")]
+    InvalidChannelVersion { expected: String },
+
+    #[error("results channels only carry outgoing packets")]
+    UnexpectedPacket {},
+
+    #[error("invariant violated: {reason}")]
+    InvariantViolation { reason: String },
 }
@@ -0,0 +1,78 @@
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Empty, QuerierWrapper, QueryRequest, StdResult, WasmMsg, WasmQuery,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::asset::{Asset, AssetInfo};
+use crate::msg::{Cw20HookMsg, ExecuteMsg, PlaceListingParams, QueryMsg, ResolveListingResponse};
+
+/// GfAuctionContract is a wrapper around the auction address that builds
+/// the messages and queries other contracts need to integrate with it
+#[derive(Clone, Debug, PartialEq)]
+pub struct GfAuctionContract(pub Addr);
+
+impl GfAuctionContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg.into())?,
+            funds,
+        }
+        .into())
+    }
+
+    // the nft has to be approved for the auction beforehand, funds cover the
+    // security deposit of physically backed items
+    pub fn place_listing(&self, params: PlaceListingParams, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::PlaceListing {
+                id: params.id,
+                minimum_bid: params.minimum_bid,
+                nft_addr: params.nft_addr,
+                escrowless: params.escrowless,
+                template: params.template,
+            },
+            funds,
+        )
+    }
+
+    // native bids attach the bid as funds, cw20 bids are sent through the token contract
+    pub fn bid(&self, listing_id: String, bid_price: Asset) -> StdResult<CosmosMsg> {
+        match bid_price.info.clone() {
+            AssetInfo::NativeToken { denom } => self.call(
+                ExecuteMsg::BidListing {
+                    listing_id,
+                    bid_price: bid_price.clone(),
+                    on_behalf_of: None,
+                },
+                vec![Coin { denom, amount: bid_price.amount }],
+            ),
+            AssetInfo::Token { contract_addr } => Ok(WasmMsg::Execute {
+                contract_addr,
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.addr().into(),
+                    amount: bid_price.amount,
+                    msg: to_binary(&Cw20HookMsg::BidListing { listing_id, on_behalf_of: None })?,
+                })?,
+                funds: vec![],
+            }
+            .into()),
+        }
+    }
+
+    pub fn resolve_listing_request(&self, id: String) -> StdResult<QueryRequest<Empty>> {
+        Ok(WasmQuery::Smart {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&QueryMsg::ResolveListing { id })?,
+        }
+        .into())
+    }
+
+    pub fn resolve_listing(&self, querier: &QuerierWrapper, id: String) -> StdResult<ResolveListingResponse> {
+        querier.query(&self.resolve_listing_request(id)?)
+    }
+}
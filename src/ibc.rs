@@ -0,0 +1,103 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, CosmosMsg, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcMsg, IbcOrder,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::SettlementPacket;
+use crate::state::{read_config, store_config_change};
+
+pub const RESULTS_VERSION: &str = "glassflow-results-1";
+
+// packets not relayed within a day are dropped, the receipt stays queryable on this chain
+pub const RESULTS_PACKET_TIMEOUT_SECONDS: u64 = 24 * 60 * 60;
+
+pub fn settlement_packet_msg(env: &Env, channel_id: String, packet: &SettlementPacket) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Ibc(IbcMsg::SendPacket {
+        channel_id,
+        data: to_binary(packet)?,
+        timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(RESULTS_PACKET_TIMEOUT_SECONDS)),
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<(), ContractError> {
+    let channel = msg.channel();
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::InvalidChannelOrder {});
+    }
+    let counterparty_version = msg.counterparty_version().unwrap_or(RESULTS_VERSION);
+    if channel.version != RESULTS_VERSION || counterparty_version != RESULTS_VERSION {
+        return Err(ContractError::InvalidChannelVersion { expected: RESULTS_VERSION.to_string() });
+    }
+    Ok(())
+}
+
+// the owner still has to select the channel with UpdateResultsChannel
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_connect")
+        .add_attribute("channel_id", &msg.channel().endpoint.channel_id))
+}
+
+// stop sending results over a closed channel
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    let mut config = read_config(deps.storage)?;
+    if config.results_channel.as_ref() == Some(channel_id) {
+        config.results_channel = None;
+        store_config_change(deps.storage, &env.block, &env.contract.address, &config)?;
+    }
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    Err(ContractError::UnexpectedPacket {})
+}
+
+// results are fire and forget, acknowledgements and timeouts are only logged
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_packet_ack")
+        .add_attribute("sequence", msg.original_packet.sequence.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_packet_timeout")
+        .add_attribute("sequence", msg.packet.sequence.to_string()))
+}
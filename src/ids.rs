@@ -0,0 +1,114 @@
+use cosmwasm_std::{StdError, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+// counters of the ids the contract hands out, each holding the last id it allocated
+pub const LISTING_IDS: Item<u64> = Item::new("listingids");
+pub const TOKEN_IDS: Item<u64> = Item::new("tokenids");
+pub const OFFER_IDS: Item<u64> = Item::new("offercount");
+
+// allocates the next id of a counter, ids start at 1 and are never handed out twice
+pub fn next_id(storage: &mut dyn Storage, counter: &Item<u64>) -> StdResult<u64> {
+    let id = last_id(storage, counter)?
+        .checked_add(1)
+        .ok_or_else(|| StdError::generic_err("id counter exhausted"))?;
+    counter.save(storage, &id)?;
+    Ok(id)
+}
+
+// the last id allocated by a counter, zero before the first allocation
+pub fn last_id(storage: &dyn Storage, counter: &Item<u64>) -> StdResult<u64> {
+    Ok(counter.may_load(storage)?.unwrap_or_default())
+}
+
+// listings and tokens used to share the config's listing count, their counters continue after it
+// so that ids allocated before keep unique
+pub fn seed_legacy_counters(storage: &mut dyn Storage, listing_count: u64) -> StdResult<()> {
+    for counter in [&LISTING_IDS, &TOKEN_IDS] {
+        if counter.may_load(storage)?.is_none() {
+            counter.save(storage, &listing_count)?;
+        }
+    }
+    Ok(())
+}
+
+// collection -> last serial minted under the collection's token id format, serials start at 1 and
+// keep counting across format changes
+pub const COLLECTION_SERIALS: Map<&str, u64> = Map::new("collectionserials");
+
+pub const MAX_TOKEN_ID_FORMAT_LEN: usize = 64;
+// a u64 serial has at most 20 digits
+pub const MAX_SERIAL_WIDTH: usize = 20;
+// the prefix of the default `GF.<count>` ids, no format may render ids carrying it
+pub const DEFAULT_TOKEN_ID_PREFIX: &str = "GF.";
+
+pub fn next_serial(storage: &mut dyn Storage, collection: &str) -> StdResult<u64> {
+    let serial = COLLECTION_SERIALS.may_load(storage, collection)?.unwrap_or_default()
+        .checked_add(1)
+        .ok_or_else(|| StdError::generic_err("serial counter exhausted"))?;
+    COLLECTION_SERIALS.save(storage, collection, &serial)?;
+    Ok(serial)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenIdPart {
+    Literal(String),
+    // the cw721 symbol of the collection
+    Symbol,
+    // the collection serial, zero padded to `width` digits
+    Serial { width: usize },
+}
+
+// parses a token id format like `{symbol}-{serial:05}`. Literals take letters, digits and `-_.:/`,
+// and exactly one `{serial}` or `{serial:0N}` keeps the ids the format renders unique
+pub fn parse_token_id_format(format: &str) -> Result<Vec<TokenIdPart>, ContractError> {
+    if format.len() > MAX_TOKEN_ID_FORMAT_LEN {
+        return Err(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN });
+    }
+    let mut parts = vec![];
+    let mut rest = format;
+    while !rest.is_empty() {
+        if let Some(placeholder) = rest.strip_prefix('{') {
+            let end = placeholder.find('}')
+                .ok_or(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN })?;
+            parts.push(parse_placeholder(&placeholder[..end])?);
+            rest = &placeholder[end + 1..];
+        } else {
+            let end = rest.find('{').unwrap_or(rest.len());
+            let literal = &rest[..end];
+            if !literal.chars().all(|c| c.is_ascii_alphanumeric() || "-_.:/".contains(c)) {
+                return Err(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN });
+            }
+            parts.push(TokenIdPart::Literal(literal.to_string()));
+            rest = &rest[end..];
+        }
+    }
+
+    if parts.iter().filter(|part| matches!(part, TokenIdPart::Serial { .. })).count() != 1 {
+        return Err(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN });
+    }
+    Ok(parts)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<TokenIdPart, ContractError> {
+    match placeholder {
+        "symbol" => return Ok(TokenIdPart::Symbol),
+        "serial" => return Ok(TokenIdPart::Serial { width: 0 }),
+        _ => {}
+    }
+    let width = placeholder.strip_prefix("serial:0")
+        .filter(|width| !width.is_empty() && width.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|width| width.parse::<usize>().ok())
+        .filter(|width| (1..=MAX_SERIAL_WIDTH).contains(width))
+        .ok_or(ContractError::InvalidTokenIdFormat { max_len: MAX_TOKEN_ID_FORMAT_LEN })?;
+    Ok(TokenIdPart::Serial { width })
+}
+
+pub fn render_token_id(parts: &[TokenIdPart], symbol: &str, serial: u64) -> String {
+    parts.iter().map(|part| match part {
+        TokenIdPart::Literal(literal) => literal.clone(),
+        TokenIdPart::Symbol => symbol.to_string(),
+        TokenIdPart::Serial { width } => format!("{:0width$}", serial, width = width),
+    }).collect()
+}
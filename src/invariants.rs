@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Uint128};
+
+use crate::asset::{Asset, AssetInfo};
+use crate::contract::contract_balance;
+use crate::error::ContractError;
+use crate::ids::{last_id, LISTING_IDS, OFFER_IDS};
+use crate::state::{
+    list_resolver_read, with_bid_state, Listing, LISTING_BIDS, FLOOR_PRICES, TOP_BIDS, ESCROW, ESCROW_BY_RECIPIENT,
+    ESCROW_COUNT, OFFERS, SECURITY_DEPOSITS, LISTING_ALIASES, LISTINGS_BY_COLLECTION, LISTINGS_BY_SELLER, LISTINGS_BY_TAG, FEATURED_LISTINGS,
+    TOKEN_LISTINGS, TOKEN_OFFERS, QUARANTINE, CUSTODY, HELD_BIDS, ABSENTEE_BIDS, RAFFLES, ACTIVE_LISTINGS, ROYALTY_LEDGER, LEDGER, LedgerBucket,
+    LISTING_COUNTS, LISTING_ENDS, PENDING_ESCROW, FLOOR_ASSETS,
+};
+
+// state consistency checked after every execute and reply when the `invariants` feature is enabled
+pub fn assert_invariants(deps: Deps, env: &Env) -> Result<(), ContractError> {
+    let listings: Vec<(String, Listing)> = list_resolver_read(deps.storage)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.and_then(|(key, listing)| {
+            let listing_id = String::from_utf8_lossy(&key).to_string();
+            let listing = with_bid_state(deps.storage, &listing_id, listing)?;
+            Ok((listing_id, listing))
+        }))
+        .collect::<StdResult<_>>()?;
+
+    assert_listings(deps, &listings)?;
+    assert_indexes(deps, env, &listings)?;
+    assert_escrow(deps)?;
+    assert_offers(deps)?;
+    assert_deposits(deps)?;
+    assert_quarantine(deps)?;
+    assert_ledger(deps, env, &listings)
+}
+
+fn violation(reason: String) -> ContractError {
+    ContractError::InvariantViolation { reason }
+}
+
+// every listing id was handed out by the listing counter, directly or through an alias, and bid
+// state only exists for live listings
+fn assert_listings(deps: Deps, listings: &[(String, Listing)]) -> Result<(), ContractError> {
+    let listing_ids = last_id(deps.storage, &LISTING_IDS)?;
+    let aliases: BTreeMap<String, String> = LISTING_ALIASES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(alias, listing_id)| (listing_id, alias)))
+        .collect::<StdResult<_>>()?;
+
+    for (listing_id, _) in listings {
+        let number = aliases.get(listing_id).unwrap_or(listing_id)
+            .strip_prefix("AUCTION.")
+            .and_then(|number| number.parse::<u64>().ok());
+        if number.map_or(true, |number| number == 0 || number > listing_ids) {
+            return Err(violation(format!("listing {} outside of the listing counter {}", listing_id, listing_ids)));
+        }
+    }
+
+    let mut active: BTreeMap<&Addr, u32> = BTreeMap::new();
+    for (_, listing) in listings {
+        *active.entry(&listing.seller).or_default() += 1;
+    }
+    for item in ACTIVE_LISTINGS.range(deps.storage, None, None, Order::Ascending) {
+        let (seller, count) = item?;
+        if active.remove(&seller) != Some(count) {
+            return Err(violation(format!("active listing count of {} out of date", seller)));
+        }
+    }
+    if let Some(seller) = active.keys().next() {
+        return Err(violation(format!("active listings of {} not counted", seller)));
+    }
+
+    // the end index holds every listing at its current end, the heartbeat counts split them at the swept height
+    let counts = LISTING_COUNTS.may_load(deps.storage)?.unwrap_or_default();
+    for (listing_id, listing) in listings {
+        if !LISTING_ENDS.has(deps.storage, (listing.block_limit, listing_id)) {
+            return Err(violation(format!("listing {} missing from the end index at {}", listing_id, listing.block_limit)));
+        }
+    }
+    let ends = LISTING_ENDS.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    let expired = listings.iter().filter(|(_, listing)| listing.block_limit < counts.swept_height).count() as u64;
+    if ends != listings.len() || counts.expired != expired || counts.active + counts.expired != listings.len() as u64 {
+        return Err(violation(format!("listing counts {:?} out of date for {} listings", counts, listings.len())));
+    }
+
+    for item in LISTING_BIDS.keys(deps.storage, None, None, Order::Ascending) {
+        let listing_id = item?;
+        if !listings.iter().any(|(id, _)| *id == listing_id) {
+            return Err(violation(format!("bid state of removed listing {}", listing_id)));
+        }
+    }
+    Ok(())
+}
+
+// the floor price index holds exactly the listings, the top bid index exactly the listings with a bid
+fn assert_indexes(deps: Deps, env: &Env, listings: &[(String, Listing)]) -> Result<(), ContractError> {
+    let mut bids = 0;
+
+    for (listing_id, listing) in listings {
+        let amount = listing.max_bid.amount.u128().to_be_bytes();
+        let asset = listing.max_bid.info.to_string();
+
+        if !FLOOR_PRICES.has(deps.storage, (listing.contract_addr.as_str(), asset.as_str(), (&amount, listing_id))) {
+            return Err(violation(format!("listing {} missing from the floor price index", listing_id)));
+        }
+
+        let has_bid = listing.max_bidder != env.contract.address;
+        match TOP_BIDS.may_load(deps.storage, (&amount, listing_id))? {
+            Some(top_bid) if !has_bid || top_bid.bidder != listing.max_bidder || top_bid.bid != listing.max_bid => {
+                return Err(violation(format!("top bid of listing {} out of date", listing_id)));
+            }
+            None if has_bid => {
+                return Err(violation(format!("listing {} missing from the top bid index", listing_id)));
+            }
+            _ => {}
+        }
+        if TOKEN_LISTINGS.may_load(deps.storage, (listing.contract_addr.as_str(), &listing.token_id))?.as_ref() != Some(listing_id) {
+            return Err(violation(format!("listing {} missing from the token index", listing_id)));
+        }
+        if !LISTINGS_BY_SELLER.has(deps.storage, (&listing.seller, listing_id)) {
+            return Err(violation(format!("listing {} missing from the seller index", listing_id)));
+        }
+        if listing.tags.iter().any(|tag| !LISTINGS_BY_TAG.has(deps.storage, (tag, listing_id))) {
+            return Err(violation(format!("listing {} missing from the tag index", listing_id)));
+        }
+        if !listing.collection.is_empty() && !LISTINGS_BY_COLLECTION.has(deps.storage, (listing.collection.as_str(), listing_id)) {
+            return Err(violation(format!("listing {} missing from the collection index", listing_id)));
+        }
+        if has_bid {
+            bids += 1;
+        }
+    }
+
+    let floors = FLOOR_PRICES.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if floors != listings.len() {
+        return Err(violation(format!("{} floor price entries for {} listings", floors, listings.len())));
+    }
+    let mut listed: BTreeMap<(String, String), u32> = BTreeMap::new();
+    for (_, listing) in listings {
+        *listed.entry((listing.contract_addr.to_string(), listing.max_bid.info.to_string())).or_default() += 1;
+    }
+    for item in FLOOR_ASSETS.range(deps.storage, None, None, Order::Ascending) {
+        let (key, count) = item?;
+        if listed.remove(&key) != Some(count) {
+            return Err(violation(format!("listed asset count of {:?} out of date", key)));
+        }
+    }
+    if let Some(key) = listed.keys().next() {
+        return Err(violation(format!("listed asset {:?} not counted", key)));
+    }
+    let top_bids = TOP_BIDS.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if top_bids != bids {
+        return Err(violation(format!("{} top bid entries for {} listings with bids", top_bids, bids)));
+    }
+    let indexed = listings.iter().filter(|(_, listing)| !listing.collection.is_empty()).count();
+    let by_token = TOKEN_LISTINGS.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if by_token != listings.len() {
+        return Err(violation(format!("{} token index entries for {} listings", by_token, listings.len())));
+    }
+    let by_seller = LISTINGS_BY_SELLER.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if by_seller != listings.len() {
+        return Err(violation(format!("{} seller index entries for {} listings", by_seller, listings.len())));
+    }
+    let tags: usize = listings.iter().map(|(_, listing)| listing.tags.len()).sum();
+    let by_tag = LISTINGS_BY_TAG.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if by_tag != tags {
+        return Err(violation(format!("{} tag index entries for {} listing tags", by_tag, tags)));
+    }
+    for item in FEATURED_LISTINGS.keys(deps.storage, None, None, Order::Ascending) {
+        let listing_id = item?;
+        if !listings.iter().any(|(id, _)| *id == listing_id) {
+            return Err(violation(format!("removed listing {} is still featured", listing_id)));
+        }
+    }
+    let by_collection = LISTINGS_BY_COLLECTION.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if by_collection != indexed {
+        return Err(violation(format!("{} collection index entries for {} listings", by_collection, indexed)));
+    }
+    Ok(())
+}
+
+// the escrow ledger and its recipient index agree and ids come from the escrow counter
+fn assert_escrow(deps: Deps) -> Result<(), ContractError> {
+    let count = ESCROW_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    let mut entries = 0;
+
+    for item in ESCROW.range(deps.storage, None, None, Order::Ascending) {
+        let (id, entry) = item?;
+        if id != entry.id || id > count {
+            return Err(violation(format!("escrow entry {} outside of the escrow counter {}", id, count)));
+        }
+        if !ESCROW_BY_RECIPIENT.has(deps.storage, (&entry.recipient, id)) {
+            return Err(violation(format!("escrow entry {} missing from the recipient index", id)));
+        }
+        entries += 1;
+    }
+
+    let indexed = ESCROW_BY_RECIPIENT.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if indexed != entries {
+        return Err(violation(format!("{} recipient index entries for {} escrow entries", indexed, entries)));
+    }
+    if PENDING_ESCROW.may_load(deps.storage)?.unwrap_or_default() != entries as u64 {
+        return Err(violation(format!("pending escrow count out of date, {} entries", entries)));
+    }
+    Ok(())
+}
+
+fn assert_offers(deps: Deps) -> Result<(), ContractError> {
+    let count = last_id(deps.storage, &OFFER_IDS)?;
+
+    for item in OFFERS.range(deps.storage, None, None, Order::Ascending) {
+        let (id, offer) = item?;
+        if id != offer.id || id > count {
+            return Err(violation(format!("offer {} outside of the offer counter {}", id, count)));
+        }
+        if !TOKEN_OFFERS.has(deps.storage, (offer.nft_addr.as_str(), &offer.token_id, id)) {
+            return Err(violation(format!("offer {} missing from the token index", id)));
+        }
+    }
+    let offers = OFFERS.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    let by_token = TOKEN_OFFERS.keys_raw(deps.storage, None, None, Order::Ascending).count();
+    if by_token != offers {
+        return Err(violation(format!("{} token index entries for {} offers", by_token, offers)));
+    }
+    Ok(())
+}
+
+// deposits of unsold items belong to a live listing
+fn assert_deposits(deps: Deps) -> Result<(), ContractError> {
+    for item in SECURITY_DEPOSITS.range(deps.storage, None, None, Order::Ascending) {
+        let (listing_id, deposit) = item?;
+        if deposit.buyer.is_none() && list_resolver_read(deps.storage).may_load(listing_id.as_bytes())?.is_none() {
+            return Err(violation(format!("deposit of listing {} without a listing", listing_id)));
+        }
+    }
+    Ok(())
+}
+
+// quarantined and custodied nfts are never listed
+fn assert_quarantine(deps: Deps) -> Result<(), ContractError> {
+    for item in QUARANTINE.keys(deps.storage, None, None, Order::Ascending) {
+        let (nft_addr, token_id) = item?;
+        if TOKEN_LISTINGS.has(deps.storage, (&nft_addr, &token_id)) {
+            return Err(violation(format!("quarantined token {} of {} is listed", token_id, nft_addr)));
+        }
+    }
+    for item in CUSTODY.keys(deps.storage, None, None, Order::Ascending) {
+        let (nft_addr, token_id) = item?;
+        if TOKEN_LISTINGS.has(deps.storage, (&nft_addr, &token_id)) {
+            return Err(violation(format!("custodied token {} of {} is listed", token_id, nft_addr)));
+        }
+    }
+    Ok(())
+}
+
+// every bucket of the ledger matches the funds it accounts for and the contract holds at least the
+// sum of its buckets, fees are only tracked by the ledger itself
+fn assert_ledger(deps: Deps, env: &Env, listings: &[(String, Listing)]) -> Result<(), ContractError> {
+    let mut expected: BTreeMap<(String, String), Uint128> = BTreeMap::new();
+    let mut assets: BTreeMap<String, AssetInfo> = BTreeMap::new();
+    let mut add = |bucket: LedgerBucket, asset: &Asset| -> StdResult<()> {
+        assets.insert(asset.info.to_string(), asset.info.clone());
+        let entry = expected.entry((asset.info.to_string(), bucket.as_str().to_string())).or_default();
+        *entry = entry.checked_add(asset.amount)?;
+        Ok(())
+    };
+
+    for (_, listing) in listings {
+        if listing.max_bidder != env.contract.address {
+            add(LedgerBucket::Bids, &listing.with_premium(&listing.max_bid))?;
+        }
+        if let Some(penalty) = &listing.cancel_penalty {
+            add(LedgerBucket::Deposits, penalty)?;
+        }
+    }
+    for item in HELD_BIDS.range(deps.storage, None, None, Order::Ascending) {
+        let ((listing_id, _), held) = item?;
+        let listing = listings.iter().find(|(id, _)| *id == listing_id)
+            .ok_or_else(|| violation(format!("held bids of removed listing {}", listing_id)))?;
+        add(LedgerBucket::Bids, &listing.1.with_premium(&held.bid))?;
+    }
+    for item in ABSENTEE_BIDS.range(deps.storage, None, None, Order::Ascending) {
+        let ((listing_id, _), absentee) = item?;
+        let listing = listings.iter().find(|(id, _)| *id == listing_id)
+            .ok_or_else(|| violation(format!("absentee bids of removed listing {}", listing_id)))?;
+        add(LedgerBucket::Bids, &listing.1.with_premium(&absentee.bid))?;
+    }
+    for item in OFFERS.range(deps.storage, None, None, Order::Ascending) {
+        add(LedgerBucket::Bids, &item?.1.price)?;
+    }
+    for item in RAFFLES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, raffle) = item?;
+        add(LedgerBucket::Bids, &Asset {
+            info: raffle.ticket_price.info,
+            amount: raffle.ticket_price.amount.checked_mul(Uint128::from(raffle.tickets_sold))?,
+        })?;
+    }
+    for item in ESCROW.range(deps.storage, None, None, Order::Ascending) {
+        add(LedgerBucket::Refunds, &item?.1.asset)?;
+    }
+    for item in ROYALTY_LEDGER.range(deps.storage, None, None, Order::Ascending) {
+        add(LedgerBucket::Royalties, &item?.1)?;
+    }
+    for item in SECURITY_DEPOSITS.range(deps.storage, None, None, Order::Ascending) {
+        add(LedgerBucket::Deposits, &item?.1.asset)?;
+    }
+
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    for item in LEDGER.range(deps.storage, None, None, Order::Ascending) {
+        let ((asset, bucket), amount) = item?;
+        if bucket != LedgerBucket::Fees.as_str() && expected.remove(&(asset.clone(), bucket.clone())) != Some(amount) {
+            return Err(violation(format!("{} ledger of {} out of date", bucket, asset)));
+        }
+        let total = totals.entry(asset).or_default();
+        *total = total.checked_add(amount)?;
+    }
+    if let Some(((asset, bucket), _)) = expected.into_iter().find(|(_, amount)| !amount.is_zero()) {
+        return Err(violation(format!("{} ledger of {} missing", bucket, asset)));
+    }
+
+    for (asset, info) in assets {
+        if let Some(total) = totals.get(&asset) {
+            let balance = contract_balance(deps, env, &info)?;
+            if balance < *total {
+                return Err(violation(format!("ledger of {} exceeds the balance {}", asset, balance)));
+            }
+        }
+    }
+    Ok(())
+}
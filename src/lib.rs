@@ -1,8 +1,25 @@
 pub mod contract;
 pub mod asset;
+pub mod coin_helpers;
 mod error;
+pub mod ids;
+pub mod math;
 pub mod msg;
+pub mod pagination;
 pub mod state;
+pub mod uri;
+
+#[cfg(feature = "library")]
+pub mod helpers;
+
+#[cfg(feature = "invariants")]
+mod invariants;
+
+#[cfg(feature = "ibc")]
+pub mod ibc;
+
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,29 @@
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use std::convert::TryFrom;
+
+// rates in basis points, 10_000 bps being 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// atomics of a `Decimal` making up one basis point
+const ATOMICS_PER_BPS: u128 = 100_000_000_000_000;
+
+pub fn bps_to_decimal(bps: u64) -> Decimal {
+    Decimal::from_ratio(bps, BPS_DENOMINATOR)
+}
+
+// the basis points of a rate, rates finer than a basis point fail instead of being rounded
+pub fn decimal_to_bps(rate: Decimal) -> StdResult<u64> {
+    let atomics = rate.atomics().u128();
+    if atomics % ATOMICS_PER_BPS != 0 {
+        return Err(StdError::generic_err(format!("rate {} is not a whole number of basis points", rate)));
+    }
+    u64::try_from(atomics / ATOMICS_PER_BPS)
+        .map_err(|_| StdError::generic_err(format!("rate {} is too large for basis points", rate)))
+}
+
+// the share of an amount a payout at a rate gets, rounded down so that the shares of a price never
+// add up to more than it and the remainder stays with the seller
+pub fn payout_share(amount: Uint128, rate: Decimal) -> Uint128 {
+    // `Uint128 * Decimal` truncates the product
+    amount * rate
+}
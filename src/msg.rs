@@ -1,44 +1,580 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw20::Cw20ReceiveMsg;
-use crate::state::{ Royalty };
-use crate::asset::Asset;
+use cw721::Cw721ReceiveMsg;
+use cw721::{Expiration, NftInfoResponse, OwnerOfResponse};
+use crate::state::{ Royalty, Cw721Variant, LocalizedString, TopBid, WatchTarget, EscrowEntry, DenomInfo, FeeTier, SellerStats, DepositConfig, ProvenanceEvent, MembershipConfig, RateLimits, AntiSniping, PriceFloor, YieldVault, ListingTemplate, IncrementStep, MinterInfo, MinterAudit, MinterApplication, Metadata, Offer, DeadLetter, ConfigChange };
+use crate::asset::{Asset, AssetInfo};
+
+pub mod v2;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct InstantiateMsg {
+    // owner of the contract, the sender when unset
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    // same rules as `UpdateFeeConfig`
+    #[serde(default)]
+    pub fee_tiers: Vec<FeeTier>,
+
+    #[serde(default)]
+    pub fee_collector: Option<String>,
+
+    #[serde(default)]
+    pub primary_fee_rate: Option<Decimal>,
+
+    // same rules as `UpdateAcceptedDenom`
+    #[serde(default)]
+    pub accepted_denoms: Vec<DenomInfo>,
+
+    // whitelisted as by `UpdateMinter`, granted by the owner
+    #[serde(default)]
+    pub minters: Vec<InitialMinter>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InitialMinter {
+    pub minter: String,
+
+    pub expires: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    // periodic crank of the chain, emits an `auction_status` event summarizing the settlement backlog
+    Tick {},
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    // the v2 messages, see `v2::ExecuteMsg`
+    V2(v2::ExecuteMsg),
     // Place an NFT on Auction
+    // escrowless listings keep the nft with the seller, who approved this contract, until the first bid
+    // template names one of the seller's saved listing templates
     PlaceListing {
         id: String,
         minimum_bid: Asset,
-        nft_addr: String
+        nft_addr: String,
+        escrowless: Option<bool>,
+        template: Option<String>,
+    },
+    // Place several NFTs on Auction at once, failing as a whole if any listing fails
+    // funds cover the security deposits of all physically backed items and the cancel penalties
+    PlaceListings(Vec<PlaceListingParams>),
+    // Place an NFT on Auction for a seller, callable by registered launchpads only
+    // the seller must own the nft and approve the launchpad for it, the launchpad sends the seller's deposits
+    PlaceListingFor {
+        seller: String,
+        params: PlaceListingParams,
+    },
+    // Place an NFT on Auction from an intent signed by its seller, submitted by any relayer
+    PlaceListingWithSig {
+        intent: ListingIntent,
+        signature: Binary,
+    },
+    // register or unregister a launchpad contract allowed to place listings for sellers
+    UpdateLaunchpad {
+        launchpad: String,
+        registered: bool,
     },
     // Bid on an NFT already put on Auction
+    // an approved operator can bid on behalf of another address
     BidListing {
         listing_id: String,
-        bid_price: Asset
+        bid_price: Asset,
+        on_behalf_of: Option<String>,
+    },
+    // bid from an intent signed by the bidder, paid by the relayer, refunds and the nft go to the bidder
+    BidOnBehalf {
+        intent: BidIntent,
+        signature: Binary,
+    },
+    // buy the nft at the buy-now price of the listing, refunding the highest bidder
+    BuyNow {
+        listing_id: String,
+        price: Asset,
     },
     // Withdraw an ended Auction
+    // callable by the seller, by the winner once ended and by anyone after the settlement grace period
     WithdrawListing {
         listing_id: String,
     },
+    // settle an ended Auction by having its winner pull the NFT, the seller's proceeds stay in escrow
+    // for `ClaimProceeds`, after a `ClaimProceeds` settlement delivers the NFT kept for the winner
+    ClaimItem {
+        listing_id: String,
+    },
+    // settle a sold Auction by having its seller pull the proceeds, the NFT stays with the contract
+    // for `ClaimItem`, after a `ClaimItem` settlement releases the proceeds kept in escrow
+    ClaimProceeds {
+        listing_id: String,
+    },
+    // cancel several listings, failing as a whole if any can't be cancelled
+    // listings with bids can only be cancelled by paying their cancel penalty to the highest bidder
+    CancelListings {
+        listing_ids: Vec<String>,
+    },
+    // return ended listings left unsold past the unsold grace period to their sellers
+    // callable by the owner and the listing curator, failing as a whole if any listing sold
+    ReturnUnsold {
+        listing_ids: Vec<String>,
+    },
+    // stop taking new listings and offers for good, callable by the owner
+    BeginSunset {},
+    // once sunset, wind down up to limit ended listings, refunding their bids and returning the
+    // nfts to the sellers, then refund open offers, callable by anyone
+    SunsetCrank {
+        limit: Option<u32>,
+    },
     Mint(GFMintMsg),
     // register the whitelisted minter or update the expiration time
     UpdateMinter {
         minter: String,
+        expires: Option<Expiration>,
+    },
+    // remove up to limit minters whose whitelisting expired, callable by anyone
+    PruneExpiredMinters {
+        limit: Option<u32>,
     },
     // remove the minter from whitelist
     RemoveMinter {
         minter: String,
     },
+    // ask to be whitelisted as minter, metadata_uri points to the applicant's portfolio
+    ApplyForMinter {
+        metadata_uri: String,
+    },
+    // whitelist a pending applicant, callable by the owner or the minter curator
+    // quota caps the tokens the minter may mint, unlimited when unset
+    ApproveMinter {
+        applicant: String,
+        expires: Option<Expiration>,
+        quota: Option<u64>,
+    },
+    // drop a pending application, callable by the owner or the minter curator
+    RejectMinter {
+        applicant: String,
+    },
+    // set or unset the address reviewing minter applications besides the owner
+    UpdateMinterCurator {
+        curator: Option<String>,
+    },
+    // set or unset the address featuring listings besides the owner
+    UpdateListingCurator {
+        curator: Option<String>,
+    },
+    // approve or revoke a charity listing templates can pass the whole sale price through to
+    // callable by the owner and the listing curator
+    UpdateCharity {
+        charity: String,
+        approved: bool,
+    },
+    // set or unset the IBC channel settlement results are sent to
+    UpdateResultsChannel {
+        channel_id: Option<String>,
+    },
+    // feature a listing up to and including height `until`, callable by the owner or listing curator
+    FeatureListing {
+        listing_id: String,
+        until: u64,
+    },
+    UnfeatureListing {
+        listing_id: String,
+    },
     // receive cw20 token
-    ReceiveToken(Cw20ReceiveMsg),
+    Receive(Cw20ReceiveMsg),
+    // nfts are listed through approvals, those sent to the contract are quarantined for their sender
+    ReceiveNft(Cw721ReceiveMsg),
+    // send a quarantined nft back to the address that sent it
+    ReclaimQuarantined {
+        nft_addr: String,
+        token_id: String,
+    },
+    // send an nft minted into custody to its owner
+    ReleaseCustody {
+        nft_addr: String,
+        token_id: String,
+    },
+    // get notified through `watch` events when a listing or collection changes
+    Watch {
+        target: WatchTarget,
+    },
+    // stop watching a listing or collection
+    Unwatch {
+        target: WatchTarget,
+    },
+    // allow an operator to bid on the sender's behalf on a listing, up to the cap
+    ApproveBidder {
+        operator: String,
+        listing_id: String,
+        cap: Uint128,
+    },
+    // revoke a bidding approval
+    RevokeBidder {
+        operator: String,
+        listing_id: String,
+    },
+    // claim the royalties accrued for the sender while royalty payouts are deferred
+    ClaimRoyalties {},
+    // send retained platform fees to the fee collector, callable by the owner or the fee manager
+    WithdrawFees {
+        asset: Asset,
+    },
+    // claim a transfer that could not be pushed to the sender and was kept in escrow, optionally to
+    // another address when the sender can't receive the asset
+    ClaimEscrow {
+        escrow_id: u64,
+        recipient: Option<String>,
+    },
+    // push an escrowed refund or payout to its recipient again, callable by anyone
+    RetryRefund {
+        escrow_id: u64,
+    },
+    // send a failed nft delivery again, callable by anyone
+    RetryDeadLetter {
+        id: u64,
+    },
+    // register a native denom usable for listings and bids or update its metadata
+    UpdateAcceptedDenom {
+        denom: String,
+        display: String,
+        decimals: u8,
+        min_bid_unit: Uint128,
+        tick_size: Option<Uint128>,
+    },
+    // remove a denom from the accepted denoms
+    RemoveAcceptedDenom {
+        denom: String,
+    },
+    // set the platform fee tiers and the fee collector
+    UpdateFeeConfig {
+        fee_tiers: Vec<FeeTier>,
+        fee_collector: Option<String>,
+        primary_fee_rate: Option<Decimal>,
+    },
+    // set or unset the address allowed to run collection fee promotions
+    UpdateFeeManager {
+        fee_manager: Option<String>,
+    },
+    // run a promotional platform fee for listings of a collection placed inside the window
+    SetCollectionFeeOverride {
+        collection: String,
+        fee_rate: Decimal,
+        start_time: Timestamp,
+        end_time: Timestamp,
+    },
+    // set or unset the levy of a collection, callable by the collection owner, already placed
+    // listings keep their levy
+    SetCollectionLevy {
+        collection: String,
+        rate: Option<Decimal>,
+        recipient: Option<String>,
+    },
+    // set or unset the buy-now floor of resales shortly after the mint, callable by the collection owner
+    SetCollectionPriceFloor {
+        collection: String,
+        price_floor: Option<PriceFloor>,
+    },
+    // set or unset the token id format of mints into the collection, e.g. `{symbol}-{serial:05}`,
+    // callable by the collection owner. Already minted tokens keep their ids
+    SetCollectionTokenIdFormat {
+        collection: String,
+        format: Option<String>,
+    },
+    // commit the hex sha256 provenance hash over all artwork hashes of the collection, callable by
+    // the collection owner once and only before any token of the collection exists
+    CommitCollectionProvenance {
+        collection: String,
+        provenance_hash: String,
+    },
+    // set the cw721 variant mints into the collection are shaped for, callable by the collection owner
+    SetCollectionVariant {
+        collection: String,
+        variant: Cw721Variant,
+    },
+    // stop or resume new listings and mints of a collection, callable by the collection owner
+    // already placed listings keep running
+    SetCollectionPaused {
+        collection: String,
+        paused: bool,
+    },
+    // end a collection fee promotion, already placed listings keep their fee
+    RemoveCollectionFeeOverride {
+        collection: String,
+    },
+    // require a security deposit from sellers of physically backed nfts, or stop requiring it
+    UpdateDepositConfig {
+        security_deposit: Option<DepositConfig>,
+    },
+    // return a security deposit to the seller once the dispute window closed
+    ReleaseDeposit {
+        listing_id: String,
+    },
+    // arbiter ruling on a sold physically backed nft, slashing the deposit to the buyer or returning it
+    ResolveDispute {
+        listing_id: String,
+        slash: bool,
+    },
+    // raffle off an nft approved for this contract, tickets sell at a fixed price until the raffle ends
+    // the tickets of the losers are paid to the seller or refunded
+    CreateRaffle {
+        nft_addr: String,
+        token_id: String,
+        ticket_price: Asset,
+        duration_blocks: u64,
+        refund_losers: bool,
+    },
+    BuyTickets {
+        raffle_id: u64,
+        count: u32,
+    },
+    // request the winner of an ended raffle from the randomness provider, callable by anyone with
+    // the funds the provider charges, a raffle without tickets returns the nft to the seller
+    DrawRaffle {
+        raffle_id: u64,
+    },
+    // return the nft of a raffle whose winner did not arrive in time to the seller and refund every
+    // ticket, callable by anyone
+    VoidRaffle {
+        raffle_id: u64,
+    },
+    // set or unset the randomness provider drawing the effective end of candle auctions and raffle winners
+    UpdateRandomnessProvider {
+        randomness_provider: Option<String>,
+    },
+    // open an auction past its start with its absentee bids, callable by anyone. The first live
+    // bid or the settlement opens it otherwise
+    OpenAuction {
+        listing_id: String,
+    },
+    // request the effective end of an ended candle auction from the randomness provider,
+    // callable by anyone with the funds the provider charges
+    RequestCandleEnd {
+        listing_id: String,
+    },
+    // randomness provider callback, job_id is the listing id or `RAFFLE.<raffle id>`
+    ReceiveRandomness {
+        job_id: String,
+        randomness: Binary,
+    },
+    // set or unset the per address limits on new listings and bids
+    UpdateRateLimits {
+        rate_limits: Option<RateLimits>,
+    },
+    // set or unset the extension of auctions receiving bids right before their end
+    UpdateAntiSniping {
+        anti_sniping: Option<AntiSniping>,
+    },
+    // credit royalties to a ledger their beneficiaries claim from instead of sending them on settlement
+    UpdateRoyaltyPayout {
+        deferred: bool,
+    },
+    // let the highest bidder restate its bid without raising it, forbidden by default
+    UpdateBidRestatement {
+        allowed: bool,
+    },
+    // save or remove a named increment schedule listing templates can select
+    UpdateIncrementSchedule {
+        name: String,
+        steps: Option<Vec<IncrementStep>>,
+    },
+    // set or unset the buyer premium bids and buy-now purchases pay on top of the hammer price,
+    // listings keep the premium they were placed with
+    UpdateBuyerPremium {
+        rate: Option<Decimal>,
+    },
+    // publish the reserve prices of auctions closing without meeting them, confidential by default
+    UpdateReserveReveal {
+        revealed: bool,
+    },
+    // keep platform fees in the contract until withdrawn instead of sending them on settlement
+    UpdateFeePayout {
+        retained: bool,
+    },
+    // set or unset the number of listings an address may have active at once
+    UpdateMaxActiveListings {
+        max_active_listings: Option<u32>,
+    },
+    // set or unset the highest sum of royalty rates a minted token may carry
+    UpdateMaxRoyaltyRate {
+        max_royalty_rate: Option<Decimal>,
+    },
+    // set the blocks after the end of an auction from which anyone may settle it, and the share of
+    // the platform fee paid to such third party settlers
+    UpdateSettlementRules {
+        grace_blocks: u64,
+        reward_rate: Option<Decimal>,
+    },
+    // set the blocks after the end of an unsold auction from which the listing curator may return it
+    UpdateUnsoldGrace {
+        grace_blocks: u64,
+    },
+    // set the longest auction duration and the blocks past its end an escrowless listing's approval
+    // must last, applying to listings placed afterwards
+    UpdateListingWindows {
+        max_aution_duration_blocks: Option<u64>,
+        approval_window_blocks: Option<u64>,
+    },
+    // switch new listings between numeric and hashed ids
+    UpdateListingIdScheme {
+        hashed: bool,
+    },
+    // set or unset the attestation contract gating listings and bids
+    UpdateComplianceContract {
+        compliance_contract: Option<String>,
+    },
+    // set or unset the oracle quoting the exchange rates between denoms
+    UpdatePriceOracle {
+        price_oracle: Option<String>,
+    },
+    // set or unset the vault idle native funds are deposited into, deposits in a replaced or
+    // unset vault are withdrawn and written off if the vault fails to return them. Yield not
+    // harvested before stays in the vault
+    UpdateYieldVault {
+        yield_vault: Option<YieldVault>,
+    },
+    // deposit the native funds on hand above the buffer into the yield vault
+    SweepYield {},
+    // withdraw the yield accrued on the deposits into the platform fees
+    HarvestYield {},
+    // set or unset the membership collection whose holders get a platform fee discount
+    UpdateMembership {
+        membership: Option<MembershipConfig>,
+    },
+    // minter approval to list a token not sold yet below its initial price or in another asset, used once
+    ApproveDiscountListing {
+        nft_addr: String,
+        token_id: String,
+        min_price: Asset,
+    },
+    // withdraw a discount listing approval
+    RevokeDiscountListing {
+        nft_addr: String,
+        token_id: String,
+    },
+    // offer an active listing without bids to a new seller, who takes over the proceeds and
+    // the escrowed nft once it accepts, unset to withdraw the offer
+    TransferListing {
+        listing_id: String,
+        new_seller: Option<String>,
+    },
+    // take over a listing offered by its seller, callable by the new seller
+    AcceptListingTransfer {
+        listing_id: String,
+    },
+    // replace the category tags of a listing, callable by its seller
+    SetListingTags {
+        listing_id: String,
+        tags: Vec<String>,
+    },
+    // designate the operator placing bids on behalf of off-chain clients on a listing, callable by
+    // its seller. Unset removes the operator
+    SetListingOperator {
+        listing_id: String,
+        operator: Option<String>,
+    },
+    // void a listing whose nft left escrow or lost its approval, refunding the highest bidder
+    InvalidateListing {
+        listing_id: String,
+    },
+    // set or unset the loyalty points earned per settled unit of an asset
+    UpdatePointsRate {
+        asset: AssetInfo,
+        rate: Option<Decimal>,
+    },
+    // checkpoint loyalty balances at the current height for export
+    SnapshotPoints {},
+    // save listing parameters of the sender under a name, replacing a template of the same name
+    SaveListingTemplate {
+        name: String,
+        template: ListingTemplate,
+    },
+    RemoveListingTemplate {
+        name: String,
+    },
+    // offer to buy a token outside of an auction, escrowing the price
+    // the offer settles immediately when it meets the auto-accept price of the owner
+    MakeOffer {
+        nft_addr: String,
+        token_id: String,
+        price: Asset,
+    },
+    // cancel an offer, refunding the buyer
+    CancelOffer {
+        offer_id: u64,
+    },
+    // accept an offer as the token owner, who approved this contract for the token
+    AcceptOffer {
+        offer_id: u64,
+    },
+    // set or unset the price from which offers settle without the owner, for a token or when
+    // token_id is unset for all of the sender's tokens in the collection
+    SetAutoAccept {
+        nft_addr: String,
+        token_id: Option<String>,
+        price: Option<Asset>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlaceListingParams {
+    pub id: String,
+
+    pub minimum_bid: Asset,
+
+    pub nft_addr: String,
+
+    pub escrowless: Option<bool>,
+
+    pub template: Option<String>,
+}
+
+// signed by the seller over `intent_digest`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingIntent {
+    pub seller: String,
+
+    // compressed secp256k1 key of the seller's account, the seller address is derived from it
+    pub pubkey: Binary,
+
+    pub params: PlaceListingParams,
+
+    // the seller's next intent nonce
+    pub nonce: u64,
+
+    // last height the intent can be submitted at
+    pub expires_at: u64,
+}
+
+// signed by the bidder over `intent_digest`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidIntent {
+    pub bidder: String,
+
+    // compressed secp256k1 key of the bidder's account, the bidder address is derived from it
+    pub pubkey: Binary,
+
+    pub listing_id: String,
+
+    pub bid_price: Asset,
+
+    // the bidder's next intent nonce
+    pub nonce: u64,
+
+    // last height the intent can be submitted at
+    pub expires_at: u64,
 }
 
+// hooks of cw20 `Send`, the tokens arrive with the message so bids in cw20 tokens need no
+// allowance of the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
@@ -46,21 +582,57 @@ pub enum Cw20HookMsg {
     PlaceListing {
         id: String,
         nft_addr: String,
+        escrowless: Option<bool>,
+        template: Option<String>,
     },
     // Bid on an NFT already put on Auction
     BidListing {
         listing_id: String,
+        on_behalf_of: Option<String>,
+    },
+    // bid from an intent signed by the bidder, the sent tokens must match its bid price
+    BidOnBehalf {
+        intent: BidIntent,
+        signature: Binary,
+    },
+    // Offer to buy a token outside of an auction
+    MakeOffer {
+        nft_addr: String,
+        token_id: String,
+    },
+    // buy the nft at the buy-now price of the listing
+    BuyNow {
+        listing_id: String,
+    },
+    // buy as many raffle tickets as the sent amount pays for
+    BuyTickets {
+        raffle_id: u64,
     },
 }
 
+// list queries take the `next_cursor` of the previous page as `start_after`
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    // the v2 queries, see `v2::QueryMsg`
+    V2(v2::QueryMsg),
     Config {},
     // Resolve listing returns all the details of a listing
     ResolveListing { id: String },
     // query minters
-    QueryMinter {},
+    QueryMinter {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // whitelisting of a minter and the audit trail of its changes
+    MinterInfo {
+        minter: String,
+    },
+    // pending minter applications, oldest applicant address first
+    MinterApplications {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
     // query nft info
     QueryNftInfo { 
         token_id: String, 
@@ -78,6 +650,282 @@ pub enum QueryMsg {
     },
     // query all auction ids
     AllAuctionIds{},
+    // query the cheapest active listing of a collection per price asset
+    FloorPrice {
+        collection: String,
+    },
+    // query the collections, first active listings, sales volume, unclaimed payouts and minter status
+    // of a creator in one response
+    CreatorOverview {
+        address: String,
+    },
+    // query the currently featured listings, ordered by listing id
+    FeaturedListings {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the active listings with a tag, ordered by listing id
+    ListingsByTag {
+        tag: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the active listings of a collection, ordered by listing id
+    ListingsByCollection {
+        collection: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the highest active bids across all listings
+    TopBids {
+        limit: Option<u32>,
+    },
+    // query the listings and collections watched by an address
+    Watchlist {
+        address: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the payouts made when a listing was settled
+    SettlementReceipt {
+        listing_id: String,
+    },
+    // query whether the nft of a listing is still held for settlement, listings failing it can be
+    // voided with `InvalidateListing`
+    VerifyEscrow {
+        listing_id: String,
+    },
+    // query an amount of a denom converted to another at the rate quoted by the price oracle,
+    // approximate values for display
+    ConvertAmount {
+        from_denom: String,
+        to_denom: String,
+        amount: Uint128,
+    },
+    // query the winners and prices of the sales settled between two heights, both included,
+    // ordered by settlement height
+    Sales {
+        start_height: u64,
+        end_height: u64,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // raw storage export of a section, keys relative to the section, for state sync and migration checks
+    Export {
+        section: ExportSection,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the recent config changes, oldest first
+    ConfigHistory {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the nft deliveries that failed on settlement
+    DeadLetters {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query how the contract's balance of an asset is accounted for
+    Ledger {
+        asset_info: AssetInfo,
+    },
+    // query the royalties accrued for an address
+    AccruedRoyalties {
+        address: String,
+    },
+    // query the escrowed transfers claimable by an address
+    Escrow {
+        address: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query the native denoms accepted for listings and bids with their display metadata
+    AcceptedDenoms {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query how the current highest bid of a listing would be paid out
+    SettlementPreview {
+        listing_id: String,
+    },
+    // query the settled sales volume of a seller
+    SellerStats {
+        seller: String,
+    },
+    // query the fee promotion of a collection
+    CollectionFeeOverride {
+        collection: String,
+    },
+    // query the levy of a collection
+    CollectionLevy {
+        collection: String,
+    },
+    // query the buy-now floor of resales of a collection
+    CollectionPriceFloor {
+        collection: String,
+    },
+    // query the token id format of a collection and the last serial minted under it
+    CollectionTokenIdFormat {
+        collection: String,
+    },
+    // query the provenance commitment of a collection
+    CollectionProvenance {
+        collection: String,
+    },
+    // query the cw721 variant of a collection
+    CollectionVariant {
+        collection: String,
+    },
+    // query the reserve price of a listing that closed without meeting it, unset while it runs
+    // and for confidential reserves
+    RevealedReserve {
+        listing_id: String,
+    },
+    // query the relists left of a listing, unset once none are left
+    AutoRelist {
+        listing_id: String,
+    },
+    // query the address a listing is offered to, unset without a pending transfer
+    ListingTransfer {
+        listing_id: String,
+    },
+    // query the pause of a collection, unset when listings and mints are open
+    CollectionPause {
+        collection: String,
+    },
+    // query whether an address is an approved charity
+    Charity {
+        address: String,
+    },
+    // query the nonce the next relayed intent of a seller or bidder must carry
+    IntentNonce {
+        signer: String,
+    },
+    // query the quarantined nft of a token
+    Quarantined {
+        nft_addr: String,
+        token_id: String,
+    },
+    // query the nft held in custody for its owner
+    Custody {
+        nft_addr: String,
+        token_id: String,
+    },
+    // query the security deposit posted for a listing
+    SecurityDeposit {
+        listing_id: String,
+    },
+    Raffle {
+        raffle_id: u64,
+    },
+    // query the nonce the next listing of a token is hashed with
+    ListingNonce {
+        nft_addr: String,
+        token_id: String,
+    },
+    // query the chain of custody of a token as recorded by this contract
+    Provenance {
+        token_id: String,
+        nft_addr: String,
+    },
+    // query the loyalty points of an address, at a snapshot height when set
+    Points {
+        address: String,
+        height: Option<u64>,
+    },
+    // export loyalty balances, at a snapshot height when set
+    AllPoints {
+        height: Option<u64>,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    // query a saved listing template of a seller
+    ListingTemplate {
+        seller: String,
+        name: String,
+    },
+    // query the operator bidding on behalf of off-chain clients on a listing, if any
+    ListingOperator {
+        listing_id: String,
+    },
+    // query the absentee bids lodged on a listing before its start, in lodging order
+    AbsenteeBids {
+        listing_id: String,
+    },
+    // query the steps of an increment schedule
+    IncrementSchedule {
+        name: String,
+    },
+    // cw721 owner and nft info of a token together with its live listing and open offers
+    TokenFullInfo {
+        contract_addr: String,
+        token_id: String,
+    },
+    Offer {
+        offer_id: u64,
+    },
+    // query the auto-accept price applying to a token for its current owner
+    AutoAcceptPrice {
+        nft_addr: String,
+        token_id: String,
+    },
+    // query the subsystems and limits of this deployment
+    Features {},
+}
+
+// execute interface expected from the randomness provider, which answers with ReceiveRandomness
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomnessProviderMsg {
+    RequestRandomness { job_id: String },
+}
+
+// query interface expected from the compliance contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceQueryMsg {
+    IsAttested { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsAttestedResponse {
+    pub attested: bool,
+}
+
+// execute interface expected from the yield vault, deposits are the native funds sent along
+// and withdrawals are sent back to the depositor
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldVaultMsg {
+    Deposit {},
+    Withdraw { amount: Uint128 },
+}
+
+// query interface expected from the yield vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldVaultQueryMsg {
+    // deposits of the address with the yield accrued on them
+    Balance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldBalanceResponse {
+    pub amount: Uint128,
+}
+
+// query interface expected from the price oracle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    ExchangeRate { base_denom: String, quote_denom: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    // units of the quote denom one unit of the base denom is worth
+    pub rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -100,11 +948,41 @@ pub struct GFMintMsg{
     pub royalties: Vec<Royalty>,
     // initial ask price
     pub init_price: Uint128,
+    // asset the initial price is denominated in, first listings in another asset need the minter's approval
+    #[serde(default)]
+    pub init_price_asset: Option<AssetInfo>,
     // nft address of specified collection
-    pub nft_addr: String
+    pub nft_addr: String,
+    // false mints a token that can not be listed on the auction
+    #[serde(default = "default_transferable")]
+    pub transferable: bool,
+    // translations of the name, one per language code
+    #[serde(default)]
+    pub localized_names: Vec<LocalizedString>,
+    // translations of the description, one per language code
+    #[serde(default)]
+    pub localized_descriptions: Vec<LocalizedString>,
+    // mint the token into the auction's custody, to be listed by the owner without another transfer
+    #[serde(default)]
+    pub custody: bool,
+}
+
+fn default_transferable() -> bool {
+    true
 }
 
 // We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingStatus {
+    // bids are accepted up to and including block_limit
+    Active,
+    // the candle auction ended but its effective end was not drawn yet
+    CandleEndPending,
+    // ready to be withdrawn or settled
+    Ended,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ResolveListingResponse {
     pub token_id: String,
@@ -118,4 +996,424 @@ pub struct ResolveListingResponse {
     pub max_bidder: Addr,
 
     pub block_limit: u64,
+
+    pub decimals: Option<u8>,
+
+    // set while the nft can still be bought now
+    pub buy_now_price: Option<Uint128>,
+
+    // effective end of a candle auction once drawn
+    pub candle_end: Option<u64>,
+
+    // launchpad that placed the listing for the seller
+    pub listed_by: Option<Addr>,
+
+    // derived at query time
+    pub status: ListingStatus,
+
+    // blocks left until block_limit, zero once it is reached
+    pub blocks_remaining: u64,
+
+    // anti-sniping extensions the listing received
+    pub extensions_used: u32,
+
+    // accepted denom info of native prices
+    pub accepted_denom: Option<DenomInfo>,
+
+    // a bid was placed and reaches the reserve price, the listing sells when settled
+    pub reserve_met: bool,
+
+    // smallest amount a bid is accepted with, unset once bidding ended
+    pub min_next_bid: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenFullInfoResponse {
+    pub access: OwnerOfResponse,
+
+    pub info: NftInfoResponse<Metadata>,
+
+    pub listing_id: Option<String>,
+
+    pub listing: Option<ResolveListingResponse>,
+
+    // oldest first, at most `MAX_LIMIT`
+    pub offers: Vec<Offer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionListing {
+    pub listing_id: String,
+
+    pub listing: ResolveListingResponse,
+}
+
+// lists hold their first page, the paginated queries return the rest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorOverviewResponse {
+    pub address: Addr,
+
+    // collections the address minted into through the contract
+    pub collections: Vec<Addr>,
+
+    pub active_listings: Vec<CollectionListing>,
+
+    // settled sales volume as seller, by asset
+    pub sales: Vec<SellerStats>,
+
+    // royalties and other payouts kept in escrow until claimed
+    pub unclaimed_payouts: Vec<EscrowEntry>,
+
+    // royalties accrued while royalty payouts are deferred
+    pub accrued_royalties: Vec<Asset>,
+
+    pub minter: Option<MinterInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeaturedListing {
+    pub listing_id: String,
+
+    // last height the listing is featured at
+    pub until: u64,
+
+    pub listing: ResolveListingResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeaturedListingsResponse {
+    pub listings: Vec<FeaturedListing>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsByTagResponse {
+    pub tag: String,
+
+    pub listings: Vec<CollectionListing>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsByCollectionResponse {
+    pub collection: String,
+
+    pub listings: Vec<CollectionListing>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FloorPrice {
+    pub listing_id: String,
+
+    pub price: Asset,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FloorPriceResponse {
+    pub collection: String,
+
+    pub floors: Vec<FloorPrice>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TopBidsResponse {
+    pub bids: Vec<TopBid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WatchlistResponse {
+    pub targets: Vec<WatchTarget>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportSection {
+    Listings,
+    Offers,
+    // settlement receipts of past sales
+    Sales,
+    Escrow,
+    // hot bid fields kept apart from the listings
+    ListingBids,
+    // per asset accounting of the contract's balance
+    Ledger,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportEntry {
+    pub key: Binary,
+
+    // the value exactly as stored
+    pub value: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportResponse {
+    pub section: ExportSection,
+
+    pub entries: Vec<ExportEntry>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigHistoryResponse {
+    pub changes: Vec<ConfigChange>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sale {
+    pub listing_id: String,
+
+    pub winner: Addr,
+
+    pub price: Asset,
+
+    // height the sale was settled at
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyEscrowResponse {
+    // the nft stays with the seller under an approval until the first bid
+    pub escrowless: bool,
+
+    // current owner of the nft, none when the cw721 contract doesn't know the token
+    pub owner: Option<Addr>,
+
+    // whether the contract is approved for the nft of an escrowless listing
+    pub contract_approved: bool,
+
+    // the nft can be delivered on settlement, `InvalidateListing` fails while it can
+    pub intact: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConvertAmountResponse {
+    pub amount: Uint128,
+
+    pub rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SalesResponse {
+    pub sales: Vec<Sale>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DeadLettersResponse {
+    pub dead_letters: Vec<DeadLetter>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LedgerResponse {
+    pub asset_info: AssetInfo,
+
+    pub bids: Uint128,
+
+    pub refunds: Uint128,
+
+    pub fees: Uint128,
+
+    pub royalties: Uint128,
+
+    pub deposits: Uint128,
+
+    // current balance of the contract with its yield vault principal, at least the sum of the buckets
+    pub balance: Uint128,
+
+    // part of the balance deposited in the yield vault
+    pub yield_deposits: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccruedRoyaltiesResponse {
+    pub royalties: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowResponse {
+    pub entries: Vec<EscrowEntry>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AcceptedDenomsResponse {
+    pub denoms: Vec<DenomInfo>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutPreview {
+    pub recipient: Addr,
+
+    pub asset: Asset,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettlementPreviewResponse {
+    pub listing_id: String,
+
+    pub price: Asset,
+
+    // first sale of the token through the contract, paid out without royalties
+    pub primary_sale: bool,
+
+    // platform fee rate effective for the seller
+    pub fee_rate: Decimal,
+
+    // the buyer or the seller holds a membership token, already reflected in fee_rate
+    pub membership_discount: bool,
+
+    pub fee: PayoutPreview,
+
+    pub royalties: Vec<PayoutPreview>,
+
+    // collection levy snapshotted on the listing
+    pub levy: Option<PayoutPreview>,
+
+    // shares of the seller proceeds paid to other addresses
+    pub payout_splits: Vec<PayoutPreview>,
+
+    pub seller_proceeds: PayoutPreview,
+
+    // paid by the buyer on top of the price to the fee collector, none without a buyer premium
+    pub buyer_premium: Option<PayoutPreview>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SellerStatsResponse {
+    pub seller: String,
+
+    pub stats: Vec<SellerStats>,
+
+    pub active_listings: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PointsResponse {
+    pub address: Addr,
+
+    pub points: Uint128,
+
+    // snapshot height of the balance, the current balance when unset
+    pub height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllPointsResponse {
+    pub height: Option<u64>,
+
+    pub balances: Vec<PointsResponse>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintersResponse {
+    pub minters: Vec<String>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterApplicationsResponse {
+    pub applications: Vec<MinterApplication>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterInfoResponse {
+    pub minter: String,
+
+    // unset once the minter was removed or pruned
+    pub info: Option<MinterInfo>,
+
+    pub audit: Vec<MinterAudit>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvenanceResponse {
+    pub token_id: String,
+
+    pub nft_addr: String,
+
+    // oldest first
+    pub events: Vec<ProvenanceEvent>,
+}
+
+// packet data sent over the results channel when a listing settles
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettlementPacket {
+    pub listing_id: String,
+
+    pub token_id: String,
+
+    pub contract_addr: Addr,
+
+    pub winner: Addr,
+
+    pub price: Asset,
+
+    pub block_height: u64,
+}
+
+// what a deployment supports, for front-ends to adapt to without knowing the chain they're on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeaturesResponse {
+    // bids and listings paid in cw20 tokens through `Receive`
+    pub cw20_bids: bool,
+
+    // standing offers on tokens, off once the contract is sunset
+    pub offers: bool,
+
+    // candle auctions and raffles, need a randomness provider
+    pub candle_auctions: bool,
+
+    pub raffles: bool,
+
+    // descending price auctions and rentals aren't part of this contract
+    pub dutch_auctions: bool,
+
+    pub rentals: bool,
+
+    // settlement results sent over IBC, needs the `ibc` build and a results channel
+    pub ibc_results: bool,
+
+    // highest sum of royalty rates a minted token may carry
+    pub max_royalty_rate: Decimal,
+
+    // most listings a batch message may take
+    pub max_batch_size: u32,
+
+    // most entries a list query returns
+    pub max_page_limit: u32,
+
+    pub max_listing_tags: u32,
+
+    pub max_auction_duration_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenIdFormatResponse {
+    // unset while mints get `GF.<count>` ids
+    pub format: Option<String>,
+
+    // zero before the first mint under a format
+    pub last_serial: u64,
 }
@@ -0,0 +1,128 @@
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::Asset;
+use crate::msg::{ExecuteMsg as V1ExecuteMsg, QueryMsg as V1QueryMsg};
+
+// second version of the messages, sent wrapped as `{"v2": ...}`. unknown fields are rejected
+// and parameters defaulted in v1 are spelled out. each message converts into the v1 message
+// handling it, so the v1 variants keep working unchanged next to it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ExecuteMsg {
+    PlaceListing {
+        token_id: String,
+        nft_addr: String,
+        minimum_bid: Asset,
+        escrowless: bool,
+        template: Option<String>,
+    },
+    // bid in a native denom, cw20 bids go through the `Receive` hook
+    Bid {
+        listing_id: String,
+        price: Asset,
+        on_behalf_of: Option<String>,
+    },
+    BuyNow {
+        listing_id: String,
+        price: Asset,
+    },
+    // settle both legs of a listing, see v1 `WithdrawListing`
+    Settle {
+        listing_id: String,
+    },
+    // settle one leg of a sold listing, see v1 `ClaimItem` and `ClaimProceeds`
+    Claim {
+        listing_id: String,
+        leg: SettlementLeg,
+    },
+    CancelListings {
+        listing_ids: Vec<String>,
+    },
+    MakeOffer {
+        nft_addr: String,
+        token_id: String,
+        price: Asset,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    AcceptOffer {
+        offer_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementLeg {
+    // the nft, pulled by the winner
+    Item,
+    // the proceeds, pulled by the seller
+    Proceeds,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum QueryMsg {
+    Config {},
+    Features {},
+    // returns a v1 `ResolveListingResponse`
+    Listing {
+        listing_id: String,
+    },
+    ListingsByCollection {
+        collection: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    ListingsByTag {
+        tag: String,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    FeaturedListings {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    Offer {
+        offer_id: u64,
+    },
+}
+
+impl From<ExecuteMsg> for V1ExecuteMsg {
+    fn from(msg: ExecuteMsg) -> Self {
+        match msg {
+            ExecuteMsg::PlaceListing { token_id, nft_addr, minimum_bid, escrowless, template } => V1ExecuteMsg::PlaceListing {
+                id: token_id,
+                minimum_bid,
+                nft_addr,
+                escrowless: Some(escrowless),
+                template,
+            },
+            ExecuteMsg::Bid { listing_id, price, on_behalf_of } => V1ExecuteMsg::BidListing { listing_id, bid_price: price, on_behalf_of },
+            ExecuteMsg::BuyNow { listing_id, price } => V1ExecuteMsg::BuyNow { listing_id, price },
+            ExecuteMsg::Settle { listing_id } => V1ExecuteMsg::WithdrawListing { listing_id },
+            ExecuteMsg::Claim { listing_id, leg: SettlementLeg::Item } => V1ExecuteMsg::ClaimItem { listing_id },
+            ExecuteMsg::Claim { listing_id, leg: SettlementLeg::Proceeds } => V1ExecuteMsg::ClaimProceeds { listing_id },
+            ExecuteMsg::CancelListings { listing_ids } => V1ExecuteMsg::CancelListings { listing_ids },
+            ExecuteMsg::MakeOffer { nft_addr, token_id, price } => V1ExecuteMsg::MakeOffer { nft_addr, token_id, price },
+            ExecuteMsg::CancelOffer { offer_id } => V1ExecuteMsg::CancelOffer { offer_id },
+            ExecuteMsg::AcceptOffer { offer_id } => V1ExecuteMsg::AcceptOffer { offer_id },
+        }
+    }
+}
+
+impl From<QueryMsg> for V1QueryMsg {
+    fn from(msg: QueryMsg) -> Self {
+        match msg {
+            QueryMsg::Config {} => V1QueryMsg::Config {},
+            QueryMsg::Features {} => V1QueryMsg::Features {},
+            QueryMsg::Listing { listing_id } => V1QueryMsg::ResolveListing { id: listing_id },
+            QueryMsg::ListingsByCollection { collection, start_after, limit } => V1QueryMsg::ListingsByCollection { collection, start_after, limit },
+            QueryMsg::ListingsByTag { tag, start_after, limit } => V1QueryMsg::ListingsByTag { tag, start_after, limit },
+            QueryMsg::FeaturedListings { start_after, limit } => V1QueryMsg::FeaturedListings { start_after, limit },
+            QueryMsg::Offer { offer_id } => V1QueryMsg::Offer { offer_id },
+        }
+    }
+}
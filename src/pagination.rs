@@ -0,0 +1,38 @@
+use cosmwasm_std::Binary;
+use cw_storage_plus::{Bound, PrimaryKey};
+
+// list queries page with opaque cursors: the storage key of the last entry of a page, relative to
+// the range the page was read from. Clients pass `next_cursor` back as `start_after` and never
+// decode it, so the key encodings can change without breaking them
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+pub fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize
+}
+
+// the range bound of the page following the cursor
+pub fn start_bound<'a, K: PrimaryKey<'a>>(start_after: Option<Binary>) -> Option<Bound<'a, K>> {
+    start_after.map(|cursor| Bound::ExclusiveRaw(cursor.0))
+}
+
+pub fn cursor<'a, K: PrimaryKey<'a>>(key: K) -> Binary {
+    Binary(key.joined_key())
+}
+
+// the items of a page with the cursor of the next one, unset once a page comes back short
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    pub next_cursor: Option<Binary>,
+}
+
+impl<T> Page<T> {
+    pub fn new(entries: Vec<(Binary, T)>, limit: usize) -> Self {
+        let next_cursor = match entries.last() {
+            Some((cursor, _)) if entries.len() == limit => Some(cursor.clone()),
+            _ => None,
+        };
+        Page { items: entries.into_iter().map(|(_, item)| item).collect(), next_cursor }
+    }
+}
@@ -1,13 +1,21 @@
+use std::collections::BTreeMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Storage, StdResult, Decimal, Uint128, Order};
+use cosmwasm_std::{Addr, Binary, Storage, StdError, StdResult, Decimal, Uint128, Order, Empty, Timestamp, BlockInfo, CosmosMsg};
+use cw721::Expiration;
 use cosmwasm_storage::{
-    bucket, bucket_read, Bucket, ReadonlyBucket, ReadonlySingleton,
-    Singleton, prefixed
+    bucket, bucket_read, to_length_prefixed, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton
 };
-use cw_storage_plus::Map;
-use crate::asset::Asset;
+use cw_storage_plus::{Map, Item, Bound, SnapshotMap, Strategy};
+use sha2::{Digest, Sha256};
+use crate::asset::{Asset, AssetInfo};
+use crate::contract::{DEFAULT_APPROVAL_WINDOW_BLOCKS, MAX_CONFIG_HISTORY, MAX_WATCHERS};
+use crate::ids::next_id;
+use crate::math::payout_share;
+use crate::pagination::{cursor, start_bound, Page};
 
 pub static CONFIG_KEY: &[u8] = b"config";
 pub static LIST_RESOLVER_KEY: &[u8] = b"listingresolver";
@@ -18,9 +26,203 @@ pub static CONFIG_NFT: &[u8] = b"nft";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    // shared listing and token counter before `ids`, only read to seed the id counters on migration
     pub listing_count: u64,
     pub owner: String,
     pub max_aution_duration_blocks: u64,
+    // platform fee tiers by cumulative settled seller volume, ascending by min_volume
+    #[serde(default)]
+    pub fee_tiers: Vec<FeeTier>,
+    // receiver of platform fees, the owner when unset
+    #[serde(default)]
+    pub fee_collector: Option<String>,
+    // platform fee of first sales through the contract, the tiers apply when unset
+    #[serde(default)]
+    pub primary_fee_rate: Option<Decimal>,
+    // may manage collection fee promotions besides the owner
+    #[serde(default)]
+    pub fee_manager: Option<String>,
+    // deposit required from sellers of physically backed nfts
+    #[serde(default)]
+    pub security_deposit: Option<DepositConfig>,
+    // attestation contract sellers and bidders must be attested by
+    #[serde(default)]
+    pub compliance_contract: Option<String>,
+    // collection whose holders pay reduced platform fees
+    #[serde(default)]
+    pub membership: Option<MembershipConfig>,
+    // contract answering randomness requests of candle auctions
+    #[serde(default)]
+    pub randomness_provider: Option<String>,
+    // new listings are stored under `hashed_listing_id` instead of the numeric id
+    #[serde(default)]
+    pub hashed_listing_ids: bool,
+    // may approve and reject minter applications besides the owner
+    #[serde(default)]
+    pub minter_curator: Option<String>,
+    // blocks past the end of an escrowless listing its seller's approval of this contract must last
+    #[serde(default = "default_approval_window_blocks")]
+    pub approval_window_blocks: u64,
+    // blocks after the end of an auction before addresses other than the seller and winner may settle it
+    #[serde(default)]
+    pub settlement_grace_blocks: u64,
+    // share of the platform fee paid to those third party settlers
+    #[serde(default)]
+    pub settlement_reward_rate: Option<Decimal>,
+    // blocks after the end of an unsold auction before the listing curator may return it to its seller
+    #[serde(default)]
+    pub unsold_grace_blocks: u64,
+    // per address limits on new listings and bids
+    #[serde(default)]
+    pub rate_limits: Option<RateLimits>,
+    // royalties are credited to the royalty ledger for their beneficiaries to claim instead of sent
+    #[serde(default)]
+    pub defer_royalties: bool,
+    // may feature listings besides the owner
+    #[serde(default)]
+    pub listing_curator: Option<String>,
+    // IBC channel settlement results are sent to, requires the `ibc` feature
+    #[serde(default)]
+    pub results_channel: Option<String>,
+    // the highest bidder may bid again without raising its bid
+    #[serde(default)]
+    pub allow_bid_restatement: bool,
+    // platform fees are kept in the ledger for the fee manager to withdraw instead of sent
+    #[serde(default)]
+    pub retain_fees: bool,
+    // listings an address may have active at once, unlimited when unset
+    #[serde(default)]
+    pub max_active_listings: Option<u32>,
+    // extensions of auctions receiving bids right before their end, none when unset
+    #[serde(default)]
+    pub anti_sniping: Option<AntiSniping>,
+    // highest sum of royalty rates a minted token may carry, up to 100% when unset
+    #[serde(default)]
+    pub max_royalty_rate: Option<Decimal>,
+    // contract quoting the exchange rates amounts are converted with
+    #[serde(default)]
+    pub price_oracle: Option<String>,
+    // height `BeginSunset` was run at, no new listings or offers are taken from then on
+    #[serde(default)]
+    pub sunset_height: Option<u64>,
+    // reserve prices of auctions closing without meeting them are published, they stay confidential otherwise
+    #[serde(default)]
+    pub reveal_reserves: bool,
+    // whitelisted contract idle native funds are deposited into, none when unset
+    #[serde(default)]
+    pub yield_vault: Option<YieldVault>,
+    // share of the hammer price bidders and buyers pay on top of it for the fee collector, none when unset
+    #[serde(default)]
+    pub buyer_premium: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldVault {
+    pub contract: String,
+
+    pub denom: String,
+
+    // funds kept on hand for refunds and settlements, only the balance above it is deposited
+    pub buffer: Uint128,
+}
+
+// a bid within `window_blocks` of the end moves the end to `extension_blocks` after the bid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AntiSniping {
+    pub window_blocks: u64,
+
+    pub extension_blocks: u64,
+
+    // extensions a listing may receive, unlimited when unset
+    pub max_extensions: Option<u32>,
+
+    // blocks a listing may be extended by in total, unlimited when unset
+    pub max_extension_blocks: Option<u64>,
+}
+
+fn default_approval_window_blocks() -> u64 {
+    DEFAULT_APPROVAL_WINDOW_BLOCKS
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimits {
+    // new listings an address may place within `listing_window_blocks`, unlimited when unset
+    pub max_listings: Option<u32>,
+
+    pub listing_window_blocks: u64,
+
+    // bids an address may place within a single block, unlimited when unset
+    pub max_bids_per_block: Option<u32>,
+}
+
+// actions of an address in the window starting at its first action after the previous window ended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateCounter {
+    pub window_start: u64,
+
+    pub count: u32,
+}
+
+pub const LISTING_RATE: Map<&Addr, RateCounter> = Map::new("listingrate");
+// seller -> listings currently active, kept by store_listing and remove_listing
+pub const ACTIVE_LISTINGS: Map<&Addr, u32> = Map::new("activelistings");
+pub const BID_RATE: Map<&Addr, RateCounter> = Map::new("bidrate");
+
+// counts an action of `addr` at `height`, returning the counter of its current window
+pub fn bump_rate_counter(
+    storage: &mut dyn Storage,
+    counters: &Map<&Addr, RateCounter>,
+    addr: &Addr,
+    height: u64,
+    window_blocks: u64,
+) -> StdResult<RateCounter> {
+    let counter = match counters.may_load(storage, addr)? {
+        Some(counter) if height < counter.window_start + window_blocks => RateCounter {
+            window_start: counter.window_start,
+            count: counter.count + 1,
+        },
+        _ => RateCounter { window_start: height, count: 1 },
+    };
+    counters.save(storage, addr, &counter)?;
+    Ok(counter)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MembershipConfig {
+    pub collection: String,
+
+    // share of the platform fee waived when the buyer or the seller holds a membership token
+    pub discount: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositConfig {
+    // native amount posted with the listing
+    pub amount: Asset,
+
+    // blocks after settlement during which the arbiter can slash the deposit to the buyer
+    pub dispute_window_blocks: u64,
+
+    pub arbiter: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTier {
+    // seller volume, in units of the settled asset, from which the tier applies
+    pub min_volume: Uint128,
+    pub fee_rate: Decimal,
+}
+
+impl Config {
+    // fee rate of the highest tier reached by the seller volume
+    pub fn fee_rate(&self, seller_volume: Uint128) -> Decimal {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_volume <= seller_volume)
+            .map(|tier| tier.fee_rate)
+            .unwrap_or_else(Decimal::zero)
+    }
 }
 
 pub fn store_config(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
@@ -31,28 +233,124 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     ReadonlySingleton::new(storage, CONFIG_KEY).load()
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigChange {
+    pub id: u64,
+
+    pub by: Addr,
+
+    pub height: u64,
+
+    pub time: Timestamp,
+
+    // unset for the config stored on instantiation
+    pub old: Option<Config>,
+
+    pub new: Config,
+}
+
+// the last `MAX_CONFIG_HISTORY` config changes
+pub const CONFIG_HISTORY: Map<u64, ConfigChange> = Map::new("confighistory");
+pub const CONFIG_CHANGE_COUNT: Item<u64> = Item::new("configchangecount");
+
+// stores the config and records the change in the config history
+pub fn store_config_change(storage: &mut dyn Storage, block: &BlockInfo, by: &Addr, config: &Config) -> StdResult<()> {
+    let old = ReadonlySingleton::new(storage, CONFIG_KEY).may_load()?;
+    store_config(storage, config)?;
+
+    let id = next_id(storage, &CONFIG_CHANGE_COUNT)?;
+    CONFIG_HISTORY.save(storage, id, &ConfigChange {
+        id,
+        by: by.clone(),
+        height: block.height,
+        time: block.time,
+        old,
+        new: config.clone(),
+    })?;
+    if id > MAX_CONFIG_HISTORY {
+        CONFIG_HISTORY.remove(storage, id - MAX_CONFIG_HISTORY);
+    }
+    Ok(())
+}
+
+pub fn read_config_history(storage: &dyn Storage, start_after: Option<Binary>, limit: usize) -> StdResult<Page<ConfigChange>> {
+    let changes = CONFIG_HISTORY
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(sequence, change)| (cursor(sequence), change)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(changes, limit))
+}
+
+// shares the CONFIG_MINTER namespace the minters were stored under as a bucket
+pub const MINTERS: Map<&str, MinterInfo> = Map::new("minters");
+
 pub fn store_minters(storage: &mut dyn Storage, minter: Addr, minter_info: MinterInfo) -> StdResult<()> {
-    bucket(storage, CONFIG_MINTER).save(minter.as_bytes(), &minter_info)
+    MINTERS.save(storage, minter.as_str(), &minter_info)
 }
 
 pub fn remove_minter(storage: &mut dyn Storage, minter: Addr) -> StdResult<()> {
-    prefixed(storage, CONFIG_MINTER).remove(minter.as_bytes());
+    MINTERS.remove(storage, minter.as_str());
     Ok(())
 }
 
-pub const MINTERS: Map<&str, MinterInfo> = Map::new("minters");
+pub fn read_minters(storage: &dyn Storage, start_after: Option<Binary>, limit: usize) -> StdResult<Page<String>> {
+    let minters = MINTERS
+        .keys(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|minter| minter.map(|minter| (cursor(minter.as_str()), minter)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(minters, limit))
+}
 
-pub fn read_minters(storage: &dyn Storage) -> StdResult<Vec<String>> {
+// minters whose whitelisting expired, oldest address first
+pub fn read_expired_minters(storage: &dyn Storage, block: &BlockInfo, limit: usize) -> StdResult<Vec<String>> {
     MINTERS
-        .keys(storage, None, None, Order::Ascending)
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| item.as_ref().map_or(true, |(_, info)| info.is_expired(block)))
+        .take(limit)
+        .map(|item| item.map(|(minter, _)| minter))
         .collect()
 }
 
 pub fn read_minter_info(storage: &dyn Storage, minter: Addr) -> Option<MinterInfo> {
-    match bucket_read(storage, CONFIG_MINTER).load(minter.as_bytes()){
-        Ok(v) => Some(v),
-        _ => None
-    }
+    MINTERS.may_load(storage, minter.as_str()).ok().flatten()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterApplication {
+    pub applicant: Addr,
+
+    pub metadata_uri: String,
+
+    pub height: u64,
+}
+
+// applicant -> pending minter application
+pub const MINTER_APPLICATIONS: Map<&Addr, MinterApplication> = Map::new("minterapplications");
+
+pub fn read_minter_applications(storage: &dyn Storage, start_after: Option<Binary>, limit: usize) -> StdResult<Page<MinterApplication>> {
+    let applications = MINTER_APPLICATIONS
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(applicant, application)| (cursor(&applicant), application)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(applications, limit))
+}
+
+// partner contracts allowed to place listings for sellers they verified
+pub const LAUNCHPADS: Map<&Addr, Empty> = Map::new("launchpads");
+
+// seller or bidder -> nonce their next relayed intent must carry
+pub const INTENT_NONCES: Map<&Addr, u64> = Map::new("intentnonces");
+
+// minter -> whitelist changes, oldest first, kept after the minter is removed
+pub const MINTER_AUDIT: Map<&str, Vec<MinterAudit>> = Map::new("minteraudit");
+
+pub fn append_minter_audit(storage: &mut dyn Storage, minter: &Addr, entry: MinterAudit) -> StdResult<()> {
+    let mut audit = MINTER_AUDIT.may_load(storage, minter.as_str())?.unwrap_or_default();
+    audit.push(entry);
+    MINTER_AUDIT.save(storage, minter.as_str(), &audit)
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -68,6 +366,461 @@ pub struct Listing {
     pub max_bidder: Addr,
 
     pub block_limit: u64,
+
+    // platform fee rate of a collection promotion active when the listing was placed
+    #[serde(default)]
+    pub fee_override: Option<Decimal>,
+
+    // the nft stays with the seller, under approval, until the first bid pulls it into escrow
+    #[serde(default)]
+    pub pending_escrow: bool,
+
+    // share of the current bid a new bid has to exceed it by
+    #[serde(default)]
+    pub min_increment: Option<Decimal>,
+
+    // lowest winning bid, the nft goes back to the seller and the bid is refunded below it
+    #[serde(default)]
+    pub reserve_price: Option<Uint128>,
+
+    // shares of the seller proceeds paid out to other addresses
+    #[serde(default)]
+    pub payout_splits: Vec<PayoutSplit>,
+
+    // decimals of the bid denom from the accepted denoms, none for cw20 bids
+    #[serde(default)]
+    pub decimals: Option<u8>,
+
+    // held deposit allowing the seller to cancel after bids, paid to the highest bidder then
+    #[serde(default)]
+    pub cancel_penalty: Option<Asset>,
+
+    // immediate sale price, see ListingTemplate
+    #[serde(default)]
+    pub buy_now_price: Option<Uint128>,
+
+    #[serde(default)]
+    pub buy_now_lock: Option<Decimal>,
+
+    // closing window of a candle auction, see ListingTemplate
+    #[serde(default)]
+    pub candle_window: Option<u64>,
+
+    // effective end height drawn from the randomness provider after the nominal end
+    #[serde(default)]
+    pub candle_end: Option<u64>,
+
+    // height the highest bid was placed at
+    #[serde(default)]
+    pub max_bid_height: u64,
+
+    // anti-sniping extensions of block_limit so far and the blocks they added
+    #[serde(default)]
+    pub extensions_used: u32,
+
+    #[serde(default)]
+    pub extended_blocks: u64,
+
+    // collection of the nft from its metadata at placement
+    #[serde(default)]
+    pub collection: String,
+
+    // launchpad that placed the listing for the seller
+    #[serde(default)]
+    pub listed_by: Option<Addr>,
+
+    // levy of the collection when the listing was placed
+    #[serde(default)]
+    pub levy: Option<CollectionLevy>,
+
+    // category tags set by the seller, indexed in `LISTINGS_BY_TAG`
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    // approved charity receiving the whole sale price, no fee, royalties or levy are taken
+    #[serde(default)]
+    pub charity: Option<Addr>,
+
+    // buyer premium of the config when the listing was placed
+    #[serde(default)]
+    pub buyer_premium: Option<Decimal>,
+
+    // steps of the increment schedule of the template when the listing was placed
+    #[serde(default)]
+    pub increment_steps: Vec<IncrementStep>,
+
+    // height live bidding opens at, absentee bids are taken before
+    #[serde(default)]
+    pub start_height: u64,
+}
+
+impl Listing {
+    // buyer premium owed on a hammer price of the listing
+    pub fn premium_on(&self, price: &Asset) -> Uint128 {
+        self.buyer_premium.map_or_else(Uint128::zero, |rate| payout_share(price.amount, rate))
+    }
+
+    // lowest next bid before the rules of the denom: the current bid raised by the increment rate
+    // or the scheduled increment for its amount once there is a bid
+    pub fn min_next_bid(&self, contract_addr: &Addr) -> Uint128 {
+        let current = self.max_bid.amount;
+        if self.max_bidder == *contract_addr {
+            return current;
+        }
+        let scheduled = self.increment_steps.iter()
+            .rev()
+            .find(|step| step.from <= current)
+            .map_or_else(Uint128::zero, |step| step.increment);
+        let rated = self.min_increment.map_or_else(Uint128::zero, |min_increment| current * min_increment);
+        current + scheduled.max(rated)
+    }
+
+    // what the contract holds for a bid, the bid and its buyer premium
+    pub fn with_premium(&self, bid: &Asset) -> Asset {
+        Asset { info: bid.info.clone(), amount: bid.amount + self.premium_on(bid) }
+    }
+
+    // buy-now price while buying now is possible, a bid at the lock share of the price or at the
+    // price itself protects the bidder from being bought out
+    pub fn buy_now_price(&self, contract_addr: &Addr) -> Option<Uint128> {
+        let price = self.buy_now_price?;
+        if self.max_bidder == *contract_addr {
+            return Some(price);
+        }
+
+        let lock = self.buy_now_lock.unwrap_or_else(Decimal::one).min(Decimal::one());
+        if self.max_bid.amount >= price * lock {
+            None
+        } else {
+            Some(price)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutSplit {
+    pub address: String,
+
+    // share of the seller proceeds
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingTemplate {
+    // auction duration, the configured maximum when unset
+    pub duration_blocks: Option<u64>,
+
+    pub min_increment: Option<Decimal>,
+
+    pub reserve_price: Option<Uint128>,
+
+    pub payout_splits: Vec<PayoutSplit>,
+
+    // native deposit paid to the highest bidder when the seller cancels after bids
+    #[serde(default)]
+    pub cancel_penalty: Option<Asset>,
+
+    // price in the listing asset at which the nft can be bought before the auction ends
+    #[serde(default)]
+    pub buy_now_price: Option<Uint128>,
+
+    // share of the buy-now price from which a bid disables buy-now for the rest of the auction
+    #[serde(default)]
+    pub buy_now_lock: Option<Decimal>,
+
+    // closing window before the nominal end in which a random height becomes the effective end
+    #[serde(default)]
+    pub candle_window_blocks: Option<u64>,
+
+    // charity approved by the listing curator the sale price passes through to
+    #[serde(default)]
+    pub charity: Option<String>,
+
+    // times an auction ending below the reserve price runs again, needs a reserve price
+    #[serde(default)]
+    pub auto_relists: Option<u32>,
+
+    // owner managed increment schedule, the larger of its increment and min_increment applies.
+    // Listings keep the steps the schedule had when they were placed
+    #[serde(default)]
+    pub increment_schedule: Option<String>,
+
+    // blocks after placement the auction opens at, bids before are lodged as absentee bids. The
+    // duration counts from the opening, escrowless listings open right away
+    #[serde(default)]
+    pub start_delay_blocks: Option<u64>,
+}
+
+// bids from `from` up to the next step's `from` have to be outbid by at least `increment`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncrementStep {
+    pub from: Uint128,
+
+    pub increment: Uint128,
+}
+
+// schedule name -> increment steps ascending by `from`, the first starting at zero
+pub const INCREMENT_SCHEDULES: Map<&str, Vec<IncrementStep>> = Map::new("incrementschedules");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CandleBid {
+    pub bidder: Addr,
+
+    pub bid: Asset,
+
+    pub height: u64,
+}
+
+// (listing id, index) -> outbid bids of a candle auction's closing window in bid order, held until
+// the effective end is known
+pub const HELD_BIDS: Map<(&str, u64), CandleBid> = Map::new("heldcandlebids");
+// listing id -> held bids, before they were stored one per key
+const LEGACY_HELD_BIDS: Map<&str, Vec<CandleBid>> = Map::new("heldbids");
+
+pub fn held_bid_count(storage: &dyn Storage, listing_id: &str) -> StdResult<u64> {
+    Ok(HELD_BIDS.prefix(listing_id).keys(storage, None, None, Order::Ascending).count() as u64)
+}
+
+pub fn has_held_bids(storage: &dyn Storage, listing_id: &str) -> bool {
+    HELD_BIDS.prefix(listing_id).keys(storage, None, None, Order::Ascending).next().is_some()
+}
+
+// holds an outbid bid after those held before it
+pub fn hold_bid(storage: &mut dyn Storage, listing_id: &str, held: &CandleBid) -> StdResult<()> {
+    let index = HELD_BIDS
+        .prefix(listing_id)
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    HELD_BIDS.save(storage, (listing_id, index), held)
+}
+
+// removes the earliest and lowest held bid of a listing, the caller refunds it
+pub fn drop_lowest_held_bid(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<Option<CandleBid>> {
+    let lowest = HELD_BIDS
+        .prefix(listing_id)
+        .range(storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?;
+    match lowest {
+        Some((index, held)) => {
+            HELD_BIDS.remove(storage, (listing_id, index));
+            debit_ledger(storage, LedgerBucket::Bids, &listing.with_premium(&held.bid))?;
+            Ok(Some(held))
+        }
+        None => Ok(None),
+    }
+}
+
+// removes the held bids of a listing, the caller refunds or settles them
+pub fn take_held_bids(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<Vec<CandleBid>> {
+    let held_bids = HELD_BIDS
+        .prefix(listing_id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (index, held) in held_bids.iter() {
+        HELD_BIDS.remove(storage, (listing_id, *index));
+        debit_ledger(storage, LedgerBucket::Bids, &listing.with_premium(&held.bid))?;
+    }
+    Ok(held_bids.into_iter().map(|(_, held)| held).collect())
+}
+
+// moves held bids stored as one list per listing to one entry per bid
+pub fn migrate_held_bids(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = LEGACY_HELD_BIDS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (listing_id, held_bids) in legacy {
+        for (index, held) in held_bids.iter().enumerate() {
+            HELD_BIDS.save(storage, (&listing_id, index as u64), held)?;
+        }
+        LEGACY_HELD_BIDS.remove(storage, &listing_id);
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AbsenteeBid {
+    pub bidder: Addr,
+
+    // highest amount the bidder pays, the auction may open below it
+    pub bid: Asset,
+
+    pub height: u64,
+}
+
+// (listing id, bidder) -> bid lodged before the auction's start, escrowed until it opens
+pub const ABSENTEE_BIDS: Map<(&str, &Addr), AbsenteeBid> = Map::new("absenteebids");
+
+pub fn has_absentee_bids(storage: &dyn Storage, listing_id: &str) -> bool {
+    ABSENTEE_BIDS.prefix(listing_id).keys_raw(storage, None, None, Order::Ascending).next().is_some()
+}
+
+// absentee bids of a listing in lodging order
+pub fn read_absentee_bids(storage: &dyn Storage, listing_id: &str) -> StdResult<Vec<AbsenteeBid>> {
+    let mut bids = ABSENTEE_BIDS
+        .prefix(listing_id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<_>>>()?;
+    bids.sort_by_key(|bid| bid.height);
+    Ok(bids)
+}
+
+// removes the absentee bids of a listing, the caller refunds them or opens the auction with them
+pub fn take_absentee_bids(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<Vec<AbsenteeBid>> {
+    let bids = read_absentee_bids(storage, listing_id)?;
+    for bid in bids.iter() {
+        ABSENTEE_BIDS.remove(storage, (listing_id, &bid.bidder));
+        debit_ledger(storage, LedgerBucket::Bids, &listing.with_premium(&bid.bid))?;
+    }
+    Ok(bids)
+}
+
+// (seller, template name) -> listing parameters referenced by PlaceListing
+pub const LISTING_TEMPLATES: Map<(&Addr, &str), ListingTemplate> = Map::new("listingtemplates");
+// charities templates may pass sale prices through to, approved by the owner or listing curator
+pub const CHARITIES: Map<&Addr, Empty> = Map::new("charities");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoRelist {
+    // minimum bid and duration every round starts over with
+    pub minimum_bid: Asset,
+
+    pub duration_blocks: u64,
+
+    pub remaining: u32,
+}
+
+// listing id -> relisting of an auction with relists left, removed with the listing
+pub const AUTO_RELISTS: Map<&str, AutoRelist> = Map::new("autorelists");
+
+// reserve price of an auction that closed without meeting it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevealedReserve {
+    pub reserve: Asset,
+
+    // highest bid the auction closed with, none without bids
+    pub max_bid: Option<Uint128>,
+
+    pub closed_height: u64,
+}
+
+// listing id -> reserve published when the listing closed unsold while reserves are revealed
+pub const REVEALED_RESERVES: Map<&str, RevealedReserve> = Map::new("revealedreserves");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Offer {
+    pub id: u64,
+
+    pub buyer: Addr,
+
+    pub nft_addr: Addr,
+
+    pub token_id: String,
+
+    // escrowed by the contract until the offer is accepted or cancelled
+    pub price: Asset,
+}
+
+pub const OFFERS: Map<u64, Offer> = Map::new("offers");
+// (nft contract, token id, offer id) of the open offers on a token
+pub const TOKEN_OFFERS: Map<(&str, &str, u64), Empty> = Map::new("tokenoffers");
+
+pub fn store_offer(storage: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    OFFERS.save(storage, offer.id, offer)?;
+    TOKEN_OFFERS.save(storage, (offer.nft_addr.as_str(), &offer.token_id, offer.id), &Empty {})?;
+    credit_ledger(storage, LedgerBucket::Bids, &offer.price)
+}
+
+pub fn remove_offer(storage: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+    OFFERS.remove(storage, offer.id);
+    TOKEN_OFFERS.remove(storage, (offer.nft_addr.as_str(), &offer.token_id, offer.id));
+    debit_ledger(storage, LedgerBucket::Bids, &offer.price)
+}
+
+pub fn read_token_offers(storage: &dyn Storage, nft_addr: &str, token_id: &str, limit: usize) -> StdResult<Vec<Offer>> {
+    TOKEN_OFFERS
+        .prefix((nft_addr, token_id))
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit)
+        .map(|offer_id| OFFERS.load(storage, offer_id?))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Raffle {
+    pub id: u64,
+
+    pub seller: Addr,
+
+    pub nft_addr: Addr,
+
+    pub token_id: String,
+
+    pub ticket_price: Asset,
+
+    // last height tickets can be bought at
+    pub end_height: u64,
+
+    // refund the tickets of the losers instead of paying them to the seller
+    pub refund_losers: bool,
+
+    pub tickets_sold: u32,
+
+    // height the winner was requested at, the raffle waits for the randomness provider from then
+    #[serde(default)]
+    pub draw_requested: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TicketPurchase {
+    pub buyer: Addr,
+
+    pub count: u32,
+}
+
+pub const RAFFLES: Map<u64, Raffle> = Map::new("raffles");
+pub const RAFFLE_COUNT: Item<u64> = Item::new("rafflecount");
+// (raffle id, number of the first ticket) -> purchase of consecutive tickets
+pub const RAFFLE_TICKETS: Map<(u64, u32), TicketPurchase> = Map::new("raffletickets");
+
+pub fn next_raffle_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    next_id(storage, &RAFFLE_COUNT)
+}
+
+// buyer of the ticket with the given number
+pub fn read_ticket_owner(storage: &dyn Storage, raffle_id: u64, ticket: u32) -> StdResult<Addr> {
+    let (_, purchase) = RAFFLE_TICKETS
+        .prefix(raffle_id)
+        .range(storage, None, Some(Bound::inclusive(ticket)), Order::Descending)
+        .next()
+        .ok_or_else(|| StdError::not_found("ticket"))??;
+    Ok(purchase.buyer)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoAccept {
+    // owner of the token when the price was set, the price lapses once the token changes hands
+    pub owner: Addr,
+
+    pub price: Asset,
+}
+
+// (nft contract, token_id) -> price from which offers on the token settle immediately
+pub const TOKEN_AUTO_ACCEPT: Map<(&str, &str), AutoAccept> = Map::new("tokenautoaccept");
+// (nft contract, owner) -> price from which offers on any token of the owner in the collection settle
+pub const COLLECTION_AUTO_ACCEPT: Map<(&str, &Addr), Asset> = Map::new("collectionautoaccept");
+
+// the token price takes precedence over the collection price of the owner
+pub fn read_auto_accept(storage: &dyn Storage, nft_addr: &str, token_id: &str, owner: &Addr) -> StdResult<Option<Asset>> {
+    if let Some(auto_accept) = TOKEN_AUTO_ACCEPT.may_load(storage, (nft_addr, token_id))? {
+        if auto_accept.owner == *owner {
+            return Ok(Some(auto_accept.price));
+        }
+    }
+    COLLECTION_AUTO_ACCEPT.may_load(storage, (nft_addr, owner))
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -78,6 +831,60 @@ pub struct Royalty {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MinterInfo {
+    // end of the whitelisting, never when unset
+    #[serde(default)]
+    pub expires: Option<Expiration>,
+
+    // sender and block of the last `UpdateMinter`, unset for minters added before they were recorded
+    #[serde(default)]
+    pub granted_by: Option<Addr>,
+
+    #[serde(default)]
+    pub granted_height: Option<u64>,
+
+    #[serde(default)]
+    pub granted_time: Option<Timestamp>,
+
+    // tokens the minter may mint, unlimited when unset
+    #[serde(default)]
+    pub quota: Option<u64>,
+
+    #[serde(default)]
+    pub minted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MinterAction {
+    Update,
+    Remove,
+    Prune,
+    Approve,
+    Reject,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterAudit {
+    pub action: MinterAction,
+
+    pub by: Addr,
+
+    pub height: u64,
+
+    pub time: Timestamp,
+
+    // expiration set by an update
+    pub expires: Option<Expiration>,
+}
+
+impl MinterInfo {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.map_or(false, |expires| expires.is_expired(block))
+    }
+
+    pub fn quota_exhausted(&self) -> bool {
+        self.quota.map_or(false, |quota| self.minted >= quota)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -88,7 +895,8 @@ pub struct Metadata{
     pub description: Option<String>,
     // An external URI
     pub external_link: Option<String>,
-    // A collection this NFT belongs to
+    // A collection this NFT belongs to, empty for tokens of standard cw721 collections
+    #[serde(default)]
     pub nft_addr: String,
     // # of real piece representations
     pub num_real_repr: Option<Uint128>,
@@ -98,6 +906,25 @@ pub struct Metadata{
     pub royalties: Option<Vec<Royalty>>,
     // initial ask price
     pub init_price: Option<Uint128>,
+    // asset the initial price is denominated in, unset for tokens minted before it was recorded
+    #[serde(default)]
+    pub init_price_asset: Option<AssetInfo>,
+    // false for certificates that must stay with their owner, unset means transferable
+    #[serde(default)]
+    pub transferable: Option<bool>,
+    // translations of the name, one per language code
+    #[serde(default)]
+    pub localized_names: Option<Vec<LocalizedString>>,
+    // translations of the description, one per language code
+    #[serde(default)]
+    pub localized_descriptions: Option<Vec<LocalizedString>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LocalizedString {
+    // BCP 47 language tag, e.g. en or pt-BR
+    pub lang: String,
+    pub text: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -119,10 +946,1212 @@ pub fn list_resolver_read(storage: &dyn Storage) -> ReadonlyBucket<Listing> {
     bucket_read(storage, LIST_RESOLVER_KEY)
 }
 
-pub fn read_auction_ids(storage: &dyn Storage) -> StdResult<Vec<String>> {
-    AUCTIONS
-    .keys(storage, None, None, Order::Ascending)
-    .collect()
+// (collection, listing id) of active listings
+pub const LISTINGS_BY_COLLECTION: Map<(&str, &str), Empty> = Map::new("listingsbycollection");
+// (seller, listing id), the live listings of a seller
+pub const LISTINGS_BY_SELLER: Map<(&Addr, &str), Empty> = Map::new("listingsbyseller");
+// listing id -> last height the listing is featured at
+pub const FEATURED_LISTINGS: Map<&str, u64> = Map::new("featuredlistings");
+// (tag, listing id), the live listings of a tag
+pub const LISTINGS_BY_TAG: Map<(&str, &str), Empty> = Map::new("listingsbytag");
+// (minter, nft contract), the collections a minter minted into
+pub const CREATOR_COLLECTIONS: Map<(&Addr, &Addr), Empty> = Map::new("creatorcollections");
+// (nft contract, token id) -> id of the live listing of the token
+pub const TOKEN_LISTINGS: Map<(&str, &str), String> = Map::new("tokenlistings");
+
+// fields of a listing rewritten by every bid, kept apart from the listing so a bid writes these
+// few bytes instead of the whole listing, the stored listing is authoritative while unset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BidState {
+    pub max_bid: Asset,
+
+    pub max_bidder: Addr,
+
+    pub block_limit: u64,
+
+    pub max_bid_height: u64,
+
+    #[serde(default)]
+    pub extensions_used: u32,
+
+    #[serde(default)]
+    pub extended_blocks: u64,
+}
+
+pub const LISTING_BIDS: Map<&str, BidState> = Map::new("listingbids");
+
+// the listing with its current bid state
+pub fn load_listing(storage: &dyn Storage, listing_id: &str) -> StdResult<Listing> {
+    let listing = list_resolver_read(storage).load(listing_id.as_bytes())?;
+    with_bid_state(storage, listing_id, listing)
+}
+
+pub fn may_load_listing(storage: &dyn Storage, listing_id: &str) -> StdResult<Option<Listing>> {
+    list_resolver_read(storage)
+        .may_load(listing_id.as_bytes())?
+        .map(|listing| with_bid_state(storage, listing_id, listing))
+        .transpose()
+}
+
+pub fn with_bid_state(storage: &dyn Storage, listing_id: &str, mut listing: Listing) -> StdResult<Listing> {
+    if let Some(bid_state) = LISTING_BIDS.may_load(storage, listing_id)? {
+        apply_bid_state(&mut listing, bid_state);
+    }
+    Ok(listing)
+}
+
+pub fn apply_bid_state(listing: &mut Listing, bid_state: BidState) {
+    listing.max_bid = bid_state.max_bid;
+    listing.max_bidder = bid_state.max_bidder;
+    listing.block_limit = bid_state.block_limit;
+    listing.max_bid_height = bid_state.max_bid_height;
+    listing.extensions_used = bid_state.extensions_used;
+    listing.extended_blocks = bid_state.extended_blocks;
+}
+
+pub fn save_bid_state(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    LISTING_BIDS.save(storage, listing_id, &BidState {
+        max_bid: listing.max_bid.clone(),
+        max_bidder: listing.max_bidder.clone(),
+        block_limit: listing.block_limit,
+        max_bid_height: listing.max_bid_height,
+        extensions_used: listing.extensions_used,
+        extended_blocks: listing.extended_blocks,
+    })
+}
+
+// live listings split by whether the tick saw them end, kept for the status heartbeat
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ListingCounts {
+    pub active: u64,
+
+    pub expired: u64,
+
+    // listings ending before this height are counted as expired
+    pub swept_height: u64,
+}
+
+pub const LISTING_COUNTS: Item<ListingCounts> = Item::new("listingcounts");
+// (block limit, listing id) of live listings, the tick moves those it passed to the expired count
+pub const LISTING_ENDS: Map<(u64, &str), Empty> = Map::new("listingends");
+
+fn count_listing(storage: &mut dyn Storage, listing_id: &str, block_limit: u64, live: bool) -> StdResult<()> {
+    let mut counts = LISTING_COUNTS.may_load(storage)?.unwrap_or_default();
+    let count = if block_limit < counts.swept_height { &mut counts.expired } else { &mut counts.active };
+    if live {
+        *count += 1;
+        LISTING_ENDS.save(storage, (block_limit, listing_id), &Empty {})?;
+    } else {
+        *count = count.saturating_sub(1);
+        LISTING_ENDS.remove(storage, (block_limit, listing_id));
+    }
+    LISTING_COUNTS.save(storage, &counts)
+}
+
+// re-indexes a live listing whose end moved
+pub fn move_listing_end(storage: &mut dyn Storage, listing_id: &str, from: u64, to: u64) -> StdResult<()> {
+    count_listing(storage, listing_id, from, false)?;
+    count_listing(storage, listing_id, to, true)
+}
+
+// moves the listings that ended since the last sweep to the expired count, reading only those
+pub fn sweep_listing_counts(storage: &mut dyn Storage, height: u64) -> StdResult<ListingCounts> {
+    let mut counts = LISTING_COUNTS.may_load(storage)?.unwrap_or_default();
+    if height <= counts.swept_height {
+        return Ok(counts);
+    }
+
+    let ended = LISTING_ENDS
+        .keys(storage, Some(Bound::InclusiveRaw(cursor((counts.swept_height, "")).0)), None, Order::Ascending)
+        .take_while(|key| key.as_ref().map_or(true, |(block_limit, _)| *block_limit < height))
+        .count() as u64;
+    counts.active = counts.active.saturating_sub(ended);
+    counts.expired += ended;
+    counts.swept_height = height;
+    LISTING_COUNTS.save(storage, &counts)?;
+    Ok(counts)
+}
+
+// indexes the listings placed before the listing counts, classified at the migration height
+pub fn seed_listing_counts(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    if LISTING_COUNTS.may_load(storage)?.is_some() {
+        return Ok(());
+    }
+
+    let listings: Vec<(String, u64)> = list_resolver_read(storage)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.and_then(|(key, listing)| {
+            let listing_id = String::from_utf8_lossy(&key).to_string();
+            let block_limit = with_bid_state(storage, &listing_id, listing)?.block_limit;
+            Ok((listing_id, block_limit))
+        }))
+        .collect::<StdResult<_>>()?;
+    LISTING_COUNTS.save(storage, &ListingCounts { active: 0, expired: 0, swept_height: height })?;
+    for (listing_id, block_limit) in listings {
+        count_listing(storage, &listing_id, block_limit, true)?;
+    }
+    Ok(())
+}
+
+// stores a new listing together with its index entries, new listings hold no bid yet
+pub fn store_listing(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    list_resolver(storage).save(listing_id.as_bytes(), listing)?;
+    if let Some(penalty) = &listing.cancel_penalty {
+        credit_ledger(storage, LedgerBucket::Deposits, penalty)?;
+    }
+    ACTIVE_LISTINGS.update(storage, &listing.seller, |active| -> StdResult<_> { Ok(active.unwrap_or_default() + 1) })?;
+    count_listing(storage, listing_id, listing.block_limit, true)?;
+    store_floor_price(storage, listing_id, listing)?;
+    TOKEN_LISTINGS.save(storage, (listing.contract_addr.as_str(), &listing.token_id), &listing_id.to_string())?;
+    LISTINGS_BY_SELLER.save(storage, (&listing.seller, listing_id), &Empty {})?;
+    if !listing.collection.is_empty() {
+        LISTINGS_BY_COLLECTION.save(storage, (&listing.collection, listing_id), &Empty {})?;
+    }
+    Ok(())
+}
+
+// removes a listing together with its index entries, the caller pays out its bid and cancel penalty
+pub fn remove_listing(storage: &mut dyn Storage, contract: &Addr, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    if listing.max_bidder != *contract {
+        debit_ledger(storage, LedgerBucket::Bids, &listing.with_premium(&listing.max_bid))?;
+    }
+    if let Some(penalty) = &listing.cancel_penalty {
+        debit_ledger(storage, LedgerBucket::Deposits, penalty)?;
+    }
+    list_resolver(storage).remove(listing_id.as_bytes());
+    LISTING_BIDS.remove(storage, listing_id);
+    match ACTIVE_LISTINGS.may_load(storage, &listing.seller)?.unwrap_or_default() {
+        0 | 1 => ACTIVE_LISTINGS.remove(storage, &listing.seller),
+        active => ACTIVE_LISTINGS.save(storage, &listing.seller, &(active - 1))?,
+    }
+    count_listing(storage, listing_id, listing.block_limit, false)?;
+    LISTINGS_BY_COLLECTION.remove(storage, (&listing.collection, listing_id));
+    LISTINGS_BY_SELLER.remove(storage, (&listing.seller, listing_id));
+    FEATURED_LISTINGS.remove(storage, listing_id);
+    for tag in listing.tags.iter() {
+        LISTINGS_BY_TAG.remove(storage, (tag, listing_id));
+    }
+    TOKEN_LISTINGS.remove(storage, (listing.contract_addr.as_str(), &listing.token_id));
+    LISTING_TRANSFERS.remove(storage, listing_id);
+    AUTO_RELISTS.remove(storage, listing_id);
+    LISTING_OPERATORS.remove(storage, listing_id);
+    remove_floor_price(storage, listing_id, listing)?;
+    remove_top_bid(storage, listing_id, listing);
+    Ok(())
+}
+
+// listing id -> address offered the listing by its seller, until it accepts
+pub const LISTING_TRANSFERS: Map<&str, Addr> = Map::new("listingtransfers");
+
+// hands a listing over to a new seller, moving its seller index entries along
+pub fn change_listing_seller(storage: &mut dyn Storage, listing_id: &str, mut listing: Listing, new_seller: Addr) -> StdResult<Listing> {
+    match ACTIVE_LISTINGS.may_load(storage, &listing.seller)?.unwrap_or_default() {
+        0 | 1 => ACTIVE_LISTINGS.remove(storage, &listing.seller),
+        active => ACTIVE_LISTINGS.save(storage, &listing.seller, &(active - 1))?,
+    }
+    ACTIVE_LISTINGS.update(storage, &new_seller, |active| -> StdResult<_> { Ok(active.unwrap_or_default() + 1) })?;
+    LISTINGS_BY_SELLER.remove(storage, (&listing.seller, listing_id));
+    LISTINGS_BY_SELLER.save(storage, (&new_seller, listing_id), &Empty {})?;
+    LISTING_TRANSFERS.remove(storage, listing_id);
+
+    listing.seller = new_seller;
+    list_resolver(storage).save(listing_id.as_bytes(), &listing)?;
+    Ok(listing)
+}
+
+// numeric `AUCTION.n` id -> hashed id the listing is stored under
+pub const LISTING_ALIASES: Map<&str, String> = Map::new("listingaliases");
+// (nft contract, token id) -> nonce of the next placement of the token
+pub const LISTING_NONCES: Map<(&Addr, &str), u64> = Map::new("listingnonces");
+
+// resolves a numeric alias to the stored listing id, other ids are returned as is
+pub fn canonical_listing_id(storage: &dyn Storage, listing_id: String) -> StdResult<String> {
+    Ok(LISTING_ALIASES.may_load(storage, &listing_id)?.unwrap_or(listing_id))
+}
+
+// hex encoded sha256 of `<nft contract>/<token id>/<nonce>`
+pub fn hashed_listing_id(nft_addr: &Addr, token_id: &str, nonce: u64) -> String {
+    Sha256::digest(format!("{}/{}/{}", nft_addr, token_id, nonce).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn read_auction_ids(storage: &dyn Storage) -> StdResult<Vec<String>> {
+    AUCTIONS
+    .keys(storage, None, None, Order::Ascending)
+    .collect()
+}
+
+// (collection, price asset, (big-endian amount, listing id))
+pub type FloorPriceKey<'a> = (&'a str, &'a str, (&'a [u8], &'a str));
+
+// current price of an active listing, an ascending range over a (collection, asset) prefix
+// yields the cheapest listing of the asset first
+pub const FLOOR_PRICES: Map<FloorPriceKey, Asset> = Map::new("floorprices");
+// (collection, price asset) -> number of active listings of the collection in the asset
+pub const FLOOR_ASSETS: Map<(&str, &str), u32> = Map::new("floorassets");
+
+pub fn store_floor_price(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    let amount = listing.max_bid.amount.u128().to_be_bytes();
+    let asset = listing.max_bid.info.to_string();
+    FLOOR_ASSETS.update(storage, (listing.contract_addr.as_str(), &asset), |listed| -> StdResult<_> {
+        Ok(listed.unwrap_or_default() + 1)
+    })?;
+    FLOOR_PRICES.save(
+        storage,
+        (listing.contract_addr.as_str(), asset.as_str(), (&amount, listing_id)),
+        &listing.max_bid
+    )
+}
+
+pub fn remove_floor_price(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    let amount = listing.max_bid.amount.u128().to_be_bytes();
+    let asset = listing.max_bid.info.to_string();
+    let key = (listing.contract_addr.as_str(), asset.as_str());
+    match FLOOR_ASSETS.may_load(storage, key)?.unwrap_or_default() {
+        0 | 1 => FLOOR_ASSETS.remove(storage, key),
+        listed => FLOOR_ASSETS.save(storage, key, &(listed - 1))?,
+    }
+    FLOOR_PRICES.remove(
+        storage,
+        (listing.contract_addr.as_str(), asset.as_str(), (&amount, listing_id))
+    );
+    Ok(())
+}
+
+// recounts the listed assets from the floor price index, for listings placed before the count
+pub fn seed_floor_assets(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut listed: BTreeMap<(String, String), u32> = BTreeMap::new();
+    for key in FLOOR_PRICES.keys(storage, None, None, Order::Ascending) {
+        let (collection, asset, _) = key?;
+        *listed.entry((collection, asset)).or_default() += 1;
+    }
+    for ((collection, asset), count) in listed {
+        FLOOR_ASSETS.save(storage, (&collection, &asset), &count)?;
+    }
+    Ok(())
+}
+
+// returns the cheapest active listing of the collection for every asset it is listed in
+pub fn read_floor_prices(storage: &dyn Storage, collection: &str) -> StdResult<Vec<(String, Asset)>> {
+    let assets: Vec<String> = FLOOR_ASSETS
+        .prefix(collection)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    let mut floors: Vec<(String, Asset)> = vec![];
+    for asset in assets {
+        let cheapest = FLOOR_PRICES
+            .prefix((collection, &asset))
+            .range(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?;
+        if let Some(((_, listing_id), price)) = cheapest {
+            floors.push((listing_id, price));
+        }
+    }
+
+    Ok(floors)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TopBid {
+    pub listing_id: String,
+
+    pub bidder: Addr,
+
+    pub bid: Asset,
+}
+
+// (big-endian bid amount, listing id) -> highest bid of a listing that received at least one bid
+pub const TOP_BIDS: Map<(&[u8], &str), TopBid> = Map::new("topbids");
+
+pub fn store_top_bid(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) -> StdResult<()> {
+    let amount = listing.max_bid.amount.u128().to_be_bytes();
+    TOP_BIDS.save(storage, (&amount, listing_id), &TopBid {
+        listing_id: listing_id.to_string(),
+        bidder: listing.max_bidder.clone(),
+        bid: listing.max_bid.clone(),
+    })
+}
+
+pub fn remove_top_bid(storage: &mut dyn Storage, listing_id: &str, listing: &Listing) {
+    let amount = listing.max_bid.amount.u128().to_be_bytes();
+    TOP_BIDS.remove(storage, (&amount, listing_id))
+}
+
+pub fn read_top_bids(storage: &dyn Storage, limit: usize) -> StdResult<Vec<TopBid>> {
+    TOP_BIDS
+        .range(storage, None, None, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchTarget {
+    Listing { listing_id: String },
+    Collection { nft_addr: String },
+}
+
+impl WatchTarget {
+    pub fn key(&self) -> String {
+        match self {
+            WatchTarget::Listing { listing_id } => ["listing".to_string(), listing_id.clone()].join(":"),
+            WatchTarget::Collection { nft_addr } => ["collection".to_string(), nft_addr.clone()].join(":"),
+        }
+    }
+}
+
+// (watcher, target key) -> target
+pub const WATCHLISTS: Map<(&Addr, &str), WatchTarget> = Map::new("watchlists");
+// (target key, watcher), reverse index used to name the watchers of a target in events
+pub const WATCHERS: Map<(&str, &Addr), Empty> = Map::new("watchers");
+
+// target key -> number of watchers, bounding the watchers named in the events of the target
+pub const WATCHER_COUNTS: Map<&str, u32> = Map::new("watchercounts");
+
+pub fn read_watcher_count(storage: &dyn Storage, target: &WatchTarget) -> StdResult<u32> {
+    Ok(WATCHER_COUNTS.may_load(storage, &target.key())?.unwrap_or_default())
+}
+
+pub fn store_watch(storage: &mut dyn Storage, watcher: &Addr, target: &WatchTarget) -> StdResult<()> {
+    let key = target.key();
+    if !WATCHERS.has(storage, (&key, watcher)) {
+        let count = read_watcher_count(storage, target)?;
+        WATCHER_COUNTS.save(storage, &key, &(count + 1))?;
+    }
+    WATCHLISTS.save(storage, (watcher, &key), target)?;
+    WATCHERS.save(storage, (&key, watcher), &Empty {})
+}
+
+pub fn remove_watch(storage: &mut dyn Storage, watcher: &Addr, target: &WatchTarget) -> StdResult<()> {
+    let key = target.key();
+    if WATCHERS.has(storage, (&key, watcher)) {
+        match read_watcher_count(storage, target)? {
+            0 | 1 => WATCHER_COUNTS.remove(storage, &key),
+            count => WATCHER_COUNTS.save(storage, &key, &(count - 1))?,
+        }
+    }
+    WATCHLISTS.remove(storage, (watcher, &key));
+    WATCHERS.remove(storage, (&key, watcher));
+    Ok(())
+}
+
+pub fn read_watchlist(
+    storage: &dyn Storage,
+    watcher: &Addr,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<WatchTarget>> {
+    let targets = WATCHLISTS
+        .prefix(watcher)
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(key, target)| (cursor(key.as_str()), target)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(targets, limit))
+}
+
+pub fn read_seller_listings(storage: &dyn Storage, seller: &Addr, limit: usize) -> StdResult<Vec<(String, Listing)>> {
+    LISTINGS_BY_SELLER
+        .prefix(seller)
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit)
+        .map(|listing_id| {
+            let listing_id = listing_id?;
+            let listing = load_listing(storage, &listing_id)?;
+            Ok((listing_id, listing))
+        })
+        .collect()
+}
+
+// featured listings whose feature has not ended at `height`
+pub fn read_featured_listings(
+    storage: &dyn Storage,
+    height: u64,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<(String, u64, Listing)>> {
+    let listings = FEATURED_LISTINGS
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .filter(|item| item.as_ref().map_or(true, |(_, until)| *until >= height))
+        .take(limit)
+        .map(|item| {
+            let (listing_id, until) = item?;
+            let listing = load_listing(storage, &listing_id)?;
+            Ok((cursor(listing_id.as_str()), (listing_id, until, listing)))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(listings, limit))
+}
+
+pub fn read_tag_listings(
+    storage: &dyn Storage,
+    tag: &str,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<(String, Listing)>> {
+    let listings = LISTINGS_BY_TAG
+        .prefix(tag)
+        .keys(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|listing_id| {
+            let listing_id = listing_id?;
+            let listing = load_listing(storage, &listing_id)?;
+            Ok((cursor(listing_id.as_str()), (listing_id, listing)))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(listings, limit))
+}
+
+pub fn read_creator_collections(storage: &dyn Storage, creator: &Addr, limit: usize) -> StdResult<Vec<Addr>> {
+    CREATOR_COLLECTIONS
+        .prefix(creator)
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn read_collection_listings(
+    storage: &dyn Storage,
+    collection: &str,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<(String, Listing)>> {
+    let listings = LISTINGS_BY_COLLECTION
+        .prefix(collection)
+        .keys(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|listing_id| {
+            let listing_id = listing_id?;
+            let listing = load_listing(storage, &listing_id)?;
+            Ok((cursor(listing_id.as_str()), (listing_id, listing)))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(listings, limit))
+}
+
+// watchers stored before the count was kept are cut off at the cap as well
+pub fn read_watchers(storage: &dyn Storage, target: &WatchTarget) -> StdResult<Vec<Addr>> {
+    WATCHERS
+        .prefix(&target.key())
+        .keys(storage, None, None, Order::Ascending)
+        .take(MAX_WATCHERS as usize)
+        .collect()
+}
+
+// (bidder, operator, listing id) -> highest bid the operator may place on the bidder's behalf
+pub const BID_APPROVALS: Map<(&Addr, &Addr, &str), Uint128> = Map::new("bidapprovals");
+
+// listing id -> operator the seller lets bid on behalf of off-chain clients, like phone bids at an
+// auction house. The client is the bidder of record and receives the refund or the nft
+pub const LISTING_OPERATORS: Map<&str, Addr> = Map::new("listingoperators");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    // delivered by the settlement transaction
+    Sent,
+    // the transfer failed and the recipient has to claim it from escrow
+    Escrowed,
+    // credited to the royalty ledger, claimed with `ClaimRoyalties`
+    Accrued,
+    // platform fee kept in the fees bucket of the ledger, withdrawn with `WithdrawFees`
+    Retained,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payout {
+    pub recipient: Addr,
+
+    pub asset: Asset,
+
+    pub status: PayoutStatus,
+
+    pub escrow_id: Option<u64>,
+}
+
+// part of the contract's balance of an asset, every handler moving funds in or out of the
+// contract keeps the buckets in sync
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerBucket {
+    // highest and held bids of listings, open offers and raffle tickets
+    Bids,
+    // refunds and payouts kept in escrow after a failed transfer
+    Refunds,
+    // platform fees retained for withdrawal
+    Fees,
+    // royalties accrued for their beneficiaries
+    Royalties,
+    // security deposits and cancel penalties
+    Deposits,
+}
+
+impl LedgerBucket {
+    pub const ALL: [LedgerBucket; 5] = [
+        LedgerBucket::Bids,
+        LedgerBucket::Refunds,
+        LedgerBucket::Fees,
+        LedgerBucket::Royalties,
+        LedgerBucket::Deposits,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerBucket::Bids => "bids",
+            LedgerBucket::Refunds => "refunds",
+            LedgerBucket::Fees => "fees",
+            LedgerBucket::Royalties => "royalties",
+            LedgerBucket::Deposits => "deposits",
+        }
+    }
+}
+
+// (asset, bucket) -> amount of the contract's balance the bucket accounts for
+pub const LEDGER: Map<(&str, &str), Uint128> = Map::new("ledger");
+
+pub fn read_ledger(storage: &dyn Storage, asset_info: &AssetInfo, bucket: LedgerBucket) -> StdResult<Uint128> {
+    Ok(LEDGER.may_load(storage, (&asset_info.to_string(), bucket.as_str()))?.unwrap_or_default())
+}
+
+pub fn credit_ledger(storage: &mut dyn Storage, bucket: LedgerBucket, asset: &Asset) -> StdResult<()> {
+    let balance = read_ledger(storage, &asset.info, bucket)?.checked_add(asset.amount)?;
+    LEDGER.save(storage, (&asset.info.to_string(), bucket.as_str()), &balance)
+}
+
+// fails instead of spending funds the bucket does not hold
+pub fn debit_ledger(storage: &mut dyn Storage, bucket: LedgerBucket, asset: &Asset) -> StdResult<()> {
+    let balance = read_ledger(storage, &asset.info, bucket)?.checked_sub(asset.amount)?;
+    if balance.is_zero() {
+        LEDGER.remove(storage, (&asset.info.to_string(), bucket.as_str()));
+        return Ok(());
+    }
+    LEDGER.save(storage, (&asset.info.to_string(), bucket.as_str()), &balance)
+}
+
+// denom -> principal held in the yield vault, counted in the balance the ledger is backed by.
+// only changed in the replies of the deposits and withdrawals, once the funds moved
+pub const YIELD_DEPOSITS: Map<&str, Uint128> = Map::new("yielddeposits");
+
+// (beneficiary, asset) -> royalties accrued while royalty payouts are deferred, keyed by the
+// address as set on the token so that an invalid one does not block the settlement
+pub const ROYALTY_LEDGER: Map<(&str, &str), Asset> = Map::new("royaltyledger");
+
+pub fn accrue_royalty(storage: &mut dyn Storage, beneficiary: &str, asset: &Asset) -> StdResult<()> {
+    ROYALTY_LEDGER.update(storage, (beneficiary, &asset.info.to_string()), |accrued| -> StdResult<_> {
+        accrued
+            .unwrap_or(Asset { info: asset.info.clone(), amount: Uint128::zero() })
+            .checked_add(asset)
+    })?;
+    credit_ledger(storage, LedgerBucket::Royalties, asset)
+}
+
+pub fn read_accrued_royalties(storage: &dyn Storage, beneficiary: &str) -> StdResult<Vec<Asset>> {
+    ROYALTY_LEDGER
+        .prefix(beneficiary)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, asset)| asset))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettlementReceipt {
+    pub listing_id: String,
+
+    pub token_id: String,
+
+    pub contract_addr: Addr,
+
+    pub seller: Addr,
+
+    pub winner: Addr,
+
+    pub price: Asset,
+
+    pub block_height: u64,
+
+    pub payouts: Vec<Payout>,
+}
+
+pub const RECEIPTS: Map<&str, SettlementReceipt> = Map::new("receipts");
+// (settlement height, listing id) of the receipts, lets past sales be listed by height range
+pub const SALES_BY_HEIGHT: Map<(u64, &str), Empty> = Map::new("salesbyheight");
+
+pub fn store_receipt(storage: &mut dyn Storage, receipt: &SettlementReceipt) -> StdResult<()> {
+    SALES_BY_HEIGHT.save(storage, (receipt.block_height, &receipt.listing_id), &Empty {})?;
+    RECEIPTS.save(storage, &receipt.listing_id, receipt)
+}
+
+// receipts of the sales settled between two heights, both included, ordered by height
+pub fn read_sales_between(
+    storage: &dyn Storage,
+    start_height: u64,
+    end_height: u64,
+    start_after: Option<Binary>,
+    limit: usize,
+) -> StdResult<Page<SettlementReceipt>> {
+    // a cursor before the start height doesn't widen the range
+    let first = cursor((start_height, ""));
+    let start = match start_after {
+        Some(start_after) if start_after.as_slice() >= first.as_slice() => start_bound(Some(start_after)),
+        _ => Some(Bound::InclusiveRaw(first.0)),
+    };
+
+    let sales = SALES_BY_HEIGHT
+        .keys(storage, start, None, Order::Ascending)
+        .take_while(|key| key.as_ref().map_or(true, |(height, _)| *height <= end_height))
+        .take(limit)
+        .map(|key| {
+            let (height, listing_id) = key?;
+            Ok((cursor((height, listing_id.as_str())), RECEIPTS.load(storage, &listing_id)?))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(sales, limit))
+}
+
+// nfts of listings settled by their seller pulling the proceeds, kept until the winner claims them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnclaimedItem {
+    pub contract_addr: Addr,
+
+    pub token_id: String,
+
+    pub winner: Addr,
+}
+
+pub const UNCLAIMED_ITEMS: Map<&str, UnclaimedItem> = Map::new("unclaimeditems");
+
+// raw (key, value) pairs stored under the namespace, keys relative to it, in storage order
+pub fn read_raw_entries(
+    storage: &dyn Storage,
+    namespace: &[u8],
+    start_after: Option<Vec<u8>>,
+    limit: usize
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let prefix = to_length_prefixed(namespace);
+    let start = match start_after {
+        // the smallest key after start_after
+        Some(start_after) => [prefix.as_slice(), start_after.as_slice(), &[0]].concat(),
+        None => prefix.clone(),
+    };
+    // namespaces are ascii, so bumping the last byte bounds all keys under the prefix
+    let mut end = prefix.clone();
+    if let Some(last) = end.last_mut() {
+        *last += 1;
+    }
+
+    storage
+        .range(Some(&start), Some(&end), Order::Ascending)
+        .take(limit)
+        .map(|(key, value)| (key[prefix.len()..].to_vec(), value))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyContext {
+    // settlement payout, by index in the receipt of the listing
+    Payout { listing_id: String, index: u32 },
+    // refund of an outbid bid
+    Refund { listing_id: String, recipient: Addr, asset: Asset },
+    // new attempt at delivering an escrowed transfer
+    Retry { escrow_id: u64 },
+    // first bid pulling the nft of an escrowless listing into escrow
+    EscrowPull { listing_id: String },
+    // nft leaving the contract on settlement
+    Delivery { listing_id: String, msg: CosmosMsg },
+    // new attempt at a dead letter
+    RetryDeadLetter { dead_letter_id: u64 },
+    // native funds deposited into the yield vault
+    YieldDeposit { denom: String, amount: Uint128 },
+    // funds withdrawn from the yield vault, releasing `principal` of the deposits and crediting
+    // what exceeds it to the fees
+    YieldWithdrawal { denom: String, amount: Uint128, principal: Uint128 },
+    // deposits withdrawn from a yield vault being left, written off when it fails to return them
+    YieldExit { denom: String, principal: Uint128 },
+}
+
+// submessage reply id -> what the submessage was transferring
+pub const REPLIES: Map<u64, ReplyContext> = Map::new("replies");
+pub const REPLY_COUNT: Item<u64> = Item::new("replycount");
+
+pub fn next_reply_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    next_id(storage, &REPLY_COUNT)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EscrowStatus {
+    // anyone may retry pushing the transfer to the recipient
+    Retryable,
+    // too many failed attempts, only the recipient can claim it
+    ClaimOnly,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowEntry {
+    pub id: u64,
+
+    pub recipient: Addr,
+
+    pub asset: Asset,
+
+    pub listing_id: String,
+
+    // failed transfer attempts, including the original one
+    pub attempts: u32,
+
+    pub status: EscrowStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DeadLetter {
+    pub id: u64,
+
+    pub listing_id: String,
+
+    // the failed message, sent again as is on retry
+    pub msg: CosmosMsg,
+
+    // error of the last attempt
+    pub error: String,
+
+    // failed attempts, including the original one
+    pub attempts: u32,
+
+    pub block_height: u64,
+}
+
+// nft deliveries that failed on settlement, failed refunds and payouts are kept in `ESCROW` instead
+pub const DEAD_LETTERS: Map<u64, DeadLetter> = Map::new("deadletters");
+pub const DEAD_LETTER_COUNT: Item<u64> = Item::new("deadlettercount");
+
+pub fn store_dead_letter(storage: &mut dyn Storage, listing_id: String, msg: CosmosMsg, error: String, block_height: u64) -> StdResult<u64> {
+    let id = next_id(storage, &DEAD_LETTER_COUNT)?;
+
+    DEAD_LETTERS.save(storage, id, &DeadLetter { id, listing_id, msg, error, attempts: 1, block_height })?;
+    Ok(id)
+}
+
+pub fn read_dead_letters(storage: &dyn Storage, start_after: Option<Binary>, limit: usize) -> StdResult<Page<DeadLetter>> {
+    let dead_letters = DEAD_LETTERS
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, dead_letter)| (cursor(id), dead_letter)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(dead_letters, limit))
+}
+
+// pull-based escrow of transfers that could not be pushed to their recipient
+pub const ESCROW: Map<u64, EscrowEntry> = Map::new("escrow");
+// (recipient, escrow id), lets recipients list what they can claim
+pub const ESCROW_BY_RECIPIENT: Map<(&Addr, u64), Empty> = Map::new("escrowbyrecipient");
+pub const ESCROW_COUNT: Item<u64> = Item::new("escrowcount");
+// number of entries in the escrow, counted once on migration
+pub const PENDING_ESCROW: Item<u64> = Item::new("pendingescrow");
+// (recipient, asset) -> height at which a refund to the recipient first failed
+pub const UNDELIVERABLE: Map<(&Addr, &str), u64> = Map::new("undeliverable");
+
+pub fn store_escrow(storage: &mut dyn Storage, recipient: Addr, asset: Asset, listing_id: String) -> StdResult<u64> {
+    let id = next_id(storage, &ESCROW_COUNT)?;
+
+    ESCROW_BY_RECIPIENT.save(storage, (&recipient, id), &Empty {})?;
+    let pending = PENDING_ESCROW.may_load(storage)?.unwrap_or_default();
+    PENDING_ESCROW.save(storage, &(pending + 1))?;
+    credit_ledger(storage, LedgerBucket::Refunds, &asset)?;
+    ESCROW.save(storage, id, &EscrowEntry {
+        id,
+        recipient,
+        asset,
+        listing_id,
+        attempts: 1,
+        status: EscrowStatus::Retryable,
+    })?;
+    Ok(id)
+}
+
+// counts the entries escrowed before the pending escrow count
+pub fn seed_pending_escrow(storage: &mut dyn Storage) -> StdResult<()> {
+    if PENDING_ESCROW.may_load(storage)?.is_none() {
+        let pending = ESCROW.keys_raw(storage, None, None, Order::Ascending).count() as u64;
+        PENDING_ESCROW.save(storage, &pending)?;
+    }
+    Ok(())
+}
+
+pub fn remove_escrow(storage: &mut dyn Storage, entry: &EscrowEntry) -> StdResult<()> {
+    ESCROW.remove(storage, entry.id);
+    ESCROW_BY_RECIPIENT.remove(storage, (&entry.recipient, entry.id));
+    let pending = PENDING_ESCROW.may_load(storage)?.unwrap_or_default();
+    PENDING_ESCROW.save(storage, &pending.saturating_sub(1))?;
+    debit_ledger(storage, LedgerBucket::Refunds, &entry.asset)
+}
+
+pub fn read_escrow_by_recipient(
+    storage: &dyn Storage,
+    recipient: &Addr,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<EscrowEntry>> {
+    let entries = ESCROW_BY_RECIPIENT
+        .prefix(recipient)
+        .keys(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|id| {
+            let id = id?;
+            Ok((cursor(id), ESCROW.load(storage, id)?))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(entries, limit))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomInfo {
+    // native (IBC) denom as seen on chain
+    pub denom: String,
+
+    // display name, e.g. ATOM
+    pub display: String,
+
+    // decimals between the display unit and the on chain unit
+    pub decimals: u8,
+
+    // smallest amount accepted as minimum bid or bid
+    pub min_bid_unit: Uint128,
+
+    // minimum bids and bids have to be whole multiples of it when set
+    #[serde(default)]
+    pub tick_size: Option<Uint128>,
+}
+
+// owner vetted native denoms usable for listings and bids
+pub const ACCEPTED_DENOMS: Map<&str, DenomInfo> = Map::new("accepteddenoms");
+
+pub fn read_accepted_denoms(storage: &dyn Storage, start_after: Option<Binary>, limit: usize) -> StdResult<Page<DenomInfo>> {
+    let denoms = ACCEPTED_DENOMS
+        .range(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(denom, denom_info)| (cursor(denom.as_str()), denom_info)))
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(denoms, limit))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeOverride {
+    pub fee_rate: Decimal,
+
+    pub start_time: Timestamp,
+
+    pub end_time: Timestamp,
+}
+
+impl FeeOverride {
+    pub fn is_active(&self, time: Timestamp) -> bool {
+        self.start_time <= time && time < self.end_time
+    }
+}
+
+// collection -> promotional platform fee, snapshotted on listings placed during the promotion
+pub const COLLECTION_FEE_OVERRIDES: Map<&str, FeeOverride> = Map::new("collectionfeeoverrides");
+
+// sales tax or export duty of physically backed art, paid out of the price alongside royalties
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionLevy {
+    pub rate: Decimal,
+
+    pub recipient: Addr,
+}
+
+// collection -> levy set by the collection owner, snapshotted on listings placed afterwards
+pub const COLLECTION_LEVIES: Map<&str, CollectionLevy> = Map::new("collectionlevies");
+
+// cw721 code backing a collection, decides the shape of the mint message
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721Variant {
+    // cw721-base with the glassflow metadata extension
+    #[default]
+    OnChainMetadata,
+    // plain cw721-base, metadata lives behind the token uri
+    Standard,
+    // on-chain metadata, tokens are minted non transferable
+    Soulbound,
+}
+
+impl Cw721Variant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Cw721Variant::OnChainMetadata => "on_chain_metadata",
+            Cw721Variant::Standard => "standard",
+            Cw721Variant::Soulbound => "soulbound",
+        }
+    }
+}
+
+// collection -> variant set by the collection owner, unset collections use on-chain metadata
+pub const COLLECTION_VARIANTS: Map<&str, Cw721Variant> = Map::new("collectionvariants");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionPause {
+    pub paused_by: Addr,
+
+    pub paused_height: u64,
+}
+
+// collection -> pause set by the collection owner, no new listings or mints while present
+pub const COLLECTION_PAUSES: Map<&str, CollectionPause> = Map::new("collectionpauses");
+
+// resales of a fresh drop can't offer buy-now below `floor_rate` of the initial price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceFloor {
+    pub floor_rate: Decimal,
+
+    // blocks after the mint the floor applies for
+    pub window_blocks: u64,
+}
+
+// collection -> price floor set by the collection owner, checked when listings are placed
+pub const COLLECTION_PRICE_FLOORS: Map<&str, PriceFloor> = Map::new("collectionpricefloors");
+
+// collection -> token id format set by the collection owner, mints without one get `GF.<count>` ids
+pub const COLLECTION_TOKEN_ID_FORMATS: Map<&str, String> = Map::new("collectiontokenidformats");
+
+// hash over the artwork hashes of a whole collection, committed before its first token exists so
+// metadata revealed later can be checked against the committed set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProvenanceCommitment {
+    // lowercase hex of a sha256 digest
+    pub provenance_hash: String,
+
+    pub committed_by: Addr,
+
+    pub committed_height: u64,
+}
+
+// collection -> provenance commitment of the collection owner, written once
+pub const COLLECTION_PROVENANCE_COMMITMENTS: Map<&str, ProvenanceCommitment> = Map::new("collectionprovenancecommitments");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SecurityDeposit {
+    pub seller: Addr,
+
+    pub asset: Asset,
+
+    pub arbiter: Addr,
+
+    pub dispute_window_blocks: u64,
+
+    // set once the listing is sold
+    pub buyer: Option<Addr>,
+
+    // height from which the deposit can be released to the seller
+    pub release_height: Option<u64>,
+}
+
+// listing id -> deposit posted by the seller of a physically backed nft
+pub const SECURITY_DEPOSITS: Map<&str, SecurityDeposit> = Map::new("securitydeposits");
+
+pub fn store_security_deposit(storage: &mut dyn Storage, listing_id: &str, deposit: &SecurityDeposit) -> StdResult<()> {
+    SECURITY_DEPOSITS.save(storage, listing_id, deposit)?;
+    credit_ledger(storage, LedgerBucket::Deposits, &deposit.asset)
+}
+
+// removes the deposit of a listing, the caller pays it out
+pub fn remove_security_deposit(storage: &mut dyn Storage, listing_id: &str, deposit: &SecurityDeposit) -> StdResult<()> {
+    SECURITY_DEPOSITS.remove(storage, listing_id);
+    debit_ledger(storage, LedgerBucket::Deposits, &deposit.asset)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QuarantinedNft {
+    // the address that sent the nft to the contract
+    pub sender: Addr,
+
+    pub block_height: u64,
+}
+
+// (nft contract, token id) -> nft sent to the contract outside of a listing, held for its sender
+pub const QUARANTINE: Map<(&str, &str), QuarantinedNft> = Map::new("quarantine");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CustodiedNft {
+    // the owner the nft was minted for
+    pub owner: Addr,
+
+    pub block_height: u64,
+}
+
+// (nft contract, token id) -> nft minted into the contract, released to its owner or listed by them
+pub const CUSTODY: Map<(&str, &str), CustodiedNft> = Map::new("custody");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvenanceEvent {
+    Minted {
+        minter: Addr,
+        owner: String,
+        block_height: u64,
+    },
+    Sold {
+        listing_id: String,
+        seller: Addr,
+        buyer: Addr,
+        price: Asset,
+        block_height: u64,
+    },
+}
+
+// (nft contract, token id, sequence) -> custody events recorded by this contract
+pub const PROVENANCE: Map<(&str, &str, u32), ProvenanceEvent> = Map::new("provenance");
+
+pub fn append_provenance(storage: &mut dyn Storage, nft_addr: &str, token_id: &str, event: &ProvenanceEvent) -> StdResult<()> {
+    let next = PROVENANCE
+        .prefix((nft_addr, token_id))
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|last| last + 1)
+        .unwrap_or_default();
+
+    PROVENANCE.save(storage, (nft_addr, token_id, next), event)
+}
+
+pub fn read_provenance(storage: &dyn Storage, nft_addr: &str, token_id: &str) -> StdResult<Vec<ProvenanceEvent>> {
+    PROVENANCE
+        .prefix((nft_addr, token_id))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, event)| event))
+        .collect()
+}
+
+// original minter of a token minted by this contract
+pub fn read_minted_by(storage: &dyn Storage, nft_addr: &str, token_id: &str) -> StdResult<Option<Addr>> {
+    Ok(PROVENANCE
+        .may_load(storage, (nft_addr, token_id, 0))?
+        .and_then(|event| match event {
+            ProvenanceEvent::Minted { minter, .. } => Some(minter),
+            _ => None,
+        }))
+}
+
+// height a token minted by this contract was minted at
+pub fn read_mint_height(storage: &dyn Storage, nft_addr: &str, token_id: &str) -> StdResult<Option<u64>> {
+    Ok(PROVENANCE
+        .may_load(storage, (nft_addr, token_id, 0))?
+        .and_then(|event| match event {
+            ProvenanceEvent::Minted { block_height, .. } => Some(block_height),
+            _ => None,
+        }))
+}
+
+// (nft contract, token id) -> number of sales settled through this contract
+pub const TOKEN_SALES: Map<(&str, &str), u64> = Map::new("tokensales");
+
+pub fn has_been_sold(storage: &dyn Storage, nft_addr: &str, token_id: &str) -> StdResult<bool> {
+    Ok(TOKEN_SALES.may_load(storage, (nft_addr, token_id))?.unwrap_or_default() > 0)
+}
+
+pub fn add_token_sale(storage: &mut dyn Storage, nft_addr: &str, token_id: &str) -> StdResult<u64> {
+    TOKEN_SALES.update(storage, (nft_addr, token_id), |sales| -> StdResult<_> {
+        Ok(sales.unwrap_or_default() + 1)
+    })
+}
+
+// (nft contract, token id) -> lowest minimum bid and its asset the minter agreed to for the primary sale,
+// kept apart from the unitless approvals under "creatorapprovals" which are no longer honored
+pub const CREATOR_APPROVALS: Map<(&str, &str), Asset> = Map::new("creatorassetapprovals");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SellerStats {
+    pub asset: AssetInfo,
+
+    // cumulative settled sale price
+    pub volume: Uint128,
+
+    pub sales: u64,
+}
+
+// (seller, price asset) -> settled sales of the seller in that asset
+pub const SELLER_STATS: Map<(&Addr, &str), SellerStats> = Map::new("sellerstats");
+
+pub fn read_seller_volume(storage: &dyn Storage, seller: &Addr, asset: &AssetInfo) -> StdResult<Uint128> {
+    Ok(SELLER_STATS
+        .may_load(storage, (seller, &asset.to_string()))?
+        .map(|stats| stats.volume)
+        .unwrap_or_default())
+}
+
+pub fn add_seller_sale(storage: &mut dyn Storage, seller: &Addr, price: &Asset) -> StdResult<SellerStats> {
+    SELLER_STATS.update(storage, (seller, &price.info.to_string()), |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or(SellerStats {
+            asset: price.info.clone(),
+            volume: Uint128::zero(),
+            sales: 0,
+        });
+        stats.volume = stats.volume.checked_add(price.amount)?;
+        stats.sales += 1;
+        Ok(stats)
+    })
+}
+
+pub fn read_seller_stats(storage: &dyn Storage, seller: &Addr) -> StdResult<Vec<SellerStats>> {
+    SELLER_STATS
+        .prefix(seller)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stats)| stats))
+        .collect()
+}
+
+// price asset -> loyalty points earned per settled unit by the buyer and the seller
+pub const POINTS_RATES: Map<&str, Decimal> = Map::new("pointsrates");
+
+// address -> loyalty points, checkpointed at the heights the owner snapshots
+pub const POINTS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "points",
+    "points__checkpoints",
+    "points__changelog",
+    Strategy::Selected,
+);
+
+pub fn add_points(storage: &mut dyn Storage, address: &Addr, points: Uint128, height: u64) -> StdResult<Uint128> {
+    POINTS.update(storage, address, height, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(points)?)
+    })
+}
+
+// current balance, or the balance at the start of a snapshot height
+pub fn read_points(storage: &dyn Storage, address: &Addr, height: Option<u64>) -> StdResult<Uint128> {
+    let points = match height {
+        Some(height) => POINTS.may_load_at_height(storage, address, height)?,
+        None => POINTS.may_load(storage, address)?,
+    };
+    Ok(points.unwrap_or_default())
+}
+
+pub fn read_all_points(
+    storage: &dyn Storage,
+    height: Option<u64>,
+    start_after: Option<Binary>,
+    limit: usize
+) -> StdResult<Page<(Addr, Uint128)>> {
+    if let Some(height) = height {
+        POINTS.assert_checkpointed(storage, height)?;
+    }
+
+    let balances = POINTS
+        .keys(storage, start_bound(start_after), None, Order::Ascending)
+        .take(limit)
+        .map(|address| {
+            let address = address?;
+            let points = read_points(storage, &address, height)?;
+            Ok((cursor(&address), (address, points)))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(Page::new(balances, limit))
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
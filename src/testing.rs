@@ -0,0 +1,112 @@
+// helpers for suites testing against the contract without replicating its storage layout
+// listings and offers are built straight into the contract storage of unit tests, cw-multi-test
+// suites read the state back through raw queries and get the invariants checked after every
+// execute and reply, the `testing` feature enabling `invariants`
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_slice, Addr, BlockInfo, Deps, Empty, Env, QuerierWrapper, StdResult, Storage, Timestamp};
+use cosmwasm_storage::to_length_prefixed;
+
+use crate::asset::Asset;
+use crate::error::ContractError;
+use crate::ids::{next_id, LISTING_IDS, OFFER_IDS};
+use crate::state::{
+    apply_bid_state, credit_ledger, save_bid_state, store_listing, store_offer, store_top_bid, BidState, LedgerBucket,
+    Listing, Offer, LISTINGS_BY_TAG, LISTING_BIDS, LIST_RESOLVER_KEY,
+};
+
+// seconds a block takes when moving between heights
+pub const BLOCK_TIME_SECONDS: u64 = 5;
+
+// the mock environment at a height, with a time following from the height so that environments at
+// the same height are equal
+pub fn mock_env_at(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env.block.time = Timestamp::from_seconds(height * BLOCK_TIME_SECONDS);
+    env
+}
+
+// moves a block forward, e.g. `app.update_block(|block| advance_blocks(block, 10))`
+pub fn advance_blocks(block: &mut BlockInfo, blocks: u64) {
+    block.height += blocks;
+    block.time = block.time.plus_seconds(blocks * BLOCK_TIME_SECONDS);
+}
+
+// an escrowed listing without bids and with no template options, fields can be changed before storing it
+pub fn new_listing(contract: &Addr, seller: Addr, nft_addr: Addr, token_id: &str, minimum_bid: Asset, block_limit: u64) -> Listing {
+    Listing {
+        token_id: token_id.to_string(),
+        contract_addr: nft_addr.clone(),
+        seller,
+        max_bid: minimum_bid,
+        max_bidder: contract.clone(),
+        block_limit,
+        fee_override: None,
+        pending_escrow: false,
+        min_increment: None,
+        reserve_price: None,
+        payout_splits: vec![],
+        decimals: None,
+        cancel_penalty: None,
+        buy_now_price: None,
+        buy_now_lock: None,
+        candle_window: None,
+        candle_end: None,
+        max_bid_height: 0,
+        extensions_used: 0,
+        extended_blocks: 0,
+        collection: nft_addr.to_string(),
+        listed_by: None,
+        levy: None,
+        tags: vec![],
+        charity: None,
+        buyer_premium: None,
+        increment_steps: vec![],
+        start_height: 0,
+    }
+}
+
+// stores a listing with its indexes and accounting as placing it would, returning its id
+// the nft has to be escrowed with the contract, or with the seller for escrowless listings
+pub fn put_listing(storage: &mut dyn Storage, contract: &Addr, listing: &Listing) -> StdResult<String> {
+    let listing_id = ["AUCTION".to_string(), next_id(storage, &LISTING_IDS)?.to_string()].join(".");
+
+    store_listing(storage, &listing_id, listing)?;
+    save_bid_state(storage, &listing_id, listing)?;
+    for tag in listing.tags.iter() {
+        LISTINGS_BY_TAG.save(storage, (tag, &listing_id), &Empty {})?;
+    }
+    if listing.max_bidder != *contract {
+        store_top_bid(storage, &listing_id, listing)?;
+        credit_ledger(storage, LedgerBucket::Bids, &listing.max_bid)?;
+    }
+    Ok(listing_id)
+}
+
+// stores an offer as making it would, returning its id, the price has to be held by the contract
+pub fn put_offer(storage: &mut dyn Storage, buyer: Addr, nft_addr: Addr, token_id: &str, price: Asset) -> StdResult<u64> {
+    let id = next_id(storage, &OFFER_IDS)?;
+
+    store_offer(storage, &Offer { id, buyer, nft_addr, token_id: token_id.to_string(), price })?;
+    Ok(id)
+}
+
+// reads a listing of a deployed contract with its current bid state
+pub fn query_listing(querier: &QuerierWrapper, contract: &Addr, listing_id: &str) -> StdResult<Option<Listing>> {
+    let mut key = to_length_prefixed(LIST_RESOLVER_KEY);
+    key.extend_from_slice(listing_id.as_bytes());
+    let mut listing: Listing = match querier.query_wasm_raw(contract, key)? {
+        Some(raw) => from_slice(&raw)?,
+        None => return Ok(None),
+    };
+
+    if let Some(raw) = querier.query_wasm_raw(contract, LISTING_BIDS.key(listing_id).to_vec())? {
+        apply_bid_state(&mut listing, from_slice::<BidState>(&raw)?);
+    }
+    Ok(Some(listing))
+}
+
+// checks the consistency of listings, indexes, escrow and the ledger of the contract
+pub fn assert_invariants(deps: Deps, env: &Env) -> Result<(), ContractError> {
+    crate::invariants::assert_invariants(deps, env)
+}
@@ -1,11 +1,11 @@
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{ Decimal, Uint128, CosmosMsg, WasmMsg, SubMsg, to_binary };
+    use cosmwasm_std::{ Addr, Decimal, Uint128, CosmosMsg, WasmMsg, WasmQuery, SubMsg, SystemResult, ContractResult, to_binary };
 
-    use crate::contract::{execute, instantiate, query_minters, query_nft_info};
+    use crate::contract::{execute, instantiate, query_minters};
     use crate::msg::{ ExecuteMsg, InstantiateMsg, GFMintMsg };
-    use crate::state::{ Royalty, Metadata };
+    use crate::state::{ Royalty, Metadata, Collection };
     use crate::error::ContractError;
     use cw721_base::msg::{ ExecuteMsg as Cw721ExecuteMsg, MintMsg };
 
@@ -15,21 +15,29 @@ mod tests {
         let mut deps = mock_dependencies();
 
         // instantiate an empty contract
-        let instantiate_msg = InstantiateMsg { };
+        let instantiate_msg = InstantiateMsg::default();
         let info = mock_info(&String::from("creator"), &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
         assert_eq!(0, res.messages.len());
 
-        let info = mock_info(&String::from("creator"), &[]);
-        let msg = ExecuteMsg::SetNftAddress{ nft_address: String::from("nft_address")};
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // the collection is owned by the minter
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(to_binary(&Collection {
+                name: String::from("collection"),
+                description: None,
+                owner: Addr::unchecked("minter1"),
+                logo_url: None,
+                banner_url: None,
+            }).unwrap())),
+            _ => panic!("unexpected query"),
+        });
 
         // make a whitelist with unauthorized user
         let sender = String::from("sender");
         let minter = String::from("minter1");
 
         let info = mock_info(&sender, &vec![]);
-        let msg = ExecuteMsg::UpdateMinter{minter};
+        let msg = ExecuteMsg::UpdateMinter{minter, expires: None};
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
         assert_eq!(err, ContractError::Unauthorized{});
 
@@ -38,12 +46,12 @@ mod tests {
         let minter = String::from("minter1");
 
         let info = mock_info(&sender, &vec![]);
-        let msg = ExecuteMsg::UpdateMinter{minter};
+        let msg = ExecuteMsg::UpdateMinter{minter, expires: None};
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         
         // check if the registration works properly
-        let minters = query_minters(deps.as_ref(), mock_env()).unwrap();
-        assert_eq!(minters, vec![String::from("minter1")]);
+        let minters = query_minters(deps.as_ref(), mock_env(), None, None).unwrap();
+        assert_eq!(minters.minters, vec![String::from("minter1")]);
 
         let mint_msg = GFMintMsg { 
             owner: String::from("minter1"), 
@@ -51,14 +59,19 @@ mod tests {
             image_uri: Some(String::from("https://glassflow")), 
             external_link: Some(String::from("https://external")), 
             description:  Some(String::from("first nft")), 
-            collection: Some(Uint128::from(1 as u128)), 
             num_real_repr: Uint128::from(1 as u128), 
             num_nfts: Uint128::from(1 as u128), 
             royalties: vec![Royalty {
                 address: String::from("minter1") ,
                 royalty_rate: Decimal::from_atomics(3u64, 1).unwrap()
             }], 
-            init_price: Uint128::from(100 as u128)  
+            init_price: Uint128::from(100 as u128),
+            init_price_asset: None,
+            nft_addr: String::from("nft_address"),
+            transferable: true,
+            localized_names: vec![],
+            localized_descriptions: vec![],
+            custody: false,
         };
         let info = mock_info(&String::from("minter1"), &vec![]);
 
@@ -70,17 +83,21 @@ mod tests {
             owner: String::from("minter1"),
             token_uri: Some(String::from("https://glassflow")),
             extension: Metadata {
-                name: String::from("first_nft"),
+                name: Some(String::from("first_nft")),
                 description: Some(String::from("first nft")),
                 external_link: Some(String::from("https://external")),
-                collection: Some(Uint128::from(1 as u128)),
-                num_real_repr: Uint128::from(1 as u128),
-                num_nfts:Uint128::from(1 as u128),
-                royalties: vec![Royalty {
+                nft_addr: String::from("nft_address"),
+                num_real_repr: Some(Uint128::from(1 as u128)),
+                num_nfts: Some(Uint128::from(1 as u128)),
+                royalties: Some(vec![Royalty {
                     address: String::from("minter1") ,
                     royalty_rate: Decimal::from_atomics(3u64, 1).unwrap()
-                }], 
-                init_price: Uint128::from(100 as u128),
+                }]), 
+                init_price: Some(Uint128::from(100 as u128)),
+                init_price_asset: None,
+                transferable: Some(true),
+                localized_names: None,
+                localized_descriptions: None,
             }
         });
         assert_eq!(1, res.messages.len());
@@ -243,4 +260,283 @@ mod tests {
     //         Err(_) => {}
     //     }
     // }
+
+    #[test]
+    fn id_counters() {
+        use cosmwasm_std::testing::MockStorage;
+        use cw_storage_plus::Item;
+        use crate::ids::{next_id, last_id, LISTING_IDS, TOKEN_IDS, OFFER_IDS};
+
+        let mut storage = MockStorage::new();
+        assert_eq!(last_id(&storage, &LISTING_IDS).unwrap(), 0);
+
+        // ids start at 1 and each counter allocates its own sequence
+        assert_eq!(next_id(&mut storage, &LISTING_IDS).unwrap(), 1);
+        assert_eq!(next_id(&mut storage, &LISTING_IDS).unwrap(), 2);
+        assert_eq!(next_id(&mut storage, &TOKEN_IDS).unwrap(), 1);
+        assert_eq!(next_id(&mut storage, &OFFER_IDS).unwrap(), 1);
+        assert_eq!(next_id(&mut storage, &LISTING_IDS).unwrap(), 3);
+        assert_eq!(last_id(&storage, &LISTING_IDS).unwrap(), 3);
+        assert_eq!(last_id(&storage, &TOKEN_IDS).unwrap(), 1);
+        assert_eq!(last_id(&storage, &OFFER_IDS).unwrap(), 1);
+
+        // an exhausted counter fails instead of handing out an id again
+        let counter: Item<u64> = Item::new("exhausted");
+        counter.save(&mut storage, &u64::MAX).unwrap();
+        next_id(&mut storage, &counter).unwrap_err();
+        assert_eq!(last_id(&storage, &counter).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn id_counters_continue_after_the_legacy_listing_count() {
+        use crate::contract::migrate;
+        use crate::ids::{next_id, seed_legacy_counters, LISTING_IDS, TOKEN_IDS, OFFER_IDS};
+        use crate::msg::MigrateMsg;
+        use crate::state::{read_config, store_config};
+
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg::default()).unwrap();
+        let mut config = read_config(&deps.storage).unwrap();
+        config.listing_count = 7;
+        store_config(&mut deps.storage, &config).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(next_id(&mut deps.storage, &LISTING_IDS).unwrap(), 8);
+        assert_eq!(next_id(&mut deps.storage, &TOKEN_IDS).unwrap(), 8);
+        assert_eq!(next_id(&mut deps.storage, &OFFER_IDS).unwrap(), 1);
+
+        // seeding again keeps the counters that are already in use
+        seed_legacy_counters(&mut deps.storage, 7).unwrap();
+        assert_eq!(next_id(&mut deps.storage, &LISTING_IDS).unwrap(), 9);
+    }
+
+    #[test]
+    fn bps_conversions() {
+        use crate::math::{bps_to_decimal, decimal_to_bps};
+
+        assert_eq!(bps_to_decimal(250), Decimal::permille(25));
+        assert_eq!(bps_to_decimal(10_000), Decimal::one());
+        for bps in [0, 1, 250, 3_333, 10_000, 1_000_000] {
+            assert_eq!(decimal_to_bps(bps_to_decimal(bps)).unwrap(), bps);
+        }
+
+        // rates finer than a basis point are not rounded away
+        decimal_to_bps(Decimal::from_ratio(1u128, 3u128)).unwrap_err();
+        decimal_to_bps(Decimal::from_ratio(1u128, 100_000u128)).unwrap_err();
+    }
+
+    #[test]
+    fn payout_shares_round_down() {
+        use crate::math::{bps_to_decimal, payout_share};
+
+        assert_eq!(payout_share(Uint128::new(99), Decimal::percent(50)), Uint128::new(49));
+        assert_eq!(payout_share(Uint128::new(1), Decimal::percent(99)), Uint128::zero());
+        assert_eq!(payout_share(Uint128::new(1_000), bps_to_decimal(1)), Uint128::zero());
+        assert_eq!(payout_share(Uint128::new(10_000), bps_to_decimal(1)), Uint128::new(1));
+        assert_eq!(payout_share(Uint128::MAX, Decimal::one()), Uint128::MAX);
+
+        // shares of a price never add up to more than it, the remainder is left for the seller
+        let price = Uint128::new(100);
+        let third = Decimal::from_ratio(1u128, 3u128);
+        let shares = payout_share(price, third) + payout_share(price, third) + payout_share(price, third);
+        assert_eq!(shares, Uint128::new(99));
+    }
+
+    #[test]
+    fn funds_validation() {
+        use cosmwasm_std::{coin, coins};
+        use crate::asset::{Asset, AssetInfo};
+        use crate::coin_helpers::{assert_paid_all, may_pay, must_pay, one_coin};
+
+        let native = |denom: &str, amount: u128| Asset { info: AssetInfo::NativeToken { denom: denom.to_string() }, amount: Uint128::new(amount) };
+        let cw20 = Asset { info: AssetInfo::Token { contract_addr: String::from("token") }, amount: Uint128::new(10) };
+        let none = mock_info("sender", &[]);
+        let ujuno = mock_info("sender", &coins(10, "ujuno"));
+        let both = mock_info("sender", &[coin(10, "ujuno"), coin(5, "uatom")]);
+
+        assert_eq!(one_coin(&ujuno).unwrap(), coin(10, "ujuno"));
+        assert_eq!(one_coin(&none).unwrap_err(), ContractError::NoFunds {});
+        assert_eq!(one_coin(&both).unwrap_err(), ContractError::MultipleDenoms {});
+        assert_eq!(one_coin(&mock_info("sender", &coins(0, "ujuno"))).unwrap_err(), ContractError::ZeroFunds { denom: String::from("ujuno") });
+        assert_eq!(must_pay(&ujuno, "ujuno").unwrap(), Uint128::new(10));
+        assert_eq!(must_pay(&ujuno, "uatom").unwrap_err(), ContractError::WrongDenom { denom: String::from("uatom") });
+        assert_eq!(must_pay(&none, "ujuno").unwrap_err(), ContractError::NoFunds {});
+        assert_eq!(may_pay(&none, "ujuno").unwrap(), Uint128::zero());
+        assert_eq!(may_pay(&both, "ujuno").unwrap_err(), ContractError::MultipleDenoms {});
+
+        native("ujuno", 10).assert_sent(&ujuno).unwrap();
+        assert_eq!(native("ujuno", 11).assert_sent(&ujuno).unwrap_err(), ContractError::FundsMismatch { expected: String::from("11ujuno") });
+        cw20.assert_sent(&none).unwrap();
+        assert_eq!(cw20.assert_sent(&ujuno).unwrap_err(), ContractError::UnexpectedFunds {});
+
+        // deposits are summed by denom and have to be paid exactly
+        assert_paid_all(&none, &[cw20.clone()]).unwrap();
+        assert_paid_all(&ujuno, &[native("ujuno", 4), native("ujuno", 6), cw20]).unwrap();
+        assert_paid_all(&both, &[native("uatom", 5), native("ujuno", 10)]).unwrap();
+        assert_eq!(assert_paid_all(&ujuno, &[]).unwrap_err(), ContractError::UnexpectedFunds {});
+        assert_eq!(
+            assert_paid_all(&ujuno, &[native("uatom", 5), native("ujuno", 10)]).unwrap_err(),
+            ContractError::FundsMismatch { expected: String::from("5uatom,10ujuno") }
+        );
+    }
+
+    #[test]
+    fn asset_helpers() {
+        use cosmwasm_std::{coins, BankMsg};
+        use cosmwasm_std::testing::MockApi;
+        use crate::asset::{Asset, AssetInfo};
+
+        let api = MockApi::default();
+        let native = |denom: &str, amount: u128| Asset { info: AssetInfo::NativeToken { denom: denom.to_string() }, amount: Uint128::new(amount) };
+
+        native("ujuno", 1).info.validate(&api).unwrap();
+        native("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2", 1).info.validate(&api).unwrap();
+        native("ju", 1).info.validate(&api).unwrap_err();
+        native("1juno", 1).info.validate(&api).unwrap_err();
+        native("u juno", 1).info.validate(&api).unwrap_err();
+
+        assert_eq!(native("ujuno", 4).checked_add(&native("ujuno", 6)).unwrap(), native("ujuno", 10));
+        assert_eq!(native("ujuno", 10).checked_sub(&native("ujuno", 4)).unwrap(), native("ujuno", 6));
+        native("ujuno", 4).checked_sub(&native("ujuno", 6)).unwrap_err();
+        native("ujuno", 4).checked_add(&native("uatom", 6)).unwrap_err();
+
+        assert_eq!(
+            native("ujuno", 10).into_transfer_msg(Addr::unchecked("recipient")).unwrap(),
+            CosmosMsg::Bank(BankMsg::Send { to_address: String::from("recipient"), amount: coins(10, "ujuno") })
+        );
+        let token = Asset { info: AssetInfo::Token { contract_addr: String::from("token") }, amount: Uint128::new(10) };
+        match token.into_transfer_msg(Addr::unchecked("recipient")).unwrap() {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, funds, .. }) => {
+                assert_eq!(contract_addr, "token");
+                assert!(funds.is_empty());
+            }
+            msg => panic!("unexpected transfer {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn v2_messages_are_strict_and_convert_to_v1() {
+        use cosmwasm_std::from_slice;
+        use crate::msg::v2;
+
+        // v1 ignores unknown fields, v2 rejects them and its defaults
+        let v1: ExecuteMsg = from_slice(br#"{"withdraw_listing":{"listing_id":"AUCTION.1","unknown":1}}"#).unwrap();
+        assert_eq!(v1, ExecuteMsg::WithdrawListing { listing_id: String::from("AUCTION.1") });
+        from_slice::<ExecuteMsg>(br#"{"v2":{"settle":{"listing_id":"AUCTION.1","unknown":1}}}"#).unwrap_err();
+        from_slice::<ExecuteMsg>(br#"{"v2":{"place_listing":{"token_id":"1","nft_addr":"nft","minimum_bid":{"info":{"native_token":{"denom":"ujuno"}},"amount":"1"},"escrowless":false}}}"#).unwrap();
+        from_slice::<ExecuteMsg>(br#"{"v2":{"place_listing":{"token_id":"1","nft_addr":"nft","minimum_bid":{"info":{"native_token":{"denom":"ujuno"}},"amount":"1"}}}}"#).unwrap_err();
+
+        let v2: ExecuteMsg = from_slice(br#"{"v2":{"claim":{"listing_id":"AUCTION.1","leg":"proceeds"}}}"#).unwrap();
+        let v2 = match v2 {
+            ExecuteMsg::V2(msg) => ExecuteMsg::from(msg),
+            msg => panic!("unexpected message {:?}", msg),
+        };
+        assert_eq!(v2, ExecuteMsg::ClaimProceeds { listing_id: String::from("AUCTION.1") });
+        assert_eq!(
+            crate::msg::QueryMsg::from(v2::QueryMsg::Listing { listing_id: String::from("AUCTION.1") }),
+            crate::msg::QueryMsg::ResolveListing { id: String::from("AUCTION.1") }
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn testing_helpers_keep_the_invariants() {
+        use cosmwasm_std::coins;
+        use cosmwasm_std::testing::mock_dependencies_with_balance;
+        use crate::asset::{Asset, AssetInfo};
+        use crate::state::{load_listing, OFFERS};
+        use crate::testing::{advance_blocks, assert_invariants, mock_env_at, new_listing, put_listing, put_offer};
+
+        let mut deps = mock_dependencies_with_balance(&coins(150, "ujuno"));
+        let env = mock_env_at(100);
+        assert_eq!(env, mock_env_at(100));
+        instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), InstantiateMsg::default()).unwrap();
+
+        let price = |amount: u128| Asset { info: AssetInfo::NativeToken { denom: String::from("ujuno") }, amount: Uint128::new(amount) };
+        let contract = &env.contract.address;
+        let mut listing = new_listing(contract, Addr::unchecked("seller"), Addr::unchecked("nft"), "GF.1", price(50), 1_100);
+        listing.max_bid = price(100);
+        listing.max_bidder = Addr::unchecked("alice");
+        listing.tags = vec![String::from("art")];
+        let listing_id = put_listing(&mut deps.storage, contract, &listing).unwrap();
+        let offer_id = put_offer(&mut deps.storage, Addr::unchecked("bob"), Addr::unchecked("nft"), "GF.2", price(50)).unwrap();
+
+        assert_eq!(load_listing(&deps.storage, &listing_id).unwrap(), listing);
+        assert_eq!(OFFERS.load(&deps.storage, offer_id).unwrap().buyer, Addr::unchecked("bob"));
+        assert_invariants(deps.as_ref(), &env).unwrap();
+
+        let mut block = env.block.clone();
+        advance_blocks(&mut block, 10);
+        assert_eq!(block, mock_env_at(110).block);
+    }
+
+    #[test]
+    fn token_id_formats() {
+        use crate::ids::{parse_token_id_format, render_token_id};
+
+        let parts = parse_token_id_format("{symbol}-{serial:05}").unwrap();
+        assert_eq!(render_token_id(&parts, "GAL", 42), "GAL-00042");
+        // serials outgrowing the padding keep all their digits
+        assert_eq!(render_token_id(&parts, "GAL", 123_456), "GAL-123456");
+        let parts = parse_token_id_format("inv/{serial}").unwrap();
+        assert_eq!(render_token_id(&parts, "GAL", 7), "inv/7");
+
+        // one serial keeps the ids unique
+        for format in ["{symbol}", "{serial}-{serial}", "{serial:5}", "{serial:0}", "{serial:0+5}", "{serial:021}",
+            "{name}-{serial}", "{serial", "a b{serial}", &format!("{}{{serial}}", "x".repeat(60))] {
+            assert!(parse_token_id_format(format).is_err(), "{}", format);
+        }
+    }
+
+    #[cfg(feature = "ibc")]
+    #[test]
+    fn results_channel() {
+        use cosmwasm_std::testing::{mock_ibc_channel_open_init, mock_ibc_channel_close_init};
+        use cosmwasm_std::IbcOrder;
+        use crate::ibc::{ibc_channel_open, ibc_channel_close, RESULTS_VERSION};
+        use crate::state::read_config;
+
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), InstantiateMsg::default()).unwrap();
+
+        // only unordered channels speaking the results version are accepted
+        let msg = mock_ibc_channel_open_init("channel-1", IbcOrder::Ordered, RESULTS_VERSION);
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidChannelOrder{});
+        let msg = mock_ibc_channel_open_init("channel-1", IbcOrder::Unordered, "ics20-1");
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidChannelVersion{ expected: RESULTS_VERSION.to_string() });
+        let msg = mock_ibc_channel_open_init("channel-1", IbcOrder::Unordered, RESULTS_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap();
+
+        // only the owner selects the channel
+        let msg = ExecuteMsg::UpdateResultsChannel{ channel_id: Some(String::from("channel-1")) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized{});
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(read_config(&deps.storage).unwrap().results_channel, Some(String::from("channel-1")));
+
+        // closing another channel keeps it, closing the results channel unsets it
+        let msg = mock_ibc_channel_close_init("channel-2", IbcOrder::Unordered, RESULTS_VERSION);
+        ibc_channel_close(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(read_config(&deps.storage).unwrap().results_channel, Some(String::from("channel-1")));
+        let msg = mock_ibc_channel_close_init("channel-1", IbcOrder::Unordered, RESULTS_VERSION);
+        ibc_channel_close(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(read_config(&deps.storage).unwrap().results_channel, None);
+    }
+
+    #[test]
+    fn pubkey_address_matches_the_chain() {
+        use crate::contract::pubkey_address;
+        use cosmwasm_std::Binary;
+
+        // account of a known key, as derived by cosmjs
+        let pubkey = Binary::from_base64("A08EGB7ro1ORuFhjOnZcSgwYlpe0DSFjVNUIkNNQxwKQ").unwrap();
+        assert_eq!(pubkey_address("cosmos", &pubkey).unwrap(), "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6");
+
+        // uncompressed keys hash to a different account
+        let err = pubkey_address("cosmos", &[4u8; 65]).unwrap_err();
+        assert_eq!(err, ContractError::InvalidListingKey {});
+    }
 }
@@ -0,0 +1,69 @@
+use crate::error::ContractError;
+
+pub const IPFS_SCHEME: &str = "ipfs://";
+pub const ALLOWED_URI_SCHEMES: [&str; 2] = [IPFS_SCHEME, "https://"];
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+const HEX_ALPHABET: &str = "0123456789abcdef";
+
+// shortest multibase encoded CIDv1 body we accept (version, codec and a multihash)
+const MIN_CID_V1_LENGTH: usize = 8;
+
+// checks the uri uses a whitelisted scheme and, for ipfs, points to a syntactically valid CID
+pub fn validate_uri(uri: &str) -> Result<(), ContractError> {
+    let valid = ALLOWED_URI_SCHEMES
+        .iter()
+        .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme))
+        && !uri.chars().any(|c| c.is_whitespace() || c.is_control());
+
+    if !valid {
+        return Err(ContractError::InvalidUri { uri: uri.to_string() });
+    }
+
+    if let Some(path) = uri.strip_prefix(IPFS_SCHEME) {
+        if !is_valid_ipfs_path(path) {
+            return Err(ContractError::InvalidIpfsUri { uri: uri.to_string() });
+        }
+    }
+    Ok(())
+}
+
+// `<cid>` optionally followed by `/<path>` without empty segments
+pub fn is_valid_ipfs_path(path: &str) -> bool {
+    let mut segments = path.split('/');
+    let cid = segments.next().unwrap_or_default();
+
+    is_valid_cid(cid) && segments.all(|segment| !segment.is_empty())
+}
+
+pub fn is_valid_cid(cid: &str) -> bool {
+    is_valid_cid_v0(cid) || is_valid_cid_v1(cid)
+}
+
+// base58btc encoded sha2-256 multihash, always `Qm` followed by 44 characters
+pub fn is_valid_cid_v0(cid: &str) -> bool {
+    cid.len() == 46 && cid.starts_with("Qm") && in_alphabet(cid, BASE58_ALPHABET)
+}
+
+// multibase prefix followed by the encoded `<version 1><codec><multihash>`
+pub fn is_valid_cid_v1(cid: &str) -> bool {
+    if cid.len() < MIN_CID_V1_LENGTH {
+        return false;
+    }
+
+    let (prefix, body) = cid.split_at(1);
+    match prefix {
+        // leading 0x01 version byte encodes to `a` in base32
+        "b" => body.starts_with('a') && in_alphabet(body, BASE32_ALPHABET),
+        "B" => body.starts_with('A') && in_alphabet(&body.to_lowercase(), BASE32_ALPHABET),
+        "f" => body.starts_with("01") && in_alphabet(body, HEX_ALPHABET),
+        "F" => body.starts_with("01") && in_alphabet(&body.to_lowercase(), HEX_ALPHABET),
+        "z" => in_alphabet(body, BASE58_ALPHABET),
+        _ => false,
+    }
+}
+
+fn in_alphabet(value: &str, alphabet: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| alphabet.contains(c))
+}
@@ -0,0 +1,3450 @@
+use cosmwasm_std::{
+    coins, from_slice, to_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw721::{Expiration, NftInfoResponse, OwnerOfResponse};
+use cw721_base::msg::{ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, QueryMsg as Cw721QueryMsg};
+use cw721_base::{ContractError as Cw721ContractError, Cw721Contract};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+use cw_auction::asset::{Asset, AssetInfo};
+use cw_auction::contract::{execute, instantiate, intent_digest, pubkey_address, query, reply, sudo, RAFFLE_DRAW_TIMEOUT_BLOCKS};
+use cw_auction::msg::v2::{ExecuteMsg as V2ExecuteMsg, QueryMsg as V2QueryMsg};
+use cw_auction::msg::{
+    AcceptedDenomsResponse, AccruedRoyaltiesResponse, BidIntent, ConfigHistoryResponse, ConvertAmountResponse, CreatorOverviewResponse, Cw20HookMsg, DeadLettersResponse, EscrowResponse, ExchangeRateResponse, FeaturedListingsResponse, ExecuteMsg, ExportEntry, ExportResponse, ExportSection, FeaturesResponse, FloorPrice, FloorPriceResponse, GFMintMsg, InitialMinter, InstantiateMsg, LedgerResponse, ListingIntent, ListingStatus, ListingsByCollectionResponse, ListingsByTagResponse,
+    MinterApplicationsResponse, MinterInfoResponse, OracleQueryMsg, PayoutPreview, PlaceListingParams, QueryMsg, RandomnessProviderMsg, ResolveListingResponse, SalesResponse, SellerStatsResponse, SettlementPreviewResponse, SudoMsg, VerifyEscrowResponse, YieldBalanceResponse, YieldVaultMsg, YieldVaultQueryMsg,
+    TokenFullInfoResponse, TokenIdFormatResponse,
+};
+use cw_auction::state::{hashed_listing_id, AbsenteeBid, AntiSniping, AutoRelist, BidState, RevealedReserve, Collection, WatchTarget, CollectionPause, Config, CustodiedNft, Cw721Variant, DenomInfo, FeeTier, IncrementStep, Listing, ListingTemplate, Metadata, MinterAction, NftInfo, PayoutSplit, PayoutStatus, PriceFloor, ProvenanceCommitment, QuarantinedNft, RateLimits, REPLIES, Royalty, SettlementReceipt, YieldVault};
+
+const OWNER: &str = "owner";
+const ARTIST: &str = "artist";
+const SELLER: &str = "seller";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+const FEE_COLLECTOR: &str = "collector";
+const DENOM: &str = "ujuno";
+const INITIAL_BALANCE: u128 = 1_000_000;
+
+// cw721-base with the auction metadata, reporting its instantiator as collection owner
+type NftContract<'a> = Cw721Contract<'a, Metadata, Empty>;
+const COLLECTION_OWNER: Item<Addr> = Item::new("collection_owner");
+
+fn nft_instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: Cw721InstantiateMsg) -> StdResult<Response> {
+    COLLECTION_OWNER.save(deps.storage, &info.sender)?;
+    NftContract::default().instantiate(deps, env, info, msg)
+}
+
+// lets the tests make transfers out of the collection fail
+const TRANSFERS_FROZEN: Item<bool> = Item::new("transfers_frozen");
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+enum NftExecuteMsg {
+    Freeze { freeze_transfers: bool },
+    Base(Cw721ExecuteMsg<Metadata>),
+}
+
+fn nft_execute(deps: DepsMut, env: Env, info: MessageInfo, msg: NftExecuteMsg) -> Result<Response, Cw721ContractError> {
+    match msg {
+        NftExecuteMsg::Freeze { freeze_transfers } => {
+            TRANSFERS_FROZEN.save(deps.storage, &freeze_transfers)?;
+            Ok(Response::default())
+        }
+        NftExecuteMsg::Base(Cw721ExecuteMsg::TransferNft { .. }) if TRANSFERS_FROZEN.may_load(deps.storage)?.unwrap_or(false) => {
+            Err(StdError::generic_err("transfers frozen").into())
+        }
+        NftExecuteMsg::Base(msg) => NftContract::default().execute(deps, env, info, msg),
+    }
+}
+
+fn nft_query(deps: Deps, env: Env, msg: Cw721QueryMsg) -> StdResult<Binary> {
+    match msg {
+        Cw721QueryMsg::CollectionInfo {} => to_binary(&Collection {
+            name: NftContract::default().contract_info.load(deps.storage)?.name,
+            description: None,
+            owner: COLLECTION_OWNER.load(deps.storage)?,
+            logo_url: None,
+            banner_url: None,
+        }),
+        msg => NftContract::default().query(deps, env, msg),
+    }
+}
+
+fn auction_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply).with_sudo(sudo))
+}
+
+fn nft_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(nft_execute, nft_instantiate, nft_query))
+}
+
+// accepts randomness requests, the tests answer them as the provider
+fn randomness_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, _: RandomnessProviderMsg| -> StdResult<Response> { Ok(Response::default()) },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { to_binary(&Empty {}) },
+    ))
+}
+
+// quotes 2.5 units of any other denom per unit of the auction denom
+fn oracle_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |_: Deps, _: Env, msg: OracleQueryMsg| -> StdResult<Binary> {
+            let OracleQueryMsg::ExchangeRate { base_denom, .. } = msg;
+            if base_denom != DENOM {
+                return Err(StdError::generic_err("no rate"));
+            }
+            to_binary(&ExchangeRateResponse { rate: Decimal::permille(2_500) })
+        },
+    ))
+}
+
+// a vault holding the deposits of a single depositor, everything it holds is that depositor's
+fn yield_vault_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, info: MessageInfo, msg: YieldVaultMsg| -> StdResult<Response> {
+            match msg {
+                YieldVaultMsg::Deposit {} => Ok(Response::default()),
+                YieldVaultMsg::Withdraw { amount } => Ok(Response::new().add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(amount.u128(), DENOM),
+                })),
+            }
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |deps: Deps, env: Env, msg: YieldVaultQueryMsg| -> StdResult<Binary> {
+            let YieldVaultQueryMsg::Balance { .. } = msg;
+            to_binary(&YieldBalanceResponse { amount: deps.querier.query_balance(&env.contract.address, DENOM)?.amount })
+        },
+    ))
+}
+
+// a vault that takes deposits and never returns them
+fn broken_yield_vault_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, msg: YieldVaultMsg| -> StdResult<Response> {
+            match msg {
+                YieldVaultMsg::Deposit {} => Ok(Response::default()),
+                YieldVaultMsg::Withdraw { .. } => Err(StdError::generic_err("vault is frozen")),
+            }
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        |_: Deps, _: Env, _: YieldVaultQueryMsg| -> StdResult<Binary> { Err(StdError::generic_err("vault is frozen")) },
+    ))
+}
+
+// lets the tests lock recipients out of receiving the token, like a vesting account would be
+const LOCKED_RECIPIENTS: Item<Vec<String>> = Item::new("locked_recipients");
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+enum TokenExecuteMsg {
+    Lock { lock_recipient: String },
+    Unlock { unlock_recipient: String },
+    Base(Cw20ExecuteMsg),
+}
+
+fn cw20_execute(deps: DepsMut, env: Env, info: MessageInfo, msg: TokenExecuteMsg) -> Result<Response, cw20_base::ContractError> {
+    let mut locked = LOCKED_RECIPIENTS.may_load(deps.storage)?.unwrap_or_default();
+    match msg {
+        TokenExecuteMsg::Lock { lock_recipient } => {
+            locked.push(lock_recipient);
+            LOCKED_RECIPIENTS.save(deps.storage, &locked)?;
+            Ok(Response::default())
+        }
+        TokenExecuteMsg::Unlock { unlock_recipient } => {
+            locked.retain(|recipient| *recipient != unlock_recipient);
+            LOCKED_RECIPIENTS.save(deps.storage, &locked)?;
+            Ok(Response::default())
+        }
+        TokenExecuteMsg::Base(Cw20ExecuteMsg::Transfer { recipient, .. }) if locked.contains(&recipient) => {
+            Err(StdError::generic_err("recipient locked").into())
+        }
+        TokenExecuteMsg::Base(msg) => cw20_base::contract::execute(deps, env, info, msg),
+    }
+}
+
+fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+struct Suite {
+    app: App,
+    auction: Addr,
+    nft: Addr,
+    cw20: Addr,
+}
+
+fn setup() -> Suite {
+    let mut app = App::new(|router, _, storage| {
+        for address in [SELLER, ALICE, BOB] {
+            router.bank
+                .init_balance(storage, &Addr::unchecked(address), coins(INITIAL_BALANCE, DENOM))
+                .unwrap();
+        }
+    });
+
+    let auction_code = app.store_code(auction_contract());
+    let nft_code = app.store_code(nft_contract());
+    let cw20_code = app.store_code(cw20_contract());
+
+    let auction = app
+        .instantiate_contract(auction_code, Addr::unchecked(OWNER), &InstantiateMsg::default(), &[], "auction", None)
+        .unwrap();
+
+    // the auction mints on behalf of the whitelisted artist owning the collection
+    let nft = app
+        .instantiate_contract(
+            nft_code,
+            Addr::unchecked(ARTIST),
+            &Cw721InstantiateMsg {
+                name: String::from("glassflow"),
+                symbol: String::from("GF"),
+                minter: auction.to_string(),
+            },
+            &[],
+            "nft",
+            None,
+        )
+        .unwrap();
+
+    let cw20 = app
+        .instantiate_contract(
+            cw20_code,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: String::from("glass token"),
+                symbol: String::from("GLASS"),
+                decimals: 6,
+                initial_balances: vec![
+                    Cw20Coin { address: String::from(ALICE), amount: Uint128::new(INITIAL_BALANCE) },
+                    Cw20Coin { address: String::from(BOB), amount: Uint128::new(INITIAL_BALANCE) },
+                ],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "cw20",
+            None,
+        )
+        .unwrap();
+
+    let mut suite = Suite { app, auction, nft, cw20 };
+
+    suite.execute(OWNER, ExecuteMsg::UpdateMinter { minter: String::from(ARTIST), expires: None }, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::UpdateAcceptedDenom {
+        denom: String::from(DENOM),
+        display: String::from("JUNO"),
+        decimals: 6,
+        min_bid_unit: Uint128::new(1),
+        tick_size: None,
+    }, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::UpdateFeeConfig {
+        fee_tiers: vec![FeeTier { min_volume: Uint128::zero(), fee_rate: Decimal::percent(5) }],
+        fee_collector: Some(String::from(FEE_COLLECTOR)),
+        primary_fee_rate: None,
+    }, &[]).unwrap();
+
+    suite
+}
+
+impl Suite {
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg, funds: &[Coin]) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(Addr::unchecked(sender), self.auction.clone(), &msg, funds)
+    }
+
+    // artist mints a token with a 10% royalty to owner
+    fn mint(&mut self, owner: &str) -> String {
+        self.mint_priced_in(owner, AssetInfo::NativeToken { denom: String::from(DENOM) })
+    }
+
+    // mints a token whose initial price is denominated in the asset
+    fn mint_priced_in(&mut self, owner: &str, init_price_asset: AssetInfo) -> String {
+        let res = self.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+            owner: String::from(owner),
+            name: String::from("first nft"),
+            image_uri: Some(String::from("https://glassflow.art/1.png")),
+            external_link: None,
+            description: Some(String::from("first nft")),
+            num_real_repr: Uint128::zero(),
+            num_nfts: Uint128::new(1),
+            royalties: vec![Royalty { address: String::from(ARTIST), royalty_rate: Decimal::percent(10) }],
+            init_price: Uint128::new(100),
+            init_price_asset: Some(init_price_asset),
+            nft_addr: self.nft.to_string(),
+            transferable: true,
+            localized_names: vec![],
+            localized_descriptions: vec![],
+            custody: false,
+        }), &[]).unwrap();
+
+        attribute(&res, "token_id")
+    }
+
+    // approves the auction for the token and lists it, returning the listing id
+    fn place_listing(&mut self, seller: &str, token_id: &str, minimum_bid: Asset) -> String {
+        self.app.execute_contract(
+            Addr::unchecked(seller),
+            self.nft.clone(),
+            &Cw721ExecuteMsg::<Metadata>::Approve {
+                spender: self.auction.to_string(),
+                token_id: token_id.to_string(),
+                expires: None,
+            },
+            &[],
+        ).unwrap();
+
+        let res = self.execute(seller, ExecuteMsg::PlaceListing {
+            id: token_id.to_string(),
+            minimum_bid,
+            nft_addr: self.nft.to_string(),
+            escrowless: None,
+            template: None,
+        }, &[]).unwrap();
+
+        attribute(&res, "listing_id")
+    }
+
+    fn bid_native(&mut self, bidder: &str, listing_id: &str, amount: u128) -> anyhow::Result<AppResponse> {
+        self.execute(bidder, ExecuteMsg::BidListing {
+            listing_id: listing_id.to_string(),
+            bid_price: native(amount),
+            on_behalf_of: None,
+        }, &coins(amount, DENOM))
+    }
+
+    fn bid_cw20(&mut self, bidder: &str, listing_id: &str, amount: u128) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(Addr::unchecked(bidder), self.cw20.clone(), &Cw20ExecuteMsg::Send {
+            contract: self.auction.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&Cw20HookMsg::BidListing { listing_id: listing_id.to_string(), on_behalf_of: None }).unwrap(),
+        }, &[])
+    }
+
+    fn nft_owner(&self, token_id: &str) -> String {
+        let res: OwnerOfResponse = self.app.wrap().query_wasm_smart(&self.nft, &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        }).unwrap();
+        res.owner
+    }
+
+    // registers a randomness provider, whose requests the tests answer
+    fn randomness_provider(&mut self) -> Addr {
+        let code = self.app.store_code(randomness_contract());
+        let provider = self.app
+            .instantiate_contract(code, Addr::unchecked(OWNER), &Empty {}, &[], "randomness", None)
+            .unwrap();
+        self.execute(OWNER, ExecuteMsg::UpdateRandomnessProvider { randomness_provider: Some(provider.to_string()) }, &[]).unwrap();
+        provider
+    }
+
+    fn native_balance(&self, address: &str) -> u128 {
+        self.app.wrap().query_balance(address, DENOM).unwrap().amount.u128()
+    }
+
+    fn cw20_balance(&self, address: &str) -> u128 {
+        let res: BalanceResponse = self.app.wrap().query_wasm_smart(&self.cw20, &Cw20QueryMsg::Balance {
+            address: address.to_string(),
+        }).unwrap();
+        res.balance.u128()
+    }
+
+    fn cw20_asset(&self, amount: u128) -> Asset {
+        Asset { info: AssetInfo::Token { contract_addr: self.cw20.to_string() }, amount: Uint128::new(amount) }
+    }
+}
+
+fn native(amount: u128) -> Asset {
+    Asset { info: AssetInfo::NativeToken { denom: String::from(DENOM) }, amount: Uint128::new(amount) }
+}
+
+fn attribute(res: &AppResponse, key: &str) -> String {
+    res.events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.clone())
+        .unwrap_or_else(|| panic!("missing attribute {}", key))
+}
+
+#[test]
+fn mint_to_owner() {
+    let mut suite = setup();
+
+    // only whitelisted minters can mint
+    let err = suite.execute(ALICE, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(ALICE),
+        name: String::from("forged"),
+        image_uri: None,
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    let token_id = suite.mint(SELLER);
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+}
+
+#[test]
+fn native_auction_lifecycle() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+
+    // bids below the current one are rejected
+    suite.bid_native(ALICE, &listing_id, 50).unwrap_err();
+
+    // outbidding refunds the previous bidder
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 150);
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - 200);
+
+    // primary sale: 5% platform fee, no royalties
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+    assert_eq!(suite.native_balance(ARTIST), 0);
+
+    let receipt: Option<SettlementReceipt> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::SettlementReceipt { listing_id })
+        .unwrap();
+    assert_eq!(receipt.unwrap().winner, Addr::unchecked(BOB));
+
+    // secondary sale pays the royalty to the artist
+    let listing_id = suite.place_listing(BOB, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 1000).unwrap();
+    suite.execute(BOB, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10 + 50);
+    assert_eq!(suite.native_balance(ARTIST), 100);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - 200 + 850);
+}
+
+#[test]
+fn cw20_auction_lifecycle() {
+    let mut suite = setup();
+    let minimum_bid = suite.cw20_asset(100);
+    let token_id = suite.mint_priced_in(SELLER, minimum_bid.info.clone());
+    let listing_id = suite.place_listing(SELLER, &token_id, minimum_bid);
+
+    suite.bid_cw20(ALICE, &listing_id, 150).unwrap();
+    suite.bid_cw20(BOB, &listing_id, 200).unwrap();
+    assert_eq!(suite.cw20_balance(ALICE), INITIAL_BALANCE);
+    assert_eq!(suite.cw20_balance(BOB), INITIAL_BALANCE - 200);
+
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+    assert_eq!(suite.cw20_balance(FEE_COLLECTOR), 10);
+    assert_eq!(suite.cw20_balance(SELLER), 190);
+}
+
+#[test]
+fn refunds_to_locked_bidders_fall_back_to_escrow() {
+    let mut suite = setup();
+    let minimum_bid = suite.cw20_asset(100);
+    let token_id = suite.mint_priced_in(SELLER, minimum_bid.info.clone());
+    let listing_id = suite.place_listing(SELLER, &token_id, minimum_bid);
+    let cw20 = suite.cw20.clone();
+    suite.app.execute_contract(Addr::unchecked(OWNER), cw20, &TokenExecuteMsg::Lock { lock_recipient: String::from(ALICE) }, &[]).unwrap();
+
+    // the failed refund does not block the outbid
+    suite.bid_cw20(ALICE, &listing_id, 150).unwrap();
+    let res = suite.bid_cw20(BOB, &listing_id, 200).unwrap();
+    assert_eq!(attribute(&res, "refund_escrowed"), "1");
+
+    // later refunds go to escrow without another attempt
+    suite.bid_cw20(ALICE, &listing_id, 250).unwrap();
+    let res = suite.bid_cw20(BOB, &listing_id, 300).unwrap();
+    assert!(res.events.iter().flat_map(|event| event.attributes.iter()).all(|attribute| attribute.key != "refund_escrowed"));
+
+    let escrow: EscrowResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::Escrow { address: String::from(ALICE), start_after: None, limit: None })
+        .unwrap();
+    let amounts: Vec<_> = escrow.entries.iter().map(|entry| entry.asset.amount.u128()).collect();
+    assert_eq!(amounts, vec![150, 250]);
+
+    // the locked bidder claims to another address
+    suite.execute(ALICE, ExecuteMsg::ClaimEscrow { escrow_id: 1, recipient: None }, &[]).unwrap_err();
+    suite.execute(ALICE, ExecuteMsg::ClaimEscrow { escrow_id: 1, recipient: Some(String::from(ARTIST)) }, &[]).unwrap();
+    assert_eq!(suite.cw20_balance(ARTIST), 150);
+
+    // once unlocked, claiming to itself lets later refunds push to the bidder again
+    let cw20 = suite.cw20.clone();
+    suite.app.execute_contract(Addr::unchecked(OWNER), cw20, &TokenExecuteMsg::Unlock { unlock_recipient: String::from(ALICE) }, &[]).unwrap();
+    suite.execute(ALICE, ExecuteMsg::ClaimEscrow { escrow_id: 2, recipient: None }, &[]).unwrap();
+    suite.bid_cw20(ALICE, &listing_id, 350).unwrap();
+    let res = suite.bid_cw20(BOB, &listing_id, 400).unwrap();
+    assert!(res.events.iter().flat_map(|event| event.attributes.iter()).all(|attribute| attribute.key != "refund_escrowed"));
+    assert_eq!(suite.cw20_balance(ALICE), INITIAL_BALANCE - 150);
+    let escrow: EscrowResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::Escrow { address: String::from(ALICE), start_after: None, limit: None })
+        .unwrap();
+    assert!(escrow.entries.is_empty());
+}
+
+#[test]
+fn unsold_listing_returns_nft() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    // only the seller can withdraw
+    suite.execute(ALICE, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap_err();
+
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE);
+}
+
+#[test]
+fn hashed_listing_ids() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateListingIdScheme { hashed: true }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+
+    // the id is predictable from the token and its placement nonce
+    let nonce: u64 = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ListingNonce {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+    }).unwrap();
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    assert_eq!(listing_id, hashed_listing_id(&suite.nft, &token_id, nonce));
+
+    // the numeric alias keeps working
+    suite.bid_native(ALICE, "AUCTION.1", 150).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+
+    // relisting the same token hashes with the next nonce
+    let relisted = suite.place_listing(ALICE, &token_id, native(100));
+    assert_eq!(relisted, hashed_listing_id(&suite.nft, &token_id, nonce + 1));
+}
+
+#[test]
+fn bids_follow_denom_tick_size() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateAcceptedDenom {
+        denom: String::from(DENOM),
+        display: String::from("JUNO"),
+        decimals: 6,
+        min_bid_unit: Uint128::new(1),
+        tick_size: Some(Uint128::new(10)),
+    }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() })
+        .unwrap();
+    assert_eq!(listing.decimals, Some(6));
+
+    let err = suite.bid_native(ALICE, &listing_id, 155).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "amount is not a multiple of the tick size 10");
+    suite.bid_native(ALICE, &listing_id, 160).unwrap();
+}
+
+#[test]
+fn resolved_listings_derive_status_and_next_bid() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateAcceptedDenom {
+        denom: String::from(DENOM),
+        display: String::from("JUNO"),
+        decimals: 6,
+        min_bid_unit: Uint128::new(1),
+        tick_size: Some(Uint128::new(10)),
+    }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("reserved"),
+        template: ListingTemplate {
+            duration_blocks: Some(1_000),
+            min_increment: Some(Decimal::percent(5)),
+            reserve_price: Some(Uint128::new(200)),
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id,
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("reserved")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+    let resolve = |suite: &Suite| -> ResolveListingResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap()
+    };
+
+    let listing = resolve(&suite);
+    assert_eq!(listing.status, ListingStatus::Active);
+    assert_eq!(listing.blocks_remaining, 1_000);
+    assert_eq!(listing.accepted_denom.unwrap().display, "JUNO");
+    assert_eq!(listing.min_next_bid, Some(Uint128::new(100)));
+    assert!(!listing.reserve_met);
+
+    // outbidding 150 by 5% takes 157, rounded up to the tick size
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    let listing = resolve(&suite);
+    assert_eq!(listing.min_next_bid, Some(Uint128::new(160)));
+    assert!(!listing.reserve_met);
+    suite.bid_native(BOB, &listing_id, 160).unwrap();
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    assert!(resolve(&suite).reserve_met);
+
+    suite.app.update_block(|block| block.height += 1_001);
+    let listing = resolve(&suite);
+    assert_eq!(listing.status, ListingStatus::Ended);
+    assert_eq!(listing.blocks_remaining, 0);
+    assert_eq!(listing.min_next_bid, None);
+}
+
+#[test]
+fn highest_bidders_only_restate_when_allowed() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+
+    let err = suite.bid_native(ALICE, &listing_id, 200).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "the highest bidder can only raise its own bid");
+    suite.bid_native(ALICE, &listing_id, 250).unwrap();
+
+    suite.execute(ALICE, ExecuteMsg::UpdateBidRestatement { allowed: true }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateBidRestatement { allowed: true }, &[]).unwrap();
+    suite.bid_native(ALICE, &listing_id, 250).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 250);
+}
+
+#[test]
+fn retained_fees_are_withdrawn_from_the_ledger() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateFeePayout { retained: true }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+
+    let ledger = |suite: &Suite| -> LedgerResponse {
+        suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::Ledger { asset_info: native(0).info })
+            .unwrap()
+    };
+    let before = ledger(&suite);
+    assert_eq!(before.bids, Uint128::new(200));
+    assert_eq!(before.balance, Uint128::new(200));
+
+    // the 5% platform fee stays in the contract
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 0);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+    let after = ledger(&suite);
+    assert_eq!(after.bids, Uint128::zero());
+    assert_eq!(after.fees, Uint128::new(10));
+    assert_eq!(after.balance, Uint128::new(10));
+
+    let err = suite.execute(OWNER, ExecuteMsg::WithdrawFees { asset: native(11) }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "only 10 in retained fees");
+    suite.execute(ALICE, ExecuteMsg::WithdrawFees { asset: native(10) }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::WithdrawFees { asset: native(10) }, &[]).unwrap();
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10);
+    assert_eq!(ledger(&suite).fees, Uint128::zero());
+}
+
+#[test]
+fn charity_listings_pass_the_whole_price_through() {
+    const CHARITY: &str = "charity";
+    let mut suite = setup();
+    let template = |charity: &str, payout_splits: Vec<PayoutSplit>| ExecuteMsg::SaveListingTemplate {
+        name: String::from("charity"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits,
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: Some(charity.to_string()),
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    };
+    let err = suite.execute(SELLER, template(CHARITY, vec![]), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "charity is not an approved charity");
+
+    // only the owner and the listing curator approve charities
+    suite.execute(OWNER, ExecuteMsg::UpdateListingCurator { curator: Some(String::from(BOB)) }, &[]).unwrap();
+    let approve = ExecuteMsg::UpdateCharity { charity: String::from(CHARITY), approved: true };
+    suite.execute(ALICE, approve.clone(), &[]).unwrap_err();
+    suite.execute(BOB, approve, &[]).unwrap();
+    let approved: bool = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::Charity { address: String::from(CHARITY) })
+        .unwrap();
+    assert!(approved);
+
+    let split = PayoutSplit { address: String::from(BOB), share: Decimal::percent(10) };
+    suite.execute(SELLER, template(CHARITY, vec![split]), &[]).unwrap_err();
+    suite.execute(SELLER, template(CHARITY, vec![]), &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("charity")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+
+    // no platform fee is taken and the seller receives nothing
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(CHARITY), 200);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 0);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE);
+}
+
+#[test]
+fn anti_sniping_extensions_are_capped_per_listing() {
+    let mut suite = setup();
+    let anti_sniping = |max_extension_blocks: Option<u64>| ExecuteMsg::UpdateAntiSniping {
+        anti_sniping: Some(AntiSniping { window_blocks: 10, extension_blocks: 10, max_extensions: Some(2), max_extension_blocks }),
+    };
+    suite.execute(OWNER, anti_sniping(Some(0)), &[]).unwrap_err();
+    suite.execute(ALICE, anti_sniping(None), &[]).unwrap_err();
+    suite.execute(OWNER, anti_sniping(Some(12)), &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    let resolve = |suite: &Suite| -> ResolveListingResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap()
+    };
+    let block_limit = resolve(&suite).block_limit;
+
+    // bids before the window leave the end alone
+    suite.app.update_block(|block| block.height = block_limit - 11);
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    assert_eq!(resolve(&suite).extensions_used, 0);
+
+    suite.app.update_block(|block| block.height = block_limit - 5);
+    let res = suite.bid_native(BOB, &listing_id, 200).unwrap();
+    assert_eq!(attribute(&res, "extended_to"), (block_limit + 5).to_string());
+
+    // the second extension is cut to the blocks left of the total
+    suite.app.update_block(|block| block.height = block_limit + 4);
+    suite.bid_native(ALICE, &listing_id, 250).unwrap();
+    let listing = resolve(&suite);
+    assert_eq!(listing.block_limit, block_limit + 12);
+    assert_eq!(listing.extensions_used, 2);
+
+    suite.app.update_block(|block| block.height = block_limit + 12);
+    suite.bid_native(BOB, &listing_id, 300).unwrap();
+    assert_eq!(resolve(&suite).block_limit, block_limit + 12);
+    suite.app.update_block(|block| block.height += 1);
+    suite.bid_native(ALICE, &listing_id, 350).unwrap_err();
+}
+
+#[test]
+fn tick_reports_settlement_backlog() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    let res = suite.app.wasm_sudo(suite.auction.clone(), &SudoMsg::Tick {}).unwrap();
+    assert_eq!(attribute(&res, "active_listings"), "1");
+    assert_eq!(attribute(&res, "expired_unsettled_listings"), "0");
+
+    suite.app.update_block(|block| block.height += 100_000);
+    let res = suite.app.wasm_sudo(suite.auction.clone(), &SudoMsg::Tick {}).unwrap();
+    assert_eq!(attribute(&res, "active_listings"), "0");
+    assert_eq!(attribute(&res, "expired_unsettled_listings"), "1");
+    assert_eq!(attribute(&res, "pending_refunds"), "0");
+
+    // the counts follow listings placed and closed between ticks
+    let token_id = suite.mint(SELLER);
+    suite.place_listing(SELLER, &token_id, native(100));
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    let res = suite.app.wasm_sudo(suite.auction.clone(), &SudoMsg::Tick {}).unwrap();
+    assert_eq!(attribute(&res, "active_listings"), "1");
+    assert_eq!(attribute(&res, "expired_unsettled_listings"), "0");
+}
+
+#[test]
+fn cancel_with_penalty_compensates_bidder() {
+    let mut suite = setup();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("cancellable"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: Some(native(50)),
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let place_listing = ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("cancellable")),
+    };
+    // the penalty is deposited with the listing
+    suite.execute(SELLER, place_listing.clone(), &[]).unwrap_err();
+    let res = suite.execute(SELLER, place_listing, &coins(50, DENOM)).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(SELLER, ExecuteMsg::CancelListings { listing_ids: vec![listing_id] }, &[]).unwrap();
+
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE + 50);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE - 50);
+}
+
+#[test]
+fn cancel_without_penalty_requires_no_bids() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+
+    let err = suite.execute(SELLER, ExecuteMsg::CancelListings { listing_ids: vec![listing_id.clone()] }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("listing {} already has bids", listing_id));
+}
+
+#[test]
+fn buy_now_locks_once_bids_approach_price() {
+    let mut suite = setup();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("buy_now"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: Some(Uint128::new(1000)),
+            buy_now_lock: Some(Decimal::percent(50)),
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+
+    let place = |suite: &mut Suite| {
+        let token_id = suite.mint(SELLER);
+        suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+            spender: suite.auction.to_string(),
+            token_id: token_id.clone(),
+            expires: None,
+        }, &[]).unwrap();
+        let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+            id: token_id.clone(),
+            minimum_bid: native(100),
+            nft_addr: suite.nft.to_string(),
+            escrowless: None,
+            template: Some(String::from("buy_now")),
+        }, &[]).unwrap();
+        (token_id, attribute(&res, "listing_id"))
+    };
+
+    // below the lock share the highest bidder can be bought out and is refunded
+    let (token_id, listing_id) = place(&mut suite);
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    suite.execute(ALICE, ExecuteMsg::BuyNow { listing_id, price: native(1000) }, &coins(1000, DENOM)).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+
+    // a bid at half the buy-now price keeps the auction running to completion
+    let (_, listing_id) = place(&mut suite);
+    suite.bid_native(BOB, &listing_id, 500).unwrap();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() })
+        .unwrap();
+    assert_eq!(listing.buy_now_price, None);
+    let err = suite.execute(ALICE, ExecuteMsg::BuyNow { listing_id, price: native(1000) }, &coins(1000, DENOM)).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "buy-now is not available for this listing");
+}
+
+#[test]
+fn candle_auction_ends_at_random_height() {
+    let mut suite = setup();
+    let provider = suite.randomness_provider();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("candle"),
+        template: ListingTemplate {
+            duration_blocks: Some(1000),
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: Some(100),
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("candle")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+
+    // alice is outbid inside the closing window, her bid is held instead of refunded
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.app.update_block(|block| block.height += 950);
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 150);
+
+    // the effective end is only drawn after bidding closed
+    let request = ExecuteMsg::RequestCandleEnd { listing_id: listing_id.clone() };
+    suite.execute(ALICE, request.clone(), &[]).unwrap_err();
+    suite.app.update_block(|block| block.height += 51);
+    let err = suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "the effective end of the candle auction has not been drawn yet");
+    suite.execute(ALICE, request, &[]).unwrap();
+
+    // zero randomness ends the auction at the start of the window, before bob's bid
+    suite.app.execute_contract(provider, suite.auction.clone(), &ExecuteMsg::ReceiveRandomness {
+        job_id: listing_id.clone(),
+        randomness: Binary::from(vec![0u8; 32]),
+    }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 150);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+}
+
+#[test]
+fn candle_auctions_refund_the_lowest_held_bid_at_the_cap() {
+    let mut suite = setup();
+    let provider = suite.randomness_provider();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("candle"),
+        template: ListingTemplate {
+            duration_blocks: Some(1000),
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: Some(100),
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("candle")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+
+    // a bid outbid at its own height can never win and is refunded right away
+    suite.app.update_block(|block| block.height += 900);
+    suite.bid_native(ALICE, &listing_id, 100).unwrap();
+    suite.bid_native(BOB, &listing_id, 101).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE);
+
+    // every later bid of the closing window holds the one it outbids, at the cap the lowest
+    // held bid is refunded and bidding stays open
+    for round in 2..62 {
+        suite.app.update_block(|block| block.height += 1);
+        let bidder = if round % 2 == 0 { ALICE } else { BOB };
+        suite.bid_native(bidder, &listing_id, 100 + round).unwrap();
+    }
+    let held = |parity: u128| (11..61u128).filter(|round| round % 2 == parity).map(|round| 100 + round).sum::<u128>();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - held(0));
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - held(1) - 161);
+
+    // the drawn end falls on alice's bid 29 blocks into the window, every other bid goes back
+    suite.app.update_block(|block| block.height += 41);
+    suite.execute(ALICE, ExecuteMsg::RequestCandleEnd { listing_id: listing_id.clone() }, &[]).unwrap();
+    let mut randomness = vec![0u8; 32];
+    randomness[7] = 29;
+    suite.app.execute_contract(provider, suite.auction.clone(), &ExecuteMsg::ReceiveRandomness {
+        job_id: listing_id.clone(),
+        randomness: Binary::from(randomness),
+    }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 130);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+}
+
+#[test]
+fn raffle_draws_winner_and_refunds_losers() {
+    let mut suite = setup();
+    let provider = suite.randomness_provider();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::CreateRaffle {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+        ticket_price: native(10),
+        duration_blocks: 100,
+        refund_losers: true,
+    }, &[]).unwrap();
+    let raffle_id: u64 = attribute(&res, "create_raffle").parse().unwrap();
+
+    suite.execute(ALICE, ExecuteMsg::BuyTickets { raffle_id, count: 2 }, &coins(20, DENOM)).unwrap();
+    suite.execute(BOB, ExecuteMsg::BuyTickets { raffle_id, count: 3 }, &coins(30, DENOM)).unwrap();
+    suite.execute(BOB, ExecuteMsg::BuyTickets { raffle_id, count: 1 }, &coins(5, DENOM)).unwrap_err();
+
+    let draw = ExecuteMsg::DrawRaffle { raffle_id };
+    suite.execute(BOB, draw.clone(), &[]).unwrap_err();
+    suite.app.update_block(|block| block.height += 101);
+    suite.execute(BOB, draw, &[]).unwrap();
+
+    // ticket 0 belongs to alice, bob's tickets are refunded
+    suite.app.execute_contract(provider, suite.auction.clone(), &ExecuteMsg::ReceiveRandomness {
+        job_id: format!("RAFFLE.{}", raffle_id),
+        randomness: Binary::from(vec![0u8; 32]),
+    }, &[]).unwrap();
+
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 19);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 1);
+}
+
+#[test]
+fn undrawn_raffles_are_voided_after_the_timeout() {
+    let mut suite = setup();
+    let provider = suite.randomness_provider();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::CreateRaffle {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+        ticket_price: native(10),
+        duration_blocks: 100,
+        refund_losers: false,
+    }, &[]).unwrap();
+    let raffle_id: u64 = attribute(&res, "create_raffle").parse().unwrap();
+    suite.execute(ALICE, ExecuteMsg::BuyTickets { raffle_id, count: 2 }, &coins(20, DENOM)).unwrap();
+    suite.execute(BOB, ExecuteMsg::BuyTickets { raffle_id, count: 3 }, &coins(30, DENOM)).unwrap();
+    suite.execute(ALICE, ExecuteMsg::BuyTickets { raffle_id, count: 1 }, &coins(10, DENOM)).unwrap();
+
+    // the winner is requested once
+    suite.app.update_block(|block| block.height += 101);
+    let draw = ExecuteMsg::DrawRaffle { raffle_id };
+    suite.execute(BOB, draw.clone(), &[]).unwrap();
+    let err = suite.execute(BOB, draw, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "the winner of the raffle was already requested");
+
+    // the provider never answers, the raffle is voided once the timeout passed
+    let void = ExecuteMsg::VoidRaffle { raffle_id };
+    suite.app.update_block(|block| block.height += RAFFLE_DRAW_TIMEOUT_BLOCKS);
+    let err = suite.execute(BOB, void.clone(), &[]).unwrap_err();
+    assert!(err.root_cause().to_string().starts_with("the raffle can only be voided after height"));
+    suite.app.update_block(|block| block.height += 1);
+    suite.execute(BOB, void, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+
+    // a late winner finds no raffle to settle
+    suite.app.execute_contract(provider, suite.auction.clone(), &ExecuteMsg::ReceiveRandomness {
+        job_id: format!("RAFFLE.{}", raffle_id),
+        randomness: Binary::from(vec![0u8; 32]),
+    }, &[]).unwrap_err();
+}
+
+#[test]
+fn listings_indexed_by_collection() {
+    let mut suite = setup();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let first_listing = suite.place_listing(SELLER, &first, native(100));
+    let second_listing = suite.place_listing(SELLER, &second, native(200));
+
+    let query = |suite: &Suite, start_after: Option<Binary>| -> ListingsByCollectionResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ListingsByCollection {
+            collection: suite.nft.to_string(),
+            start_after,
+            limit: Some(1),
+        }).unwrap()
+    };
+
+    let page = query(&suite, None);
+    assert_eq!(page.listings.len(), 1);
+    assert_eq!(page.listings[0].listing_id, first_listing);
+    assert_eq!(page.listings[0].listing.token_id, first);
+    let page = query(&suite, page.next_cursor);
+    assert_eq!(page.listings[0].listing_id, second_listing);
+    assert!(query(&suite, page.next_cursor).listings.is_empty());
+
+    // cancelled listings leave the index
+    suite.execute(SELLER, ExecuteMsg::CancelListings { listing_ids: vec![first_listing] }, &[]).unwrap();
+    let page = query(&suite, None);
+    assert_eq!(page.listings.len(), 1);
+    assert_eq!(page.listings[0].listing_id, second_listing);
+}
+
+#[test]
+fn escrowless_approval_must_outlast_listing_window() {
+    let mut suite = setup();
+    let err = suite.execute(ALICE, ExecuteMsg::UpdateListingWindows {
+        max_aution_duration_blocks: Some(1_000),
+        approval_window_blocks: Some(500),
+    }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    suite.execute(OWNER, ExecuteMsg::UpdateListingWindows {
+        max_aution_duration_blocks: Some(0),
+        approval_window_blocks: None,
+    }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateListingWindows {
+        max_aution_duration_blocks: Some(1_000),
+        approval_window_blocks: Some(500),
+    }, &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    let height = suite.app.block_info().height;
+    let approve = |suite: &mut Suite, expires: Expiration| {
+        suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+            spender: suite.auction.to_string(),
+            token_id: token_id.clone(),
+            expires: Some(expires),
+        }, &[]).unwrap();
+    };
+    let place_listing = ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    };
+
+    // the approval must last through the auction and the approval window after it
+    approve(&mut suite, Expiration::AtHeight(height + 1_499));
+    let err = suite.execute(SELLER, place_listing.clone(), &[]).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("the approval of the contract must last until height {}", height + 1_500)
+    );
+
+    approve(&mut suite, Expiration::AtHeight(height + 1_500));
+    let res = suite.execute(SELLER, place_listing, &[]).unwrap();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: attribute(&res, "listing_id") })
+        .unwrap();
+    assert_eq!(listing.block_limit, height + 1_000);
+}
+
+#[test]
+fn minter_changes_are_audited() {
+    let mut suite = setup();
+    let height = suite.app.block_info().height;
+
+    let res = suite.execute(OWNER, ExecuteMsg::UpdateMinter {
+        minter: String::from(ALICE),
+        expires: Some(Expiration::AtHeight(height + 100)),
+    }, &[]).unwrap();
+    let event = res.events.iter().find(|event| event.ty == "wasm-minter_audit").unwrap();
+    assert!(event.attributes.iter().any(|attribute| attribute.key == "by" && attribute.value == OWNER));
+
+    let query = |suite: &Suite| -> MinterInfoResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::MinterInfo { minter: String::from(ALICE) }).unwrap()
+    };
+    let info = query(&suite).info.unwrap();
+    assert_eq!(info.granted_by, Some(Addr::unchecked(OWNER)));
+    assert_eq!(info.granted_height, Some(height));
+
+    // the trail outlives the whitelisting
+    suite.app.update_block(|block| block.height += 100);
+    suite.execute(BOB, ExecuteMsg::PruneExpiredMinters { limit: None }, &[]).unwrap();
+    let minter = query(&suite);
+    assert_eq!(minter.info, None);
+    let actions: Vec<_> = minter.audit.iter().map(|entry| (entry.action.clone(), entry.by.to_string())).collect();
+    assert_eq!(actions, vec![
+        (MinterAction::Update, String::from(OWNER)),
+        (MinterAction::Prune, String::from(BOB)),
+    ]);
+}
+
+#[test]
+fn minter_applications_are_curated() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateMinterCurator { curator: Some(String::from(BOB)) }, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::RemoveMinter { minter: String::from(ARTIST) }, &[]).unwrap();
+
+    let apply = ExecuteMsg::ApplyForMinter { metadata_uri: String::from("https://glassflow.art/portfolio") };
+    suite.execute(ARTIST, apply.clone(), &[]).unwrap();
+    suite.execute(ALICE, apply, &[]).unwrap();
+    let pending: MinterApplicationsResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::MinterApplications { start_after: None, limit: None })
+        .unwrap();
+    assert_eq!(pending.applications.len(), 2);
+
+    // applying grants nothing until approved
+    let approve = ExecuteMsg::ApproveMinter { applicant: String::from(ARTIST), expires: None, quota: Some(1) };
+    let err = suite.execute(ALICE, approve.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    suite.execute(BOB, approve, &[]).unwrap();
+    suite.execute(BOB, ExecuteMsg::RejectMinter { applicant: String::from(ALICE) }, &[]).unwrap();
+    let err = suite.execute(BOB, ExecuteMsg::RejectMinter { applicant: String::from(ALICE) }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "no pending minter application");
+
+    // the quota set at approval caps minting
+    suite.mint(SELLER);
+    let err = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("second nft"),
+        image_uri: None,
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "minting quota exhausted");
+
+    let minter: MinterInfoResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::MinterInfo { minter: String::from(ARTIST) })
+        .unwrap();
+    assert_eq!(minter.info.unwrap().granted_by, Some(Addr::unchecked(BOB)));
+}
+
+#[test]
+fn launchpad_lists_for_seller() {
+    let mut suite = setup();
+    let launchpad = "launchpad";
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let place_listing = ExecuteMsg::PlaceListingFor {
+        seller: String::from(SELLER),
+        params: PlaceListingParams {
+            id: token_id.clone(),
+            minimum_bid: native(100),
+            nft_addr: suite.nft.to_string(),
+            escrowless: None,
+            template: None,
+        },
+    };
+
+    // only registered launchpads list for others
+    let err = suite.execute(launchpad, place_listing.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    suite.execute(OWNER, ExecuteMsg::UpdateLaunchpad { launchpad: String::from(launchpad), registered: true }, &[]).unwrap();
+
+    // and only tokens their sellers let them operate
+    let err = suite.execute(launchpad, place_listing.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "sender is neither the owner nor an approved operator of the token");
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::ApproveAll {
+        operator: String::from(launchpad),
+        expires: None,
+    }, &[]).unwrap();
+
+    let res = suite.execute(launchpad, place_listing, &[]).unwrap();
+    assert_eq!(attribute(&res, "listed_by"), launchpad);
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: attribute(&res, "listing_id") })
+        .unwrap();
+    assert_eq!(listing.seller, SELLER);
+    assert_eq!(listing.listed_by, Some(Addr::unchecked(launchpad)));
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+}
+
+#[test]
+fn token_full_info_merges_listing_and_offers() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let query = |suite: &Suite| -> TokenFullInfoResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::TokenFullInfo {
+            contract_addr: suite.nft.to_string(),
+            token_id: token_id.clone(),
+        }).unwrap()
+    };
+
+    suite.execute(ALICE, ExecuteMsg::MakeOffer {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+        price: native(80),
+    }, &coins(80, DENOM)).unwrap();
+    let info = query(&suite);
+    assert_eq!(info.access.owner, SELLER);
+    assert_eq!(info.info.extension.name, Some(String::from("first nft")));
+    assert_eq!(info.listing, None);
+    assert_eq!(info.offers.len(), 1);
+    assert_eq!(info.offers[0].buyer, ALICE);
+
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(BOB, &listing_id, 150).unwrap();
+    let info = query(&suite);
+    assert_eq!(info.access.owner, suite.auction.to_string());
+    assert_eq!(info.listing_id, Some(listing_id));
+    assert_eq!(info.listing.unwrap().max_bidder, BOB);
+
+    suite.execute(ALICE, ExecuteMsg::CancelOffer { offer_id: info.offers[0].id }, &[]).unwrap();
+    assert!(query(&suite).offers.is_empty());
+}
+
+#[test]
+fn failed_nft_delivery_is_kept_for_retry() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+
+    let freeze = |suite: &mut Suite, freeze_transfers: bool| {
+        suite.app.execute_contract(
+            Addr::unchecked(ARTIST),
+            suite.nft.clone(),
+            &NftExecuteMsg::Freeze { freeze_transfers },
+            &[],
+        ).unwrap();
+    };
+    let dead_letters = |suite: &Suite| -> DeadLettersResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::DeadLetters { start_after: None, limit: None }).unwrap()
+    };
+
+    // the sale still pays out when the delivery fails
+    freeze(&mut suite, true);
+    suite.app.update_block(|block| block.height += 100_000);
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+
+    let letters = dead_letters(&suite).dead_letters;
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].listing_id, listing_id);
+
+    suite.execute(BOB, ExecuteMsg::RetryDeadLetter { id: letters[0].id }, &[]).unwrap();
+    assert_eq!(dead_letters(&suite).dead_letters[0].attempts, 2);
+
+    freeze(&mut suite, false);
+    suite.execute(BOB, ExecuteMsg::RetryDeadLetter { id: letters[0].id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert!(dead_letters(&suite).dead_letters.is_empty());
+}
+
+#[test]
+fn delivered_nfts_leave_no_reply_context_behind() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+
+    let querier = suite.app.wrap();
+    for id in 1..=10 {
+        assert_eq!(REPLIES.query(&querier, suite.auction.clone(), id).unwrap(), None);
+    }
+}
+
+#[test]
+fn third_party_settlement_after_grace_period() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateSettlementRules {
+        grace_blocks: 100,
+        reward_rate: Some(Decimal::percent(50)),
+    }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    let withdraw = ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() };
+    let block_limit = suite.app.block_info().height + 50_000;
+
+    // the winner settles once the auction ended, others after the grace period
+    let err = suite.execute(ALICE, withdraw.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Auction Not Ended Yet");
+    suite.app.update_block(|block| block.height = block_limit + 100);
+    let err = suite.execute(BOB, withdraw.clone(), &[]).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("listing can be settled by others from height {}", block_limit + 101)
+    );
+
+    // the third party settler collects half of the platform fee
+    suite.app.update_block(|block| block.height += 1);
+    suite.execute(BOB, withdraw, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE + 5);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 5);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+}
+
+#[test]
+fn export_dumps_raw_storage_pages() {
+    let mut suite = setup();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let first_listing = suite.place_listing(SELLER, &first, native(100));
+    let second_listing = suite.place_listing(SELLER, &second, native(100));
+
+    let export = |suite: &Suite, start_after: Option<Binary>| -> ExportResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Export {
+            section: ExportSection::Listings,
+            start_after,
+            limit: Some(1),
+        }).unwrap()
+    };
+
+    let page = export(&suite, None);
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.entries[0].key, Binary::from(first_listing.as_bytes()));
+    let listing: Listing = from_slice(&page.entries[0].value).unwrap();
+    assert_eq!(listing.token_id, first);
+
+    let page = export(&suite, Some(page.entries[0].key.clone()));
+    assert_eq!(page.entries[0].key, Binary::from(second_listing.as_bytes()));
+    assert!(export(&suite, Some(page.entries[0].key.clone())).entries.is_empty());
+}
+
+#[test]
+fn bids_rewrite_only_the_bid_state() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    let export = |suite: &Suite, section: ExportSection| -> Vec<ExportEntry> {
+        let page: ExportResponse = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Export {
+            section,
+            start_after: None,
+            limit: None,
+        }).unwrap();
+        page.entries
+    };
+
+    let listing_before = export(&suite, ExportSection::Listings);
+    assert!(export(&suite, ExportSection::ListingBids).is_empty());
+
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+
+    // the stored listing is untouched, the bid lives in its own compact entry
+    assert_eq!(export(&suite, ExportSection::Listings), listing_before);
+    let bids = export(&suite, ExportSection::ListingBids);
+    assert_eq!(bids.len(), 1);
+    let bid_state: BidState = from_slice(&bids[0].value).unwrap();
+    assert_eq!(bid_state.max_bid, native(150));
+    assert_eq!(bid_state.max_bidder, Addr::unchecked(ALICE));
+
+    let listing_bytes = listing_before[0].key.len() + listing_before[0].value.len();
+    let bid_bytes = bids[0].key.len() + bids[0].value.len();
+    assert!(bid_bytes * 2 < listing_bytes);
+
+    let resolved: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id }).unwrap();
+    assert_eq!(resolved.max_bid, native(150));
+}
+
+#[test]
+fn config_changes_are_kept_in_history() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateFeeManager { fee_manager: Some(String::from(ALICE)) }, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::UpdateSettlementRules { grace_blocks: 100, reward_rate: None }, &[]).unwrap();
+    // failed updates leave no trace
+    suite.execute(ALICE, ExecuteMsg::UpdateFeeManager { fee_manager: None }, &[]).unwrap_err();
+
+    let history = |suite: &Suite, start_after: Option<Binary>, limit: Option<u32>| -> ConfigHistoryResponse {
+        suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::ConfigHistory { start_after, limit }).unwrap()
+    };
+
+    let changes = history(&suite, None, None).changes;
+    // instantiation and the fee config of the setup come first
+    assert_eq!(changes.len(), 4);
+    assert!(changes[0].old.is_none());
+    assert_eq!(changes[2].by, Addr::unchecked(OWNER));
+    assert_eq!(changes[2].old.as_ref().unwrap().fee_manager, None);
+    assert_eq!(changes[2].new.fee_manager, Some(String::from(ALICE)));
+    assert_eq!(changes[3].old.as_ref().unwrap().settlement_grace_blocks, 0);
+    assert_eq!(changes[3].new.settlement_grace_blocks, 100);
+
+    let page = history(&suite, None, Some(3));
+    assert_eq!(page.changes, changes[..3].to_vec());
+    let page = history(&suite, page.next_cursor, Some(3));
+    assert_eq!(page.changes, vec![changes[3].clone()]);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn rate_limits_throttle_listings_and_bids() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateRateLimits {
+        rate_limits: Some(RateLimits {
+            max_listings: Some(1),
+            listing_window_blocks: 100,
+            max_bids_per_block: Some(1),
+        }),
+    }, &[]).unwrap();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &first, native(100));
+
+    let place_second = ExecuteMsg::PlaceListing {
+        id: second.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    };
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: second.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    suite.execute(SELLER, place_second.clone(), &[]).unwrap_err();
+
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.bid_native(ALICE, &listing_id, 160).unwrap_err();
+    // other addresses keep their own counters
+    suite.bid_native(BOB, &listing_id, 170).unwrap();
+
+    suite.app.update_block(|block| block.height += 1);
+    suite.bid_native(ALICE, &listing_id, 180).unwrap();
+
+    suite.app.update_block(|block| block.height += 99);
+    suite.execute(SELLER, place_second, &[]).unwrap();
+}
+
+#[test]
+fn active_listings_are_capped_per_seller() {
+    let mut suite = setup();
+    suite.execute(ALICE, ExecuteMsg::UpdateMaxActiveListings { max_active_listings: Some(1) }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateMaxActiveListings { max_active_listings: Some(1) }, &[]).unwrap();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &first, native(100));
+
+    let place_second = ExecuteMsg::PlaceListing {
+        id: second.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    };
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: second.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let err = suite.execute(SELLER, place_second.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "at most 1 active listings per address");
+
+    let active_listings = |suite: &Suite| {
+        let stats: SellerStatsResponse = suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::SellerStats { seller: String::from(SELLER) })
+            .unwrap();
+        stats.active_listings
+    };
+    assert_eq!(active_listings(&suite), 1);
+
+    // settling frees the slot
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(active_listings(&suite), 0);
+    suite.execute(SELLER, place_second, &[]).unwrap();
+    assert_eq!(active_listings(&suite), 1);
+}
+
+#[test]
+fn nfts_sent_directly_are_quarantined_for_their_sender() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::SendNft {
+        contract: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        msg: Binary::default(),
+    }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+
+    let quarantined: Option<QuarantinedNft> = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Quarantined {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+    }).unwrap();
+    assert_eq!(quarantined.unwrap().sender, Addr::unchecked(SELLER));
+
+    let reclaim = ExecuteMsg::ReclaimQuarantined { nft_addr: suite.nft.to_string(), token_id: token_id.clone() };
+    suite.execute(ALICE, reclaim.clone(), &[]).unwrap_err();
+    suite.execute(SELLER, reclaim.clone(), &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+    suite.execute(SELLER, reclaim, &[]).unwrap_err();
+}
+
+#[test]
+fn tokens_minted_into_custody_list_without_a_transfer() {
+    let mut suite = setup();
+    let mint = GFMintMsg {
+        owner: String::from("Seller"),
+        name: String::from("custodied nft"),
+        image_uri: None,
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: true,
+    };
+    // owners must be valid addresses
+    suite.execute(ARTIST, ExecuteMsg::Mint(mint.clone()), &[]).unwrap_err();
+
+    let mint = GFMintMsg { owner: String::from(SELLER), ..mint };
+    let listed = attribute(&suite.execute(ARTIST, ExecuteMsg::Mint(mint.clone()), &[]).unwrap(), "token_id");
+    let released = attribute(&suite.execute(ARTIST, ExecuteMsg::Mint(mint), &[]).unwrap(), "token_id");
+    assert_eq!(suite.nft_owner(&listed), suite.auction.to_string());
+    let custody: Option<CustodiedNft> = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Custody {
+        nft_addr: suite.nft.to_string(),
+        token_id: listed.clone(),
+    }).unwrap();
+    assert_eq!(custody.unwrap().owner, Addr::unchecked(SELLER));
+
+    // only the owner lists a custodied token, no approval needed
+    let place = ExecuteMsg::PlaceListing {
+        id: listed.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: None,
+    };
+    let err = suite.execute(ALICE, place.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "sender is neither the owner nor an approved operator of the token");
+    let listing_id = attribute(&suite.execute(SELLER, place, &[]).unwrap(), "listing_id");
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&listed), ALICE);
+
+    let release = ExecuteMsg::ReleaseCustody { nft_addr: suite.nft.to_string(), token_id: released.clone() };
+    suite.execute(ALICE, release.clone(), &[]).unwrap_err();
+    suite.execute(SELLER, release.clone(), &[]).unwrap();
+    assert_eq!(suite.nft_owner(&released), SELLER);
+    suite.execute(SELLER, release, &[]).unwrap_err();
+}
+
+#[test]
+fn relayers_place_listings_signed_by_sellers() {
+    let mut suite = setup();
+    let signing_key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(true).as_bytes().to_vec());
+    let seller = pubkey_address("juno", &pubkey).unwrap();
+
+    let token_id = suite.mint(&seller);
+    suite.app.execute_contract(Addr::unchecked(&seller), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+
+    let intent = ListingIntent {
+        seller: seller.clone(),
+        pubkey: pubkey.clone(),
+        params: PlaceListingParams {
+            id: token_id.clone(),
+            minimum_bid: native(100),
+            nft_addr: suite.nft.to_string(),
+            escrowless: None,
+            template: None,
+        },
+        nonce: 0,
+        expires_at: suite.app.block_info().height + 10,
+    };
+    let sign = |suite: &Suite, intent: &ListingIntent| -> Binary {
+        let digest = intent_digest(&suite.app.block_info().chain_id, &suite.auction, intent).unwrap();
+        let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+        Binary::from(signature.to_bytes().to_vec())
+    };
+    let signature = sign(&suite, &intent);
+
+    // the key must belong to the seller's account
+    let other_key = SigningKey::from_bytes(&[8u8; 32].into()).unwrap();
+    let mut impostor = intent.clone();
+    impostor.pubkey = Binary::from(other_key.verifying_key().to_encoded_point(true).as_bytes().to_vec());
+    let err = suite.execute(ALICE, ExecuteMsg::PlaceListingWithSig { intent: impostor, signature: signature.clone() }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "public key does not belong to the intent signer");
+
+    // the signature covers the whole intent
+    let mut tampered = intent.clone();
+    tampered.params.minimum_bid = native(1);
+    suite.execute(ALICE, ExecuteMsg::PlaceListingWithSig { intent: tampered, signature: signature.clone() }, &[]).unwrap_err();
+
+    let res = suite.execute(ALICE, ExecuteMsg::PlaceListingWithSig { intent: intent.clone(), signature: signature.clone() }, &[]).unwrap();
+    assert_eq!(attribute(&res, "relayer"), ALICE);
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: attribute(&res, "listing_id") }).unwrap();
+    assert_eq!(listing.seller, Addr::unchecked(&seller));
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+    let nonce: u64 = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::IntentNonce { signer: seller }).unwrap();
+    assert_eq!(nonce, 1);
+
+    // intents are single use
+    suite.execute(ALICE, ExecuteMsg::PlaceListingWithSig { intent, signature }, &[]).unwrap_err();
+}
+
+#[test]
+fn relayed_bids_are_credited_to_the_signer() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    let signing_key = SigningKey::from_bytes(&[9u8; 32].into()).unwrap();
+    let pubkey = Binary::from(signing_key.verifying_key().to_encoded_point(true).as_bytes().to_vec());
+    let bidder = pubkey_address("juno", &pubkey).unwrap();
+
+    let intent = BidIntent {
+        bidder: bidder.clone(),
+        pubkey,
+        listing_id: listing_id.clone(),
+        bid_price: native(150),
+        nonce: 0,
+        expires_at: suite.app.block_info().height + 10,
+    };
+    let digest = intent_digest(&suite.app.block_info().chain_id, &suite.auction, &intent).unwrap();
+    let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+    let bid = ExecuteMsg::BidOnBehalf { intent, signature: Binary::from(signature.to_bytes().to_vec()) };
+
+    // the relayer must pay the signed bid price
+    suite.execute(BOB, bid.clone(), &coins(100, DENOM)).unwrap_err();
+
+    let bob_before = suite.native_balance(BOB);
+    let res = suite.execute(BOB, bid, &coins(150, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "relayer"), BOB);
+    assert_eq!(suite.native_balance(BOB), bob_before - 150);
+
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap();
+    assert_eq!(listing.max_bidder, Addr::unchecked(&bidder));
+
+    // outbid refunds go to the signer
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    assert_eq!(suite.native_balance(&bidder), 150);
+}
+
+#[test]
+fn collection_levy_is_paid_at_settlement() {
+    let mut suite = setup();
+    let set_levy = ExecuteMsg::SetCollectionLevy {
+        collection: suite.nft.to_string(),
+        rate: Some(Decimal::percent(10)),
+        recipient: Some(String::from(BOB)),
+    };
+    // only the collection owner sets the levy
+    suite.execute(SELLER, set_levy.clone(), &[]).unwrap_err();
+    suite.execute(ARTIST, set_levy, &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    // listings keep the levy they were placed with
+    suite.execute(ARTIST, ExecuteMsg::SetCollectionLevy { collection: suite.nft.to_string(), rate: None, recipient: None }, &[]).unwrap();
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+
+    let preview: SettlementPreviewResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::SettlementPreview { listing_id: listing_id.clone() }).unwrap();
+    assert_eq!(preview.levy.unwrap().asset, native(20));
+
+    let res = suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(attribute(&res, "levy"), "20");
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE + 20);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 170);
+}
+
+#[test]
+fn mints_follow_the_collection_variant() {
+    let mut suite = setup();
+    let variant: Cw721Variant = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::CollectionVariant { collection: suite.nft.to_string() }).unwrap();
+    assert_eq!(variant, Cw721Variant::OnChainMetadata);
+
+    // only the collection owner picks the variant
+    let collection = suite.nft.to_string();
+    let set_variant = |variant| ExecuteMsg::SetCollectionVariant { collection: collection.clone(), variant };
+    suite.execute(SELLER, set_variant(Cw721Variant::Soulbound), &[]).unwrap_err();
+    suite.execute(ARTIST, set_variant(Cw721Variant::Soulbound), &[]).unwrap();
+
+    // soulbound tokens are minted non transferable and can not be listed
+    let token_id = suite.mint(SELLER);
+    let info: NftInfoResponse<Metadata> = suite.app.wrap()
+        .query_wasm_smart(&suite.nft, &Cw721QueryMsg::NftInfo { token_id: token_id.clone() }).unwrap();
+    assert_eq!(info.extension.transferable, Some(false));
+    let err = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id,
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "nft is not transferable");
+
+    // standard collections only take the token uri
+    suite.execute(ARTIST, set_variant(Cw721Variant::Standard), &[]).unwrap();
+    let err = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("plain nft"),
+        image_uri: Some(String::from("https://glassflow.art/2.json")),
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![Royalty { address: String::from(ARTIST), royalty_rate: Decimal::percent(10) }],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "standard cw721 collections can not store royalties or translations");
+
+    let res = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("plain nft"),
+        image_uri: Some(String::from("https://glassflow.art/2.json")),
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap();
+    assert_eq!(attribute(&res, "variant"), "standard");
+    let token_id = attribute(&res, "token_id");
+    let info: NftInfoResponse<Metadata> = suite.app.wrap()
+        .query_wasm_smart(&suite.nft, &Cw721QueryMsg::NftInfo { token_id: token_id.clone() }).unwrap();
+    assert_eq!(info.token_uri, Some(String::from("https://glassflow.art/2.json")));
+    assert_eq!(info.extension.name, None);
+
+    // and their tokens still sell, without royalties
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+}
+
+#[test]
+fn creator_overview_bundles_dashboard_data() {
+    let mut suite = setup();
+    let sold = suite.mint(ARTIST);
+    let listed = suite.mint(ARTIST);
+    let sold_listing = suite.place_listing(ARTIST, &sold, native(100));
+    let listed_listing = suite.place_listing(ARTIST, &listed, native(100));
+    suite.bid_native(ALICE, &sold_listing, 200).unwrap();
+    suite.execute(ARTIST, ExecuteMsg::WithdrawListing { listing_id: sold_listing }, &[]).unwrap();
+
+    let overview: CreatorOverviewResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::CreatorOverview { address: String::from(ARTIST) }).unwrap();
+    assert_eq!(overview.collections, vec![suite.nft.clone()]);
+    assert_eq!(overview.active_listings.len(), 1);
+    assert_eq!(overview.active_listings[0].listing_id, listed_listing);
+    assert_eq!(overview.sales.len(), 1);
+    assert_eq!(overview.sales[0].volume, Uint128::new(200));
+    assert!(overview.unclaimed_payouts.is_empty());
+    assert!(overview.minter.is_some());
+}
+
+#[test]
+fn deferred_royalties_accrue_until_claimed() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateRoyaltyPayout { deferred: true }, &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+
+    // the secondary sale credits the royalty instead of sending it
+    let listing_id = suite.place_listing(ALICE, &token_id, native(100));
+    suite.bid_native(BOB, &listing_id, 1000).unwrap();
+    suite.execute(ALICE, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(suite.native_balance(ARTIST), 0);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 200 + 850);
+
+    let receipt: Option<SettlementReceipt> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::SettlementReceipt { listing_id }).unwrap();
+    let royalty = receipt.unwrap().payouts.into_iter().find(|payout| payout.recipient == Addr::unchecked(ARTIST)).unwrap();
+    assert_eq!(royalty.status, PayoutStatus::Accrued);
+
+    let accrued: AccruedRoyaltiesResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::AccruedRoyalties { address: String::from(ARTIST) }).unwrap();
+    assert_eq!(accrued.royalties, vec![native(100)]);
+
+    suite.execute(ARTIST, ExecuteMsg::ClaimRoyalties {}, &[]).unwrap();
+    assert_eq!(suite.native_balance(ARTIST), 100);
+    suite.execute(ARTIST, ExecuteMsg::ClaimRoyalties {}, &[]).unwrap_err();
+
+    let overview: CreatorOverviewResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::CreatorOverview { address: String::from(ARTIST) }).unwrap();
+    assert!(overview.accrued_royalties.is_empty());
+}
+
+#[test]
+fn listings_indexed_by_tag() {
+    let mut suite = setup();
+    let painting = suite.mint(SELLER);
+    let sculpture = suite.mint(SELLER);
+    let painting_listing = suite.place_listing(SELLER, &painting, native(100));
+    let sculpture_listing = suite.place_listing(SELLER, &sculpture, native(100));
+
+    let set_tags = |listing_id: &str, tags: &[&str]| ExecuteMsg::SetListingTags {
+        listing_id: listing_id.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+    };
+    suite.execute(ALICE, set_tags(&painting_listing, &["painting"]), &[]).unwrap_err();
+    suite.execute(SELLER, set_tags(&painting_listing, &["Painting"]), &[]).unwrap_err();
+    suite.execute(SELLER, set_tags(&painting_listing, &["painting", "oil"]), &[]).unwrap();
+    suite.execute(SELLER, set_tags(&sculpture_listing, &["sculpture", "oil"]), &[]).unwrap();
+
+    let by_tag = |suite: &Suite, tag: &str| -> Vec<String> {
+        let res: ListingsByTagResponse = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ListingsByTag {
+            tag: tag.to_string(),
+            start_after: None,
+            limit: None,
+        }).unwrap();
+        res.listings.into_iter().map(|listing| listing.listing_id).collect()
+    };
+    assert_eq!(by_tag(&suite, "painting"), vec![painting_listing.clone()]);
+    assert_eq!(by_tag(&suite, "oil").len(), 2);
+
+    // retagging and settling drop the old entries
+    suite.execute(SELLER, set_tags(&sculpture_listing, &["sculpture"]), &[]).unwrap();
+    assert_eq!(by_tag(&suite, "oil"), vec![painting_listing.clone()]);
+    suite.bid_native(ALICE, &painting_listing, 200).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: painting_listing }, &[]).unwrap();
+    assert!(by_tag(&suite, "painting").is_empty());
+    assert_eq!(by_tag(&suite, "sculpture"), vec![sculpture_listing]);
+}
+
+#[test]
+fn curators_return_unsold_listings_after_the_grace_period() {
+    let mut suite = setup();
+    suite.execute(OWNER, ExecuteMsg::UpdateListingCurator { curator: Some(String::from(BOB)) }, &[]).unwrap();
+    suite.execute(BOB, ExecuteMsg::UpdateUnsoldGrace { grace_blocks: 100 }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateUnsoldGrace { grace_blocks: 100 }, &[]).unwrap();
+    let unsold = suite.mint(SELLER);
+    let sold = suite.mint(SELLER);
+    let unsold_listing = suite.place_listing(SELLER, &unsold, native(100));
+    let sold_listing = suite.place_listing(SELLER, &sold, native(100));
+    suite.bid_native(ALICE, &sold_listing, 150).unwrap();
+
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: unsold_listing.clone() })
+        .unwrap();
+    let return_unsold = |listing_id: &str| ExecuteMsg::ReturnUnsold { listing_ids: vec![listing_id.to_string()] };
+
+    let block_limit = listing.block_limit;
+    suite.app.update_block(|block| block.height = block_limit + 100);
+    let err = suite.execute(BOB, return_unsold(&unsold_listing), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("listing can be settled by others from height {}", block_limit + 101));
+
+    suite.app.update_block(|block| block.height += 1);
+    suite.execute(ALICE, return_unsold(&unsold_listing), &[]).unwrap_err();
+    suite.execute(BOB, return_unsold(&sold_listing), &[]).unwrap_err();
+    let res = suite.execute(BOB, return_unsold(&unsold_listing), &[]).unwrap();
+    assert_eq!(attribute(&res, "cleanup_by"), BOB);
+    assert_eq!(attribute(&res, "return_unsold"), unsold_listing);
+    assert_eq!(suite.nft_owner(&unsold), SELLER);
+}
+
+#[test]
+fn winners_and_sellers_claim_their_legs_separately() {
+    let mut suite = setup();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let first_listing = suite.place_listing(SELLER, &first, native(100));
+    let second_listing = suite.place_listing(SELLER, &second, native(100));
+    suite.bid_native(ALICE, &first_listing, 150).unwrap();
+    suite.bid_native(BOB, &second_listing, 150).unwrap();
+
+    let claim_item = |listing_id: &str| ExecuteMsg::ClaimItem { listing_id: listing_id.to_string() };
+    let claim_proceeds = |listing_id: &str| ExecuteMsg::ClaimProceeds { listing_id: listing_id.to_string() };
+    suite.execute(ALICE, claim_item(&first_listing), &[]).unwrap_err();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: second_listing.clone() })
+        .unwrap();
+    let block_limit = listing.block_limit;
+    suite.app.update_block(|block| block.height = block_limit + 1);
+
+    // the winner pulls the nft, the proceeds wait for the seller
+    let seller_balance = suite.native_balance(SELLER);
+    suite.execute(BOB, claim_item(&first_listing), &[]).unwrap_err();
+    let res = suite.execute(ALICE, claim_item(&first_listing), &[]).unwrap();
+    assert_eq!(attribute(&res, "claim_item"), first_listing);
+    assert_eq!(suite.nft_owner(&first), ALICE);
+    assert_eq!(suite.native_balance(SELLER), seller_balance);
+    suite.execute(ALICE, claim_item(&first_listing), &[]).unwrap_err();
+    suite.execute(ALICE, claim_proceeds(&first_listing), &[]).unwrap_err();
+    suite.execute(SELLER, claim_proceeds(&first_listing), &[]).unwrap();
+    let first_proceeds = suite.native_balance(SELLER) - seller_balance;
+    assert!(first_proceeds > 0);
+    suite.execute(SELLER, claim_proceeds(&first_listing), &[]).unwrap_err();
+
+    // the seller pulls the proceeds, the nft waits for the winner
+    suite.execute(SELLER, claim_proceeds(&second_listing), &[]).unwrap();
+    assert_eq!(suite.native_balance(SELLER) - seller_balance, first_proceeds * 2);
+    assert_eq!(suite.nft_owner(&second), suite.auction.to_string());
+    suite.execute(ALICE, claim_item(&second_listing), &[]).unwrap_err();
+    suite.execute(BOB, claim_item(&second_listing), &[]).unwrap();
+    assert_eq!(suite.nft_owner(&second), BOB);
+    suite.execute(BOB, claim_item(&second_listing), &[]).unwrap_err();
+}
+
+#[test]
+fn sales_are_listed_by_settlement_height() {
+    let mut suite = setup();
+    let tokens: Vec<String> = (0..3).map(|_| suite.mint(SELLER)).collect();
+    let listings: Vec<String> = tokens.iter().map(|token| suite.place_listing(SELLER, token, native(100))).collect();
+    suite.bid_native(ALICE, &listings[0], 110).unwrap();
+    suite.bid_native(BOB, &listings[1], 120).unwrap();
+    suite.bid_native(ALICE, &listings[2], 130).unwrap();
+
+    let mut heights = vec![];
+    for listing_id in listings.iter() {
+        suite.app.update_block(|block| block.height += 10);
+        heights.push(suite.app.block_info().height);
+        suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    }
+
+    let sales = |suite: &Suite, start_height: u64, end_height: u64, start_after: Option<Binary>| {
+        let res: SalesResponse = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Sales {
+            start_height,
+            end_height,
+            start_after,
+            limit: Some(1),
+        }).unwrap();
+        let sales = res.sales.into_iter().map(|sale| (sale.winner.to_string(), sale.price.amount.u128())).collect::<Vec<_>>();
+        (sales, res.next_cursor)
+    };
+    let (page, next_cursor) = sales(&suite, heights[1], heights[2], None);
+    assert_eq!(page, vec![(String::from(BOB), 120)]);
+    let (page, next_cursor) = sales(&suite, heights[1], heights[2], next_cursor);
+    assert_eq!(page, vec![(String::from(ALICE), 130)]);
+    assert!(sales(&suite, heights[1], heights[2], next_cursor).0.is_empty());
+    assert!(sales(&suite, heights[2] + 1, heights[2] + 100, None).0.is_empty());
+}
+
+#[test]
+fn royalties_are_capped_by_the_platform_maximum() {
+    let mut suite = setup();
+    let update = |max_royalty_rate| ExecuteMsg::UpdateMaxRoyaltyRate { max_royalty_rate };
+    suite.execute(ARTIST, update(Some(Decimal::percent(5))), &[]).unwrap_err();
+    suite.execute(OWNER, update(Some(Decimal::percent(101))), &[]).unwrap_err();
+    suite.execute(OWNER, update(Some(Decimal::percent(5))), &[]).unwrap();
+
+    // the test mints carry a 10% royalty
+    let err = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("capped nft"),
+        image_uri: None,
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![Royalty { address: String::from(ARTIST), royalty_rate: Decimal::percent(10) }],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "sum of royalty rates is larger than the maximum of 0.05");
+
+    suite.execute(OWNER, update(Some(Decimal::percent(10))), &[]).unwrap();
+    let token_id = suite.mint(SELLER);
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+}
+
+#[test]
+fn amounts_convert_at_the_oracle_rate() {
+    let mut suite = setup();
+    let convert = |suite: &Suite, from_denom: &str, amount: u128| -> StdResult<ConvertAmountResponse> {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ConvertAmount {
+            from_denom: from_denom.to_string(),
+            to_denom: String::from("uusd"),
+            amount: Uint128::new(amount),
+        })
+    };
+    convert(&suite, DENOM, 101).unwrap_err();
+
+    let code = suite.app.store_code(oracle_contract());
+    let oracle = suite.app
+        .instantiate_contract(code, Addr::unchecked(OWNER), &Empty {}, &[], "oracle", None)
+        .unwrap();
+    suite.execute(ALICE, ExecuteMsg::UpdatePriceOracle { price_oracle: Some(oracle.to_string()) }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdatePriceOracle { price_oracle: Some(oracle.to_string()) }, &[]).unwrap();
+
+    // converted amounts are rounded down
+    let res = convert(&suite, DENOM, 101).unwrap();
+    assert_eq!(res.amount, Uint128::new(252));
+    assert_eq!(res.rate, Decimal::permille(2_500));
+    convert(&suite, "uatom", 101).unwrap_err();
+    assert_eq!(convert(&suite, "uusd", 101).unwrap().amount, Uint128::new(101));
+}
+
+#[test]
+fn broken_escrows_are_reported_before_invalidation() {
+    let mut suite = setup();
+    let escrowed = suite.mint(SELLER);
+    let escrowless = suite.mint(SELLER);
+    let escrowed_listing = suite.place_listing(SELLER, &escrowed, native(100));
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: escrowless.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: escrowless.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    }, &[]).unwrap();
+    let escrowless_listing = attribute(&res, "listing_id");
+
+    let verify = |suite: &Suite, listing_id: &str| -> VerifyEscrowResponse {
+        suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::VerifyEscrow { listing_id: listing_id.to_string() })
+            .unwrap()
+    };
+    assert_eq!(verify(&suite, &escrowed_listing), VerifyEscrowResponse {
+        escrowless: false,
+        owner: Some(suite.auction.clone()),
+        contract_approved: false,
+        intact: true,
+    });
+    assert_eq!(verify(&suite, &escrowless_listing), VerifyEscrowResponse {
+        escrowless: true,
+        owner: Some(Addr::unchecked(SELLER)),
+        contract_approved: true,
+        intact: true,
+    });
+    let invalidate = |listing_id: &str| ExecuteMsg::InvalidateListing { listing_id: listing_id.to_string() };
+    suite.execute(BOB, invalidate(&escrowless_listing), &[]).unwrap_err();
+
+    // the seller revoking the approval breaks the escrowless listing
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Revoke {
+        spender: suite.auction.to_string(),
+        token_id: escrowless.clone(),
+    }, &[]).unwrap();
+    let report = verify(&suite, &escrowless_listing);
+    assert!(!report.contract_approved);
+    assert!(!report.intact);
+    suite.execute(BOB, invalidate(&escrowless_listing), &[]).unwrap();
+    suite.app.wrap()
+        .query_wasm_smart::<VerifyEscrowResponse>(&suite.auction, &QueryMsg::VerifyEscrow { listing_id: escrowless_listing })
+        .unwrap_err();
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn listings_are_inspected_through_raw_queries() {
+    use cw_auction::testing::{advance_blocks, query_listing};
+
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.app.update_block(|block| advance_blocks(block, 10));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+
+    let listing = query_listing(&suite.app.wrap(), &suite.auction, &listing_id).unwrap().unwrap();
+    assert_eq!(listing.max_bidder, Addr::unchecked(ALICE));
+    assert_eq!(listing.max_bid_height, suite.app.block_info().height);
+    assert_eq!(query_listing(&suite.app.wrap(), &suite.auction, "AUCTION.9").unwrap(), None);
+}
+
+#[test]
+fn instantiation_bootstraps_the_configuration() {
+    let mut app = App::default();
+    let auction_code = app.store_code(auction_contract());
+    let denom = DenomInfo {
+        denom: String::from(DENOM),
+        display: String::from("JUNO"),
+        decimals: 6,
+        min_bid_unit: Uint128::new(10),
+        tick_size: None,
+    };
+    let msg = InstantiateMsg {
+        owner: Some(String::from(OWNER)),
+        fee_tiers: vec![FeeTier { min_volume: Uint128::zero(), fee_rate: Decimal::percent(2) }],
+        fee_collector: Some(String::from(FEE_COLLECTOR)),
+        primary_fee_rate: None,
+        accepted_denoms: vec![denom.clone()],
+        minters: vec![InitialMinter { minter: String::from(ARTIST), expires: None }],
+    };
+
+    // a deployer other than the owner configures everything in one go
+    let auction = app
+        .instantiate_contract(auction_code, Addr::unchecked("dao"), &msg, &[], "auction", None)
+        .unwrap();
+
+    let config: Config = app.wrap().query_wasm_smart(&auction, &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.owner, OWNER);
+    assert_eq!(config.fee_tiers, msg.fee_tiers);
+    assert_eq!(config.fee_collector, Some(String::from(FEE_COLLECTOR)));
+
+    let denoms: AcceptedDenomsResponse = app.wrap()
+        .query_wasm_smart(&auction, &QueryMsg::AcceptedDenoms { start_after: None, limit: None })
+        .unwrap();
+    assert_eq!(denoms.denoms, vec![denom]);
+
+    let minter: MinterInfoResponse = app.wrap()
+        .query_wasm_smart(&auction, &QueryMsg::MinterInfo { minter: String::from(ARTIST) })
+        .unwrap();
+    assert_eq!(minter.info.unwrap().granted_by, Some(Addr::unchecked(OWNER)));
+    assert_eq!(minter.audit.len(), 1);
+
+    // invalid settings fail the instantiation like their update messages would
+    let invalid = InstantiateMsg {
+        fee_tiers: vec![FeeTier { min_volume: Uint128::zero(), fee_rate: Decimal::percent(101) }],
+        ..InstantiateMsg::default()
+    };
+    let err = app
+        .instantiate_contract(auction_code, Addr::unchecked("dao"), &invalid, &[], "auction", None)
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "fee tiers must be ascending by volume with rates not larger than 1");
+}
+
+#[test]
+fn collection_owners_pause_their_collection() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listed_token = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &listed_token, native(100));
+
+    // only the collection owner pauses
+    let collection = suite.nft.to_string();
+    let pause = |paused| ExecuteMsg::SetCollectionPaused { collection: collection.clone(), paused };
+    suite.execute(SELLER, pause(true), &[]).unwrap_err();
+    suite.execute(ARTIST, pause(true), &[]).unwrap();
+
+    let query = |suite: &Suite| -> Option<CollectionPause> {
+        suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::CollectionPause { collection: suite.nft.to_string() })
+            .unwrap()
+    };
+    assert_eq!(query(&suite).unwrap().paused_by, Addr::unchecked(ARTIST));
+
+    // new listings and mints are refused, running listings keep taking bids
+    let err = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("collection {} is paused by its owner", suite.nft));
+    let err = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("paused nft"),
+        image_uri: Some(String::from("https://glassflow.art/2.png")),
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("collection {} is paused by its owner", suite.nft));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+
+    // resuming opens the collection again
+    suite.execute(ARTIST, pause(false), &[]).unwrap();
+    assert_eq!(query(&suite), None);
+    suite.place_listing(SELLER, &token_id, native(100));
+}
+
+#[test]
+fn sunset_returns_nfts_and_refunds_bids() {
+    let mut suite = setup();
+    let listed_token = suite.mint(SELLER);
+    let offered_token = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &listed_token, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(BOB, ExecuteMsg::MakeOffer {
+        nft_addr: suite.nft.to_string(),
+        token_id: offered_token.clone(),
+        price: native(80),
+    }, &coins(80, DENOM)).unwrap();
+
+    // only the owner sunsets, and only once
+    suite.execute(BOB, ExecuteMsg::SunsetCrank { limit: None }, &[]).unwrap_err();
+    suite.execute(SELLER, ExecuteMsg::BeginSunset {}, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::BeginSunset {}, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::BeginSunset {}, &[]).unwrap_err();
+
+    // nothing new is taken
+    let err = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: offered_token.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: Some(true),
+        template: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "the marketplace is being sunset, no new listings or offers are taken");
+    suite.execute(ALICE, ExecuteMsg::MakeOffer {
+        nft_addr: suite.nft.to_string(),
+        token_id: offered_token.clone(),
+        price: native(80),
+    }, &coins(80, DENOM)).unwrap_err();
+
+    // running auctions finish first
+    let err = suite.execute(BOB, ExecuteMsg::SunsetCrank { limit: None }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Auction Not Ended Yet");
+
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap();
+    suite.app.update_block(|block| block.height = listing.block_limit + 1);
+    let res = suite.execute(BOB, ExecuteMsg::SunsetCrank { limit: None }, &[]).unwrap();
+    assert_eq!(attribute(&res, "sunset_crank"), "2");
+
+    assert_eq!(suite.nft_owner(&listed_token), SELLER);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(suite.auction.as_str()), 0);
+}
+
+#[test]
+fn listings_are_handed_over_to_a_new_seller() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    let transfer = |new_seller: Option<&str>| ExecuteMsg::TransferListing {
+        listing_id: listing_id.clone(),
+        new_seller: new_seller.map(String::from),
+    };
+    let accept = ExecuteMsg::AcceptListingTransfer { listing_id: listing_id.clone() };
+
+    // only the seller offers the listing, only the offered address takes it
+    suite.execute(BOB, transfer(Some(BOB)), &[]).unwrap_err();
+    suite.execute(SELLER, transfer(Some(BOB)), &[]).unwrap();
+    let pending: Option<Addr> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ListingTransfer { listing_id: listing_id.clone() }).unwrap();
+    assert_eq!(pending, Some(Addr::unchecked(BOB)));
+    suite.execute(ALICE, accept.clone(), &[]).unwrap_err();
+    suite.execute(BOB, accept.clone(), &[]).unwrap();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap();
+    assert_eq!(listing.seller, BOB);
+
+    // listings with bids stay with their seller
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    let err = suite.execute(BOB, transfer(Some(SELLER)), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("listing {} already has bids", listing_id));
+
+    // the new seller collects the proceeds
+    suite.execute(BOB, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE + 190);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE);
+}
+
+#[test]
+fn fresh_drops_resell_above_the_price_floor() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+
+    // only the collection owner sets a floor, bounded to the initial price
+    let collection = suite.nft.to_string();
+    let price_floor = |floor_rate| ExecuteMsg::SetCollectionPriceFloor {
+        collection: collection.clone(),
+        price_floor: Some(PriceFloor { floor_rate, window_blocks: 1_000 }),
+    };
+    suite.execute(ALICE, price_floor(Decimal::percent(80)), &[]).unwrap_err();
+    suite.execute(ARTIST, price_floor(Decimal::percent(101)), &[]).unwrap_err();
+    suite.execute(ARTIST, price_floor(Decimal::percent(80)), &[]).unwrap();
+
+    suite.execute(ALICE, ExecuteMsg::SaveListingTemplate {
+        name: String::from("cheap"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: Some(Uint128::new(50)),
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+    suite.app.execute_contract(Addr::unchecked(ALICE), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let resell = ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(10),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("cheap")),
+    };
+
+    // the resale can't undercut the floor inside the window, afterwards it can
+    let err = suite.execute(ALICE, resell.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "buy-now price can not be below 80 this soon after the mint");
+    suite.app.update_block(|block| block.height += 1_000);
+    suite.execute(ALICE, resell, &[]).unwrap();
+}
+
+#[test]
+fn features_follow_the_deployment() {
+    let mut suite = setup();
+    let features = |suite: &Suite| -> FeaturesResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::Features {}).unwrap()
+    };
+
+    let initial = features(&suite);
+    assert!(initial.cw20_bids && initial.offers);
+    assert!(!initial.candle_auctions && !initial.raffles && !initial.ibc_results);
+    assert!(!initial.dutch_auctions && !initial.rentals);
+    assert_eq!(initial.max_royalty_rate, Decimal::one());
+    assert_eq!(initial.max_batch_size, 30);
+    assert_eq!(initial.max_page_limit, 30);
+
+    // toggled by the configuration
+    suite.randomness_provider();
+    suite.execute(OWNER, ExecuteMsg::UpdateMaxRoyaltyRate { max_royalty_rate: Some(Decimal::percent(15)) }, &[]).unwrap();
+    suite.execute(OWNER, ExecuteMsg::BeginSunset {}, &[]).unwrap();
+    let updated = features(&suite);
+    assert!(updated.candle_auctions && updated.raffles);
+    assert!(!updated.offers);
+    assert_eq!(updated.max_royalty_rate, Decimal::percent(15));
+}
+
+#[test]
+fn outbid_bidders_are_notified_by_event() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+
+    let res = suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    assert!(!res.events.iter().any(|event| event.ty == "wasm-outbid"));
+
+    let res = suite.bid_native(BOB, &listing_id, 200).unwrap();
+    let event = res.events.iter().find(|event| event.ty == "wasm-outbid").unwrap();
+    let value = |key: &str| event.attributes.iter().find(|attribute| attribute.key == key).unwrap().value.clone();
+    assert_eq!(value("listing_id"), listing_id);
+    assert_eq!(value("bidder"), ALICE);
+    assert_eq!(value("refund"), native(150).to_string());
+    assert_eq!(value("new_bid"), native(200).to_string());
+    assert_eq!(value("new_bidder"), BOB);
+}
+
+#[test]
+fn auctions_below_reserve_are_relisted() {
+    let mut suite = setup();
+    let template = |auto_relists, reserve_price| ExecuteMsg::SaveListingTemplate {
+        name: String::from("relisted"),
+        template: ListingTemplate {
+            duration_blocks: Some(100),
+            min_increment: None,
+            reserve_price,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    };
+    // relists need a reserve price to miss
+    suite.execute(SELLER, template(Some(2), None), &[]).unwrap_err();
+    suite.execute(SELLER, template(Some(0), Some(Uint128::new(200))), &[]).unwrap_err();
+    suite.execute(SELLER, template(Some(2), Some(Uint128::new(200))), &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("relisted")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+    let relist = |suite: &Suite| -> Option<AutoRelist> {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::AutoRelist { listing_id: listing_id.clone() }).unwrap()
+    };
+    let resolve = |suite: &Suite| -> ResolveListingResponse {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() }).unwrap()
+    };
+    assert_eq!(relist(&suite).unwrap().remaining, 2);
+
+    // a bid below the reserve is refunded and the auction runs again from the minimum bid
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    let alice_balance = suite.native_balance(ALICE);
+    suite.app.update_block(|block| block.height += 101);
+    let res = suite.execute(BOB, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(attribute(&res, "relists_left"), "1");
+    assert_eq!(suite.native_balance(ALICE), alice_balance + 150);
+    assert_eq!(suite.nft_owner(&token_id), suite.auction.to_string());
+    let listing = resolve(&suite);
+    assert_eq!(listing.status, ListingStatus::Active);
+    assert_eq!(listing.blocks_remaining, 100);
+    assert_eq!(listing.max_bid, native(100));
+
+    suite.app.update_block(|block| block.height += 101);
+    suite.execute(BOB, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(relist(&suite), None);
+    assert_eq!(resolve(&suite).status, ListingStatus::Active);
+
+    // out of relists the nft goes back to the seller
+    suite.app.update_block(|block| block.height += 101);
+    let res = suite.execute(BOB, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    assert_eq!(attribute(&res, "listing_unsold"), listing_id);
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+}
+
+#[test]
+fn missed_reserves_are_revealed_after_close() {
+    let mut suite = setup();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("reserved"),
+        template: ListingTemplate {
+            duration_blocks: Some(100),
+            min_increment: None,
+            reserve_price: Some(Uint128::new(200)),
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: None,
+        },
+    }, &[]).unwrap();
+    let place = |suite: &mut Suite| {
+        let token_id = suite.mint(SELLER);
+        suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+            spender: suite.auction.to_string(),
+            token_id: token_id.clone(),
+            expires: None,
+        }, &[]).unwrap();
+        let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+            id: token_id,
+            minimum_bid: native(100),
+            nft_addr: suite.nft.to_string(),
+            escrowless: None,
+            template: Some(String::from("reserved")),
+        }, &[]).unwrap();
+        attribute(&res, "listing_id")
+    };
+    let revealed = |suite: &Suite, listing_id: &str| -> Option<RevealedReserve> {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::RevealedReserve { listing_id: listing_id.to_string() }).unwrap()
+    };
+
+    // confidential unless the owner reveals them
+    let confidential = place(&mut suite);
+    suite.execute(SELLER, ExecuteMsg::UpdateReserveReveal { revealed: true }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateReserveReveal { revealed: true }, &[]).unwrap();
+    let listing_id = place(&mut suite);
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    assert_eq!(revealed(&suite, &listing_id), None);
+
+    suite.app.update_block(|block| block.height += 101);
+    suite.execute(OWNER, ExecuteMsg::UpdateReserveReveal { revealed: false }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: confidential.clone() }, &[]).unwrap();
+    assert_eq!(revealed(&suite, &confidential), None);
+
+    suite.execute(OWNER, ExecuteMsg::UpdateReserveReveal { revealed: true }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: listing_id.clone() }, &[]).unwrap();
+    let height = suite.app.block_info().height;
+    assert_eq!(revealed(&suite, &listing_id), Some(RevealedReserve {
+        reserve: native(200),
+        max_bid: Some(Uint128::new(150)),
+        closed_height: height,
+    }));
+}
+
+#[test]
+fn idle_funds_earn_yield_until_needed() {
+    let mut suite = setup();
+    let code = suite.app.store_code(yield_vault_contract());
+    let vault = suite.app.instantiate_contract(code, Addr::unchecked(OWNER), &Empty {}, &[], "vault", None).unwrap();
+    let yield_vault = Some(YieldVault { contract: vault.to_string(), denom: String::from(DENOM), buffer: Uint128::new(50) });
+    suite.execute(ALICE, ExecuteMsg::UpdateYieldVault { yield_vault: yield_vault.clone() }, &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateYieldVault { yield_vault }, &[]).unwrap();
+    let ledger = |suite: &Suite| -> LedgerResponse {
+        suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::Ledger { asset_info: native(0).info })
+            .unwrap()
+    };
+
+    // only the funds above the buffer are deposited, and still back the ledger
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(BOB, ExecuteMsg::SweepYield {}, &[]).unwrap();
+    let err = suite.execute(BOB, ExecuteMsg::SweepYield {}, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "no funds above the yield buffer to deposit");
+    assert_eq!(suite.native_balance(vault.as_str()), 100);
+    let deposited = ledger(&suite);
+    assert_eq!(deposited.balance, Uint128::new(150));
+    assert_eq!(deposited.yield_deposits, Uint128::new(100));
+
+    // the yield goes to the platform fees
+    suite.app.send_tokens(Addr::unchecked(ALICE), vault.clone(), &coins(30, DENOM)).unwrap();
+    suite.execute(BOB, ExecuteMsg::HarvestYield {}, &[]).unwrap();
+    suite.execute(BOB, ExecuteMsg::HarvestYield {}, &[]).unwrap_err();
+    assert_eq!(ledger(&suite).fees, Uint128::new(30));
+
+    // a settlement the funds on hand can't pay withdraws the deposits first
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    suite.execute(BOB, ExecuteMsg::SweepYield {}, &[]).unwrap();
+    assert_eq!(ledger(&suite).yield_deposits, Uint128::new(180));
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10);
+    let settled = ledger(&suite);
+    assert_eq!(settled.yield_deposits, Uint128::zero());
+    assert_eq!(settled.balance, Uint128::new(30));
+
+    // unsetting the vault pulls everything out of it
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(BOB, ExecuteMsg::SweepYield {}, &[]).unwrap();
+    assert_eq!(ledger(&suite).yield_deposits, Uint128::new(130));
+    suite.execute(OWNER, ExecuteMsg::UpdateYieldVault { yield_vault: None }, &[]).unwrap();
+    assert_eq!(suite.native_balance(vault.as_str()), 0);
+    assert_eq!(suite.native_balance(suite.auction.as_str()), 180);
+    assert_eq!(ledger(&suite).yield_deposits, Uint128::zero());
+}
+
+#[test]
+fn v2_messages_run_next_to_v1() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::V2(V2ExecuteMsg::PlaceListing {
+        token_id: token_id.clone(),
+        nft_addr: suite.nft.to_string(),
+        minimum_bid: native(100),
+        escrowless: false,
+        template: None,
+    }), &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+
+    // v1 bids on a v2 listing and the other way around
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.execute(BOB, ExecuteMsg::V2(V2ExecuteMsg::Bid {
+        listing_id: listing_id.clone(),
+        price: native(200),
+        on_behalf_of: None,
+    }), &coins(200, DENOM)).unwrap();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::V2(V2QueryMsg::Listing { listing_id: listing_id.clone() }))
+        .unwrap();
+    assert_eq!(listing.max_bidder, Addr::unchecked(BOB));
+
+    suite.execute(SELLER, ExecuteMsg::V2(V2ExecuteMsg::Settle { listing_id }), &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+}
+
+#[test]
+fn collections_number_their_tokens() {
+    let mut suite = setup();
+    let collection = suite.nft.to_string();
+    let set_format = |format: &str| ExecuteMsg::SetCollectionTokenIdFormat {
+        collection: collection.clone(),
+        format: Some(format.to_string()),
+    };
+
+    // only the collection owner picks the format, which can't render the default ids
+    let err = suite.execute(ALICE, set_format("{symbol}-{serial:05}"), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    let err = suite.execute(ARTIST, set_format("{symbol}.{serial}"), &[]).unwrap_err();
+    assert!(err.root_cause().to_string().starts_with("token id formats take"));
+    suite.execute(ARTIST, set_format("{symbol}"), &[]).unwrap_err();
+
+    let default_id = suite.mint(ALICE);
+    assert!(default_id.starts_with("GF."));
+    suite.execute(ARTIST, set_format("{symbol}-{serial:05}"), &[]).unwrap();
+    assert_eq!(suite.mint(ALICE), "GF-00001");
+    assert_eq!(suite.mint(BOB), "GF-00002");
+    assert_eq!(suite.nft_owner("GF-00002"), BOB);
+
+    // the serial keeps counting under a new format
+    suite.execute(ARTIST, set_format("vault/{serial}"), &[]).unwrap();
+    assert_eq!(suite.mint(ALICE), "vault/3");
+    let format: TokenIdFormatResponse = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::CollectionTokenIdFormat {
+        collection: collection.clone(),
+    }).unwrap();
+    assert_eq!(format, TokenIdFormatResponse { format: Some(String::from("vault/{serial}")), last_serial: 3 });
+
+    suite.execute(ARTIST, ExecuteMsg::SetCollectionTokenIdFormat { collection, format: None }, &[]).unwrap();
+    let next_id = suite.mint(ALICE);
+    assert!(next_id.starts_with("GF.") && next_id != default_id);
+}
+
+#[test]
+fn provenance_is_committed_before_the_first_mint() {
+    let mut suite = setup();
+    let collection = suite.nft.to_string();
+    let hash = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+    let commit = |provenance_hash: &str| ExecuteMsg::CommitCollectionProvenance {
+        collection: collection.clone(),
+        provenance_hash: provenance_hash.to_string(),
+    };
+
+    let err = suite.execute(ALICE, commit(hash), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    let err = suite.execute(ARTIST, commit(&hash[..62]), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "provenance hashes are hex encoded sha256 digests");
+    suite.execute(ARTIST, commit(hash), &[]).unwrap();
+
+    // the commitment never changes, also before the first mint
+    let err = suite.execute(ARTIST, commit(&"0".repeat(64)), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "provenance hashes are committed once, before the collection's first token");
+    suite.mint(ALICE);
+    let commitment: Option<ProvenanceCommitment> = suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::CollectionProvenance {
+        collection,
+    }).unwrap();
+    assert_eq!(commitment, Some(ProvenanceCommitment {
+        provenance_hash: hash.to_ascii_lowercase(),
+        committed_by: Addr::unchecked(ARTIST),
+        committed_height: suite.app.block_info().height,
+    }));
+}
+
+#[test]
+fn provenance_cant_be_committed_after_a_mint() {
+    let mut suite = setup();
+    suite.mint(ALICE);
+    let err = suite.execute(ARTIST, ExecuteMsg::CommitCollectionProvenance {
+        collection: suite.nft.to_string(),
+        provenance_hash: "0".repeat(64),
+    }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "provenance hashes are committed once, before the collection's first token");
+}
+
+#[test]
+fn buyers_pay_the_premium_on_top_of_the_hammer_price() {
+    let mut suite = setup();
+    let earlier_token = suite.mint(SELLER);
+    let earlier_listing = suite.place_listing(SELLER, &earlier_token, native(100));
+
+    suite.execute(ALICE, ExecuteMsg::UpdateBuyerPremium { rate: Some(Decimal::percent(10)) }, &[]).unwrap_err();
+    let err = suite.execute(OWNER, ExecuteMsg::UpdateBuyerPremium { rate: Some(Decimal::percent(26)) }, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "buyer premiums can be at most 25%");
+    suite.execute(OWNER, ExecuteMsg::UpdateBuyerPremium { rate: Some(Decimal::percent(10)) }, &[]).unwrap();
+
+    // listings keep the premium they were placed with
+    suite.bid_native(ALICE, &earlier_listing, 150).unwrap();
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 150);
+
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    let bid = |amount| ExecuteMsg::BidListing { listing_id: listing_id.clone(), bid_price: native(amount), on_behalf_of: None };
+    suite.bid_native(BOB, &listing_id, 150).unwrap_err();
+    suite.execute(BOB, bid(150), &coins(165, DENOM)).unwrap();
+    // outbid bidders get the premium back with their bid
+    suite.execute(ALICE, bid(200), &coins(220, DENOM)).unwrap();
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE - 150 - 220);
+
+    let preview: SettlementPreviewResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::SettlementPreview { listing_id: listing_id.clone() })
+        .unwrap();
+    assert_eq!(preview.buyer_premium, Some(PayoutPreview { recipient: Addr::unchecked(FEE_COLLECTOR), asset: native(20) }));
+    assert_eq!(preview.seller_proceeds.asset, native(190));
+
+    // the premium goes to the fee collector next to the 5% platform fee
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), ALICE);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10 + 20);
+    assert_eq!(suite.native_balance(SELLER), INITIAL_BALANCE + 190);
+
+    // cw20 bids send the premium within the sent amount
+    let minimum_bid = suite.cw20_asset(100);
+    let token_id = suite.mint_priced_in(SELLER, minimum_bid.info.clone());
+    let listing_id = suite.place_listing(SELLER, &token_id, minimum_bid);
+    suite.bid_cw20(BOB, &listing_id, 220).unwrap();
+    let err = suite.bid_cw20(ALICE, &listing_id, 230).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "payments have to carry the buyer premium of 0.1 of the price on top of it");
+    suite.bid_cw20(ALICE, &listing_id, 231).unwrap();
+    assert_eq!(suite.cw20_balance(BOB), INITIAL_BALANCE);
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.cw20_balance(FEE_COLLECTOR), 10 + 21);
+    assert_eq!(suite.cw20_balance(SELLER), 200);
+}
+
+#[test]
+fn increment_schedules_raise_bids_by_the_step_of_the_current_bid() {
+    let mut suite = setup();
+    let step = |from: u128, increment: u128| IncrementStep { from: Uint128::new(from), increment: Uint128::new(increment) };
+    let house = vec![step(0, 5), step(100, 10), step(500, 50)];
+    let schedule = |steps| ExecuteMsg::UpdateIncrementSchedule { name: String::from("house"), steps: Some(steps) };
+
+    suite.execute(SELLER, schedule(house.clone()), &[]).unwrap_err();
+    let err = suite.execute(OWNER, schedule(vec![step(10, 5)]), &[]).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "increment schedules take 1 to 20 steps starting from zero, ascending and with nonzero increments",
+    );
+    suite.execute(OWNER, schedule(vec![step(0, 5), step(0, 10)]), &[]).unwrap_err();
+    suite.execute(OWNER, schedule(house.clone()), &[]).unwrap();
+
+    let template = |increment_schedule: &str| ExecuteMsg::SaveListingTemplate {
+        name: String::from("stepped"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: None,
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: Some(increment_schedule.to_string()),
+            start_delay_blocks: None,
+        },
+    };
+    let err = suite.execute(SELLER, template("unknown"), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "no increment schedule unknown");
+    suite.execute(SELLER, template("house"), &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id,
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("stepped")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+    let min_next_bid = |suite: &Suite| -> Option<Uint128> {
+        let listing: ResolveListingResponse = suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: listing_id.clone() })
+            .unwrap();
+        listing.min_next_bid
+    };
+
+    // the first bid only has to meet the minimum
+    suite.bid_native(ALICE, &listing_id, 100).unwrap();
+    assert_eq!(min_next_bid(&suite), Some(Uint128::new(110)));
+    let err = suite.bid_native(BOB, &listing_id, 109).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "bid must be at least 110");
+    suite.bid_native(BOB, &listing_id, 450).unwrap();
+
+    // running listings keep the steps they were placed with
+    suite.execute(OWNER, schedule(vec![step(0, 1)]), &[]).unwrap();
+    assert_eq!(min_next_bid(&suite), Some(Uint128::new(460)));
+    suite.bid_native(ALICE, &listing_id, 459).unwrap_err();
+    suite.bid_native(ALICE, &listing_id, 500).unwrap();
+    assert_eq!(min_next_bid(&suite), Some(Uint128::new(550)));
+
+    let steps: Option<Vec<IncrementStep>> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::IncrementSchedule { name: String::from("house") })
+        .unwrap();
+    assert_eq!(steps, Some(vec![step(0, 1)]));
+}
+
+#[test]
+fn absentee_bids_open_the_auction_one_increment_above_the_runner_up() {
+    let mut suite = setup();
+    suite.execute(SELLER, ExecuteMsg::SaveListingTemplate {
+        name: String::from("delayed"),
+        template: ListingTemplate {
+            duration_blocks: None,
+            min_increment: Some(Decimal::percent(10)),
+            reserve_price: None,
+            payout_splits: vec![],
+            cancel_penalty: None,
+            buy_now_price: None,
+            buy_now_lock: None,
+            candle_window_blocks: None,
+            charity: None,
+            auto_relists: None,
+            increment_schedule: None,
+            start_delay_blocks: Some(100),
+        },
+    }, &[]).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let res = suite.execute(SELLER, ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid: native(100),
+        nft_addr: suite.nft.to_string(),
+        escrowless: None,
+        template: Some(String::from("delayed")),
+    }, &[]).unwrap();
+    let listing_id = attribute(&res, "listing_id");
+    let start_height = suite.app.block_info().height + 100;
+
+    // bids before the start are lodged, a bidder lodging again gets its former bid back
+    suite.bid_native(ALICE, &listing_id, 99).unwrap_err();
+    suite.bid_native(BOB, &listing_id, 200).unwrap();
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+    suite.bid_native(BOB, &listing_id, 300).unwrap();
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - 300);
+    let bids: Vec<AbsenteeBid> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::AbsenteeBids { listing_id: listing_id.clone() })
+        .unwrap();
+    let lodged: Vec<(&str, u128)> = bids.iter().map(|bid| (bid.bidder.as_str(), bid.bid.amount.u128())).collect();
+    assert_eq!(lodged, vec![(ALICE, 150), (BOB, 300)]);
+
+    let open = ExecuteMsg::OpenAuction { listing_id: listing_id.clone() };
+    let err = suite.execute(SELLER, open.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("the auction opens at height {}", start_height));
+    let err = suite.execute(ALICE, ExecuteMsg::BuyNow { listing_id: listing_id.clone(), price: native(1_000) }, &coins(1_000, DENOM)).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), format!("the auction opens at height {}", start_height));
+
+    // the highest bid opens one increment above the runner-up and gets the rest back
+    suite.app.update_block(|block| block.height = start_height);
+    let res = suite.execute(SELLER, open.clone(), &[]).unwrap();
+    assert_eq!(attribute(&res, "opening_bid"), format!("165{}", DENOM));
+    assert_eq!(suite.native_balance(ALICE), INITIAL_BALANCE);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - 165);
+    let err = suite.execute(SELLER, open, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "no absentee bids to open the auction with");
+
+    let err = suite.bid_native(ALICE, &listing_id, 180).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "bid must be at least 181");
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+}
+
+#[test]
+fn listing_operators_bid_on_behalf_of_their_clients() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    let client = "client";
+    let operator = |operator: Option<&str>| ExecuteMsg::SetListingOperator {
+        listing_id: listing_id.clone(),
+        operator: operator.map(String::from),
+    };
+    let phone_bid = |amount: u128| ExecuteMsg::BidListing {
+        listing_id: listing_id.clone(),
+        bid_price: native(amount),
+        on_behalf_of: Some(String::from(client)),
+    };
+
+    suite.execute(BOB, phone_bid(150), &coins(150, DENOM)).unwrap_err();
+    suite.execute(ALICE, operator(Some(BOB)), &[]).unwrap_err();
+    suite.execute(SELLER, operator(Some(BOB)), &[]).unwrap();
+    let stored: Option<Addr> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ListingOperator { listing_id: listing_id.clone() })
+        .unwrap();
+    assert_eq!(stored, Some(Addr::unchecked(BOB)));
+
+    // the client is the bidder of record, its refund skips the operator
+    let res = suite.execute(BOB, phone_bid(150), &coins(150, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "operator"), BOB);
+    assert_eq!(attribute(&res, "beneficiary"), client);
+    suite.bid_native(ALICE, &listing_id, 200).unwrap();
+    assert_eq!(suite.native_balance(client), 150);
+    assert_eq!(suite.native_balance(BOB), INITIAL_BALANCE - 150);
+
+    suite.execute(SELLER, operator(None), &[]).unwrap();
+    let err = suite.execute(BOB, phone_bid(250), &coins(250, DENOM)).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+    suite.execute(SELLER, operator(Some(BOB)), &[]).unwrap();
+    suite.execute(BOB, phone_bid(250), &coins(250, DENOM)).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), client);
+}
+
+#[test]
+fn offers_at_the_token_auto_accept_price_settle_right_away() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let nft_addr = suite.nft.to_string();
+    let set_price = ExecuteMsg::SetAutoAccept {
+        nft_addr: nft_addr.clone(),
+        token_id: Some(token_id.clone()),
+        price: Some(native(200)),
+    };
+    let offer = |amount: u128| ExecuteMsg::MakeOffer {
+        nft_addr: nft_addr.clone(),
+        token_id: token_id.clone(),
+        price: native(amount),
+    };
+    let auto_accept_price = |suite: &Suite| -> Option<Asset> {
+        suite.app.wrap().query_wasm_smart(&suite.auction, &QueryMsg::AutoAcceptPrice {
+            nft_addr: suite.nft.to_string(),
+            token_id: token_id.clone(),
+        }).unwrap()
+    };
+
+    // only the owner sets the price of a token
+    let err = suite.execute(ALICE, set_price.clone(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "sender is neither the owner nor an approved operator of the token");
+    suite.execute(SELLER, set_price, &[]).unwrap();
+    assert_eq!(auto_accept_price(&suite), Some(native(200)));
+
+    // offers below the price wait for the owner
+    let res = suite.execute(ALICE, offer(150), &coins(150, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "make_offer"), "1");
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+
+    let seller_before = suite.native_balance(SELLER);
+    let res = suite.execute(BOB, offer(200), &coins(200, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "auto_accepted"), "true");
+    assert_eq!(attribute(&res, "accept_offer"), "2");
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+    assert_eq!(suite.native_balance(SELLER), seller_before + 190);
+    assert_eq!(suite.native_balance(FEE_COLLECTOR), 10);
+
+    // the price lapses with the sale, the new owner's offers wait again
+    assert_eq!(auto_accept_price(&suite), None);
+    suite.execute(ALICE, offer(1_000), &coins(1_000, DENOM)).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+}
+
+#[test]
+fn collection_auto_accept_prices_cover_the_owners_approved_tokens() {
+    let mut suite = setup();
+    let priced = suite.mint(SELLER);
+    let covered = suite.mint(SELLER);
+    let unapproved = suite.mint(ALICE);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::ApproveAll {
+        operator: suite.auction.to_string(),
+        expires: None,
+    }, &[]).unwrap();
+    let nft_addr = suite.nft.to_string();
+    let set_price = |token_id: Option<&str>, price: Option<Asset>| ExecuteMsg::SetAutoAccept {
+        nft_addr: nft_addr.clone(),
+        token_id: token_id.map(String::from),
+        price,
+    };
+    let offer = |token_id: &str, amount: u128| ExecuteMsg::MakeOffer {
+        nft_addr: nft_addr.clone(),
+        token_id: token_id.to_string(),
+        price: native(amount),
+    };
+
+    suite.execute(SELLER, set_price(None, Some(native(300))), &[]).unwrap();
+    suite.execute(ALICE, set_price(None, Some(native(300))), &[]).unwrap();
+    // a token price takes precedence over the collection price
+    suite.execute(SELLER, set_price(Some(&priced), Some(native(500))), &[]).unwrap();
+
+    suite.execute(BOB, offer(&priced, 300), &coins(300, DENOM)).unwrap();
+    assert_eq!(suite.nft_owner(&priced), SELLER);
+    let res = suite.execute(BOB, offer(&covered, 300), &coins(300, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "auto_accepted"), "true");
+    assert_eq!(suite.nft_owner(&covered), BOB);
+
+    // tokens this contract can't move wait for their owner
+    let res = suite.execute(BOB, offer(&unapproved, 300), &coins(300, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "make_offer"), "3");
+    assert_eq!(suite.nft_owner(&unapproved), ALICE);
+
+    // unsetting the collection price stops further settlements
+    let token_id = suite.mint(SELLER);
+    suite.execute(SELLER, set_price(None, None), &[]).unwrap();
+    suite.execute(BOB, offer(&token_id, 300), &coins(300, DENOM)).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), SELLER);
+}
+
+#[test]
+fn auto_accepted_offers_keep_the_ledger_balanced() {
+    let mut suite = setup();
+    let listed_token = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &listed_token, native(100));
+    suite.bid_native(ALICE, &listing_id, 150).unwrap();
+
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::SetAutoAccept {
+        nft_addr: suite.nft.to_string(),
+        token_id: Some(token_id.clone()),
+        price: Some(native(50)),
+    }, &[]).unwrap();
+
+    // the offer is held before it settles, the bid on the listing stays accounted for
+    let res = suite.execute(BOB, ExecuteMsg::MakeOffer {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+        price: native(60),
+    }, &coins(60, DENOM)).unwrap();
+    assert_eq!(attribute(&res, "auto_accepted"), "true");
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+    let ledger: LedgerResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::Ledger { asset_info: native(0).info })
+        .unwrap();
+    assert_eq!(ledger.bids, Uint128::new(150));
+}
+
+#[test]
+fn deductions_past_the_price_are_cut_down_to_settle() {
+    let mut suite = setup();
+    let res = suite.execute(ARTIST, ExecuteMsg::Mint(GFMintMsg {
+        owner: String::from(SELLER),
+        name: String::from("greedy nft"),
+        image_uri: Some(String::from("https://glassflow.art/1.png")),
+        external_link: None,
+        description: None,
+        num_real_repr: Uint128::zero(),
+        num_nfts: Uint128::new(1),
+        royalties: vec![Royalty { address: String::from(ARTIST), royalty_rate: Decimal::percent(98) }],
+        init_price: Uint128::new(100),
+        init_price_asset: Some(AssetInfo::NativeToken { denom: String::from(DENOM) }),
+        nft_addr: suite.nft.to_string(),
+        transferable: true,
+        localized_names: vec![],
+        localized_descriptions: vec![],
+        custody: false,
+    }), &[]).unwrap();
+    let token_id = attribute(&res, "token_id");
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    suite.bid_native(ALICE, &listing_id, 100).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+
+    // the 5% fee and the 98% royalty of the resale exceed the price, the royalty takes the rest
+    let listing_id = suite.place_listing(ALICE, &token_id, native(1_000));
+    suite.bid_native(BOB, &listing_id, 1_000).unwrap();
+    let preview: SettlementPreviewResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::SettlementPreview { listing_id: listing_id.clone() })
+        .unwrap();
+    assert_eq!(preview.royalties[0].asset.amount, Uint128::new(950));
+    assert_eq!(preview.seller_proceeds.asset.amount, Uint128::zero());
+    suite.execute(ALICE, ExecuteMsg::WithdrawListing { listing_id }, &[]).unwrap();
+    assert_eq!(suite.nft_owner(&token_id), BOB);
+    assert_eq!(suite.native_balance(ARTIST), 950);
+}
+
+#[test]
+fn listings_are_told_apart_by_collection() {
+    let mut suite = setup();
+    let nft_code = suite.app.store_code(nft_contract());
+    let other_nft = suite.app
+        .instantiate_contract(
+            nft_code,
+            Addr::unchecked(ARTIST),
+            &Cw721InstantiateMsg {
+                name: String::from("glassflow editions"),
+                symbol: String::from("GFE"),
+                minter: suite.auction.to_string(),
+            },
+            &[],
+            "other nft",
+            None,
+        )
+        .unwrap();
+    for collection in [suite.nft.to_string(), other_nft.to_string()] {
+        suite.execute(ARTIST, ExecuteMsg::SetCollectionTokenIdFormat {
+            collection,
+            format: Some(String::from("edition-{serial}")),
+        }, &[]).unwrap();
+    }
+    let token_id = suite.mint(SELLER);
+    suite.place_listing(SELLER, &token_id, native(100));
+
+    // the same token id in another collection is not on auction
+    let other_nft_addr = std::mem::replace(&mut suite.nft, other_nft);
+    let other_token_id = suite.mint(SELLER);
+    assert_eq!(other_token_id, token_id);
+    let info: NftInfo<Metadata> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::QueryNftInfo { token_id: other_token_id.clone(), nft_addr: suite.nft.to_string() })
+        .unwrap();
+    assert!(!info.is_listing);
+    suite.place_listing(SELLER, &other_token_id, native(100));
+
+    let info: NftInfo<Metadata> = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::QueryNftInfo { token_id, nft_addr: other_nft_addr.to_string() })
+        .unwrap();
+    assert_eq!(info.listing_price, Some(native(100)));
+}
+
+#[test]
+fn watchers_per_target_are_capped() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let listing_id = suite.place_listing(SELLER, &token_id, native(100));
+    let target = WatchTarget::Listing { listing_id: listing_id.clone() };
+    let watch = || ExecuteMsg::Watch { target: target.clone() };
+    for watcher in 0..50 {
+        suite.execute(&format!("watcher{}", watcher), watch(), &[]).unwrap();
+    }
+
+    // watching again keeps the slot, a new watcher waits for one to be freed
+    suite.execute("watcher0", watch(), &[]).unwrap();
+    let err = suite.execute(ALICE, watch(), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "at most 50 watchers per listing or collection");
+    suite.execute("watcher0", ExecuteMsg::Unwatch { target: target.clone() }, &[]).unwrap();
+    suite.execute(ALICE, watch(), &[]).unwrap();
+
+    let res = suite.bid_native(BOB, &listing_id, 100).unwrap();
+    let event = res.events.iter().find(|event| event.ty == "wasm-watch").unwrap();
+    let watchers = event.attributes.iter().find(|attr| attr.key == "watchers").unwrap();
+    assert_eq!(watchers.value.split(',').count(), 50);
+}
+
+#[test]
+fn a_broken_yield_vault_can_still_be_unset() {
+    let mut suite = setup();
+    let code = suite.app.store_code(broken_yield_vault_contract());
+    let vault = suite.app.instantiate_contract(code, Addr::unchecked(OWNER), &Empty {}, &[], "vault", None).unwrap();
+    suite.execute(OWNER, ExecuteMsg::UpdateYieldVault {
+        yield_vault: Some(YieldVault { contract: vault.to_string(), denom: String::from(DENOM), buffer: Uint128::zero() }),
+    }, &[]).unwrap();
+    suite.app.send_tokens(Addr::unchecked(ALICE), suite.auction.clone(), &coins(200, DENOM)).unwrap();
+    suite.execute(BOB, ExecuteMsg::SweepYield {}, &[]).unwrap();
+
+    // the deposits the vault keeps are written off
+    let res = suite.execute(OWNER, ExecuteMsg::UpdateYieldVault { yield_vault: None }, &[]).unwrap();
+    assert_eq!(attribute(&res, "yield_written_off"), "200");
+    let ledger: LedgerResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::Ledger { asset_info: native(0).info })
+        .unwrap();
+    assert_eq!(ledger.yield_deposits, Uint128::zero());
+    assert_eq!(suite.native_balance(vault.as_str()), 200);
+}
+
+#[test]
+fn first_listings_in_another_asset_need_the_minters_approval() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    suite.app.execute_contract(Addr::unchecked(SELLER), suite.nft.clone(), &Cw721ExecuteMsg::<Metadata>::Approve {
+        spender: suite.auction.to_string(),
+        token_id: token_id.clone(),
+        expires: None,
+    }, &[]).unwrap();
+    let nft_addr = suite.nft.to_string();
+    let list = |minimum_bid: Asset| ExecuteMsg::PlaceListing {
+        id: token_id.clone(),
+        minimum_bid,
+        nft_addr: nft_addr.clone(),
+        escrowless: None,
+        template: None,
+    };
+
+    // the initial price of 100 ujuno says nothing about amounts of the cw20
+    let err = suite.execute(SELLER, list(suite.cw20_asset(1_000_000)), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "listing in another asset than the initial price requires the creator's approval");
+    let err = suite.execute(SELLER, list(native(50)), &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "listing below the initial price of 100 requires the creator's approval");
+
+    // only the minter approves, and the approval holds for its asset only
+    let approve = ExecuteMsg::ApproveDiscountListing {
+        nft_addr: suite.nft.to_string(),
+        token_id: token_id.clone(),
+        min_price: suite.cw20_asset(500),
+    };
+    suite.execute(SELLER, approve.clone(), &[]).unwrap_err();
+    suite.execute(ARTIST, approve, &[]).unwrap();
+    suite.execute(SELLER, list(native(50)), &[]).unwrap_err();
+    suite.execute(SELLER, list(suite.cw20_asset(400)), &[]).unwrap_err();
+    let res = suite.execute(SELLER, list(suite.cw20_asset(500)), &[]).unwrap();
+    let listing: ResolveListingResponse = suite.app.wrap()
+        .query_wasm_smart(&suite.auction, &QueryMsg::ResolveListing { id: attribute(&res, "listing_id") }).unwrap();
+    assert_eq!(listing.max_bid, suite.cw20_asset(500));
+}
+
+#[test]
+fn floor_prices_are_kept_per_listed_asset() {
+    let mut suite = setup();
+    let token_id = suite.mint(SELLER);
+    let cheapest = suite.place_listing(SELLER, &token_id, native(100));
+    let token_id = suite.mint(SELLER);
+    let pricier = suite.place_listing(SELLER, &token_id, native(300));
+    let token_id = suite.mint_priced_in(SELLER, suite.cw20_asset(0).info);
+    let in_cw20 = suite.place_listing(SELLER, &token_id, suite.cw20_asset(400));
+    let floors = |suite: &Suite| -> Vec<FloorPrice> {
+        let res: FloorPriceResponse = suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::FloorPrice { collection: suite.nft.to_string() }).unwrap();
+        res.floors
+    };
+
+    assert_eq!(floors(&suite), vec![
+        FloorPrice { listing_id: in_cw20.clone(), price: suite.cw20_asset(400) },
+        FloorPrice { listing_id: cheapest.clone(), price: native(100) },
+    ]);
+
+    // the next cheapest listing of the asset takes over, assets without listings drop out
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: cheapest }, &[]).unwrap();
+    suite.execute(SELLER, ExecuteMsg::WithdrawListing { listing_id: in_cw20 }, &[]).unwrap();
+    assert_eq!(floors(&suite), vec![FloorPrice { listing_id: pricier, price: native(300) }]);
+}
+
+#[test]
+fn curators_feature_listings_until_a_height() {
+    let mut suite = setup();
+    let first = suite.mint(SELLER);
+    let second = suite.mint(SELLER);
+    let first_listing = suite.place_listing(SELLER, &first, native(100));
+    let second_listing = suite.place_listing(SELLER, &second, native(100));
+    let height = suite.app.block_info().height;
+
+    let feature = |listing_id: &str, until: u64| ExecuteMsg::FeatureListing { listing_id: listing_id.to_string(), until };
+    suite.execute(ALICE, feature(&first_listing, height + 10), &[]).unwrap_err();
+    suite.execute(OWNER, ExecuteMsg::UpdateListingCurator { curator: Some(String::from(ALICE)) }, &[]).unwrap();
+    suite.execute(ALICE, feature(&first_listing, height + 10), &[]).unwrap();
+    suite.execute(ALICE, feature(&second_listing, height + 100), &[]).unwrap();
+
+    let featured = |suite: &Suite| -> Vec<String> {
+        let res: FeaturedListingsResponse = suite.app.wrap()
+            .query_wasm_smart(&suite.auction, &QueryMsg::FeaturedListings { start_after: None, limit: None }).unwrap();
+        res.listings.into_iter().map(|listing| listing.listing_id).collect()
+    };
+    assert_eq!(featured(&suite), vec![first_listing.clone(), second_listing.clone()]);
+
+    // features lapse after their height and end with the listing
+    suite.app.update_block(|block| block.height += 11);
+    assert_eq!(featured(&suite), vec![second_listing.clone()]);
+    suite.execute(ALICE, ExecuteMsg::UnfeatureListing { listing_id: second_listing }, &[]).unwrap();
+    assert!(featured(&suite).is_empty());
+}